@@ -506,3 +506,51 @@ fn copy_4m_baseline(b: &mut test::Bencher) {
         fs::copy(&file, &copy).unwrap();
     });
 }
+
+#[bench]
+fn batch_create_root_relative(b: &mut test::Bencher) {
+    use cap_tempfile::ambient_authority;
+
+    let dir = cap_tempfile::tempdir(ambient_authority()).unwrap();
+
+    let mut path = PathBuf::new();
+    for _ in 0..16 {
+        path.push("abc");
+    }
+    dir.create_dir_all(&path).unwrap();
+    let names = (0..64)
+        .map(|i| path.join(format!("file{}", i)))
+        .collect::<Vec<_>>();
+
+    b.iter(|| {
+        for name in &names {
+            let _file = dir.create(name).unwrap();
+        }
+    });
+}
+
+#[bench]
+fn batch_create_scoped(b: &mut test::Bencher) {
+    use cap_tempfile::ambient_authority;
+
+    let dir = cap_tempfile::tempdir(ambient_authority()).unwrap();
+
+    let mut path = PathBuf::new();
+    for _ in 0..16 {
+        path.push("abc");
+    }
+    dir.create_dir_all(&path).unwrap();
+    let names = (0..64)
+        .map(|i| PathBuf::from(format!("file{}", i)))
+        .collect::<Vec<_>>();
+
+    b.iter(|| {
+        dir.batch(&path, |scoped| {
+            for name in &names {
+                let _file = scoped.create(name)?;
+            }
+            Ok(())
+        })
+        .unwrap();
+    });
+}
@@ -72,6 +72,48 @@ impl Dir {
         self.std_file
     }
 
+    /// Constructs a new instance of `Self` from a synchronous
+    /// [`cap_std::fs::Dir`].
+    ///
+    /// This takes ownership of `dir`'s handle rather than reopening the
+    /// directory, so `Self` has access to exactly the same paths that `dir`
+    /// had.
+    ///
+    /// This requires the `sync` feature.
+    #[cfg(feature = "sync")]
+    #[inline]
+    pub fn from_sync(dir: cap_std::fs::Dir) -> Self {
+        #[cfg(not(windows))]
+        {
+            Self::from_fd(dir.into_fd())
+        }
+
+        #[cfg(windows)]
+        {
+            Self::from_handle(dir.into_handle())
+        }
+    }
+
+    /// Consumes `self` and returns a synchronous [`cap_std::fs::Dir`].
+    ///
+    /// Like [`Dir::from_sync`], this transfers ownership of the handle
+    /// rather than reopening the directory.
+    ///
+    /// This requires the `sync` feature.
+    #[cfg(feature = "sync")]
+    #[inline]
+    pub fn into_sync(self) -> cap_std::fs::Dir {
+        #[cfg(not(windows))]
+        {
+            cap_std::fs::Dir::from_fd(self.into_fd())
+        }
+
+        #[cfg(windows)]
+        {
+            cap_std::fs::Dir::from_handle(self.into_handle())
+        }
+    }
+
     /// Attempts to open a file in read-only mode.
     ///
     /// This corresponds to [`async_std::fs::File::open`], but only accesses paths
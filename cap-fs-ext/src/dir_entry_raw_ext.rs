@@ -0,0 +1,47 @@
+use cap_primitives::fs::{_DirEntryRawExt, FileType};
+use std::io;
+
+/// Extension trait for `DirEntry` which exposes the raw values the directory
+/// stream reported for an entry.
+pub trait DirEntryRawExt {
+    /// Return the inode number of the entry.
+    ///
+    /// On Posix-ish platforms this is the `d_ino` value from the directory
+    /// stream. On Windows this is the file index, which requires opening the
+    /// file.
+    fn ino(&self) -> io::Result<u64>;
+
+    /// Return the raw type value the directory stream reported for the entry.
+    ///
+    /// On Posix-ish platforms this is the `d_type` value, one of the `DT_*`
+    /// constants, which may be `DT_UNKNOWN` on filesystems that don't record
+    /// it. On Windows this is the `FILE_ATTRIBUTE_*` bits from the find data,
+    /// including `FILE_ATTRIBUTE_REPARSE_POINT`.
+    fn d_type_raw(&self) -> io::Result<u32>;
+
+    /// Return the file type of the entry, if the directory stream provided
+    /// one, without performing any additional system calls.
+    ///
+    /// This returns `None` where the type isn't known without a `stat`, in
+    /// which case `file_type` can be used to obtain it.
+    fn file_type_cached(&self) -> Option<FileType>;
+}
+
+/// The `DirEntry` types of `std`, `cap_std`, and `cap_async_std` all
+/// implement the hidden `_DirEntryRawExt`, which this just exposes.
+impl<T: _DirEntryRawExt> DirEntryRawExt for T {
+    #[inline]
+    fn ino(&self) -> io::Result<u64> {
+        _DirEntryRawExt::ino(self)
+    }
+
+    #[inline]
+    fn d_type_raw(&self) -> io::Result<u32> {
+        _DirEntryRawExt::d_type_raw(self)
+    }
+
+    #[inline]
+    fn file_type_cached(&self) -> Option<FileType> {
+        _DirEntryRawExt::file_type_cached(self)
+    }
+}
@@ -11,23 +11,37 @@
 )]
 
 mod dir_entry_ext;
+mod dir_entry_raw_ext;
 mod dir_ext;
 mod file_type_ext;
 mod is_file_read_write;
 mod metadata_ext;
+mod open_options_append_only_ext;
+mod open_options_backup_semantics_ext;
+mod open_options_create_parents_ext;
+mod open_options_drop_cache_ext;
 mod open_options_follow_ext;
 mod open_options_maybe_dir_ext;
+mod open_options_missing_component_ext;
+mod open_options_truncate_to_written_ext;
 mod reopen;
 
 pub use dir_entry_ext::DirEntryExt;
+pub use dir_entry_raw_ext::DirEntryRawExt;
 #[cfg(all(any(feature = "std", feature = "async_std"), feature = "fs_utf8"))]
 pub use dir_ext::DirExtUtf8;
 pub use dir_ext::{DirExt, SystemTimeSpec};
 pub use file_type_ext::FileTypeExt;
 pub use is_file_read_write::IsFileReadWrite;
 pub use metadata_ext::MetadataExt;
+pub use open_options_append_only_ext::OpenOptionsAppendOnlyExt;
+pub use open_options_backup_semantics_ext::OpenOptionsBackupSemanticsExt;
+pub use open_options_create_parents_ext::OpenOptionsCreateParentsExt;
+pub use open_options_drop_cache_ext::OpenOptionsDropCacheExt;
 pub use open_options_follow_ext::OpenOptionsFollowExt;
 pub use open_options_maybe_dir_ext::OpenOptionsMaybeDirExt;
+pub use open_options_missing_component_ext::OpenOptionsMissingComponentExt;
+pub use open_options_truncate_to_written_ext::OpenOptionsTruncateToWrittenExt;
 pub use reopen::Reopen;
 
 /// Re-export these to allow them to be used with `Reuse`.
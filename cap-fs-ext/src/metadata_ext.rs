@@ -1,5 +1,7 @@
 #[cfg(windows)]
 use cap_primitives::fs::_WindowsByHandle;
+#[cfg(windows)]
+use winapi::um::winnt::{FILE_ATTRIBUTE_COMPRESSED, FILE_ATTRIBUTE_ENCRYPTED};
 
 /// Extension trait for `Metadata`.
 pub trait MetadataExt {
@@ -28,6 +30,25 @@ pub trait MetadataExt {
     ///
     /// [`std::os::unix::fs::MetadataExt::nlink`]: https://doc.rust-lang.org/std/os/unix/fs/trait.MetadataExt.html#tymethod.nlink
     fn nlink(&self) -> u64;
+
+    /// Returns `true` if the file is compressed by the filesystem.
+    ///
+    /// On Windows, this reports `FILE_ATTRIBUTE_COMPRESSED`, which NTFS sets
+    /// on compressed files. On other platforms, this always returns `false`.
+    #[inline]
+    fn is_compressed(&self) -> bool {
+        false
+    }
+
+    /// Returns `true` if the file is encrypted by the filesystem.
+    ///
+    /// On Windows, this reports `FILE_ATTRIBUTE_ENCRYPTED`, which NTFS sets
+    /// on files encrypted with EFS. On other platforms, this always returns
+    /// `false`.
+    #[inline]
+    fn is_encrypted(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(not(windows))]
@@ -69,6 +90,16 @@ impl MetadataExt for std::fs::Metadata {
             .expect("`nlink` depends on a Metadata constructed from an open `File`")
             .into()
     }
+
+    #[inline]
+    fn is_compressed(&self) -> bool {
+        std::os::windows::fs::MetadataExt::file_attributes(self) & FILE_ATTRIBUTE_COMPRESSED != 0
+    }
+
+    #[inline]
+    fn is_encrypted(&self) -> bool {
+        std::os::windows::fs::MetadataExt::file_attributes(self) & FILE_ATTRIBUTE_ENCRYPTED != 0
+    }
 }
 
 #[cfg(all(not(windows), any(feature = "std", feature = "async_std")))]
@@ -109,4 +140,14 @@ impl MetadataExt for cap_primitives::fs::Metadata {
             .expect("`nlink` depends on a Metadata constructed from an open `File`")
             .into()
     }
+
+    #[inline]
+    fn is_compressed(&self) -> bool {
+        _WindowsByHandle::file_attributes(self) & FILE_ATTRIBUTE_COMPRESSED != 0
+    }
+
+    #[inline]
+    fn is_encrypted(&self) -> bool {
+        _WindowsByHandle::file_attributes(self) & FILE_ATTRIBUTE_ENCRYPTED != 0
+    }
 }
@@ -0,0 +1,24 @@
+/// Extension trait for `cap_primitives::fs::OpenOptions` which adds
+/// `append_if_append_only`, a function for controlling whether opening an
+/// append-only file for writing should fall back to append mode. On Linux,
+/// files marked append-only, for example with `chattr +a`, can only be
+/// opened for writing in append mode.
+pub trait OpenOptionsAppendOnlyExt {
+    /// Sets the option to open append-only files in append mode, rather
+    /// than failing, when write access is requested without append mode.
+    ///
+    /// This has no effect if truncation is also requested, since append-only
+    /// files can't be truncated, or on platforms other than Linux.
+    fn append_if_append_only(&mut self, append_if_append_only: bool) -> &mut Self;
+}
+
+impl OpenOptionsAppendOnlyExt for cap_primitives::fs::OpenOptions {
+    #[inline]
+    fn append_if_append_only(&mut self, append_if_append_only: bool) -> &mut Self {
+        // `append_if_append_only` functionality is implemented within
+        // `cap_primitives`; we're just exposing it here since `OpenOptions`
+        // is re-exported by `cap_std` etc. and `append_if_append_only` isn't
+        // in `std`.
+        self._cap_fs_ext_append_if_append_only(append_if_append_only)
+    }
+}
@@ -0,0 +1,29 @@
+/// Extension trait for `cap_primitives::fs::OpenOptions` which adds
+/// `backup_semantics`, a function for opening files with Windows' backup
+/// semantics.
+pub trait OpenOptionsBackupSemanticsExt {
+    /// Sets the option to open with `FILE_FLAG_BACKUP_SEMANTICS` on Windows.
+    ///
+    /// This flag is what allows directories to be opened as handles, and
+    /// when the process has enabled `SeBackupPrivilege` or
+    /// `SeRestorePrivilege`, it also allows the opened file to be read or
+    /// written regardless of its security descriptor, as backup tools need.
+    /// It only affects the final open; `path` is still resolved within the
+    /// sandbox, so it doesn't allow escaping it. Opening directories through
+    /// `Dir::open_dir`, or with `OpenOptionsMaybeDirExt::maybe_dir`, already
+    /// sets the flag.
+    ///
+    /// This has no effect on other platforms.
+    fn backup_semantics(&mut self, backup_semantics: bool) -> &mut Self;
+}
+
+impl OpenOptionsBackupSemanticsExt for cap_primitives::fs::OpenOptions {
+    #[inline]
+    fn backup_semantics(&mut self, backup_semantics: bool) -> &mut Self {
+        // `backup_semantics` functionality is implemented within
+        // `cap_primitives`; we're just exposing it here since `OpenOptions`
+        // is re-exported by `cap_std` etc. and `backup_semantics` isn't in
+        // `std`.
+        self._cap_fs_ext_backup_semantics(backup_semantics)
+    }
+}
@@ -0,0 +1,22 @@
+/// Extension trait for `cap_primitives::fs::OpenOptions` which adds
+/// `create_parents`, a function for creating any missing parent directories
+/// of a file when it's created, as with `create_dir_all`.
+pub trait OpenOptionsCreateParentsExt {
+    /// Sets the option to create any missing parent directories, within the
+    /// sandbox, before creating the file.
+    ///
+    /// This only applies when the open would create the file, that is, when
+    /// `create` or `create_new` is also set; otherwise it has no effect.
+    fn create_parents(&mut self, create_parents: bool) -> &mut Self;
+}
+
+impl OpenOptionsCreateParentsExt for cap_primitives::fs::OpenOptions {
+    #[inline]
+    fn create_parents(&mut self, create_parents: bool) -> &mut Self {
+        // `create_parents` is recorded in `cap_primitives` and applied by
+        // `cap_std`'s `Dir`; we're just exposing it here since `OpenOptions`
+        // is re-exported by `cap_std` etc. and `create_parents` isn't in
+        // `std`.
+        self._cap_fs_ext_create_parents(create_parents)
+    }
+}
@@ -0,0 +1,23 @@
+/// Extension trait for `cap_primitives::fs::OpenOptions` which adds
+/// `drop_cache_on_close`, a function for requesting that a file's pages be
+/// dropped from the page cache when the file is closed. This is useful for
+/// programs such as backup tools, which read a lot of data once and
+/// shouldn't evict other programs' data from the cache.
+pub trait OpenOptionsDropCacheExt {
+    /// Sets the option to drop the file's pages from the page cache when the
+    /// `File` is dropped, as with `File::drop_cache`.
+    ///
+    /// This is only effective on Linux; on other platforms it has no effect.
+    fn drop_cache_on_close(&mut self, drop_cache_on_close: bool) -> &mut Self;
+}
+
+impl OpenOptionsDropCacheExt for cap_primitives::fs::OpenOptions {
+    #[inline]
+    fn drop_cache_on_close(&mut self, drop_cache_on_close: bool) -> &mut Self {
+        // `drop_cache_on_close` is recorded in `cap_primitives` and applied
+        // by `cap_std`'s `File`; we're just exposing it here since
+        // `OpenOptions` is re-exported by `cap_std` etc. and
+        // `drop_cache_on_close` isn't in `std`.
+        self._cap_fs_ext_drop_cache_on_close(drop_cache_on_close)
+    }
+}
@@ -0,0 +1,23 @@
+/// Extension trait for `cap_primitives::fs::OpenOptions` which adds
+/// `report_missing_component`, a function for saying which component of a
+/// path was missing when an open fails with `NotFound`.
+pub trait OpenOptionsMissingComponentExt {
+    /// Sets the option to attach a `MissingComponentError` to `NotFound`
+    /// errors, naming the missing component and whether it's a parent
+    /// directory or the final component.
+    ///
+    /// The attached error wraps the OS error, so with this option set,
+    /// `raw_os_error` on the returned error is `None`.
+    fn report_missing_component(&mut self, report_missing_component: bool) -> &mut Self;
+}
+
+impl OpenOptionsMissingComponentExt for cap_primitives::fs::OpenOptions {
+    #[inline]
+    fn report_missing_component(&mut self, report_missing_component: bool) -> &mut Self {
+        // `report_missing_component` functionality is implemented within
+        // `cap_primitives`; we're just exposing it here since `OpenOptions`
+        // is re-exported by `cap_std` etc. and `report_missing_component`
+        // isn't in `std`.
+        self._cap_fs_ext_report_missing_component(report_missing_component)
+    }
+}
@@ -0,0 +1,25 @@
+/// Extension trait for `cap_primitives::fs::OpenOptions` which adds
+/// `truncate_to_written`, a function for requesting that a file be truncated
+/// to the end of the furthest write through it when it's closed. This is
+/// useful when overwriting a file in place, where writing less than was
+/// there before would otherwise leave the old data's tail behind.
+pub trait OpenOptionsTruncateToWrittenExt {
+    /// Sets the option to truncate the file to the end of the furthest write
+    /// through the `File` when it's dropped, if that's shorter than the file,
+    /// as with `File::finish`. If nothing is written through the `File`, and
+    /// its length isn't set, the file is left as it is.
+    ///
+    /// This only has an effect if the file is opened for writing.
+    fn truncate_to_written(&mut self, truncate_to_written: bool) -> &mut Self;
+}
+
+impl OpenOptionsTruncateToWrittenExt for cap_primitives::fs::OpenOptions {
+    #[inline]
+    fn truncate_to_written(&mut self, truncate_to_written: bool) -> &mut Self {
+        // `truncate_to_written` is recorded in `cap_primitives` and applied
+        // by `cap_std`'s `File`; we're just exposing it here since
+        // `OpenOptions` is re-exported by `cap_std` etc. and
+        // `truncate_to_written` isn't in `std`.
+        self._cap_fs_ext_truncate_to_written(truncate_to_written)
+    }
+}
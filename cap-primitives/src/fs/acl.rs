@@ -0,0 +1,163 @@
+//! POSIX access control lists.
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+use crate::fs::errors;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use crate::fs::{get_acl_impl, set_acl_impl};
+use std::{fs, io, path::Path};
+
+/// Permission to read, in [`AclEntry::perms`].
+pub const ACL_READ: u16 = 0o4;
+
+/// Permission to write, in [`AclEntry::perms`].
+pub const ACL_WRITE: u16 = 0o2;
+
+/// Permission to execute, or to search a directory, in [`AclEntry::perms`].
+pub const ACL_EXECUTE: u16 = 0o1;
+
+/// Who an [`AclEntry`] applies to.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum AclTag {
+    /// The file's owner, corresponding to the owner mode bits.
+    UserObj,
+    /// The user with the given uid.
+    User(u32),
+    /// The file's group, corresponding to the group mode bits when there's
+    /// no mask.
+    GroupObj,
+    /// The group with the given gid.
+    Group(u32),
+    /// The maximum permissions granted by `User`, `GroupObj`, and `Group`
+    /// entries, corresponding to the group mode bits.
+    Mask,
+    /// Everyone else, corresponding to the other mode bits.
+    Other,
+}
+
+/// One entry of an [`Acl`], granting `perms` to whoever `tag` describes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AclEntry {
+    tag: AclTag,
+    perms: u16,
+}
+
+impl AclEntry {
+    /// Constructs a new instance of `Self` granting `perms`, a combination of
+    /// [`ACL_READ`], [`ACL_WRITE`], and [`ACL_EXECUTE`], to `tag`. Other bits
+    /// are ignored.
+    #[inline]
+    pub const fn new(tag: AclTag, perms: u16) -> Self {
+        Self {
+            tag,
+            perms: perms & 0o7,
+        }
+    }
+
+    /// Returns who this entry applies to.
+    #[inline]
+    pub const fn tag(&self) -> AclTag {
+        self.tag
+    }
+
+    /// Returns the permissions this entry grants.
+    #[inline]
+    pub const fn perms(&self) -> u16 {
+        self.perms
+    }
+}
+
+/// A POSIX access control list, as stored in a file's
+/// `system.posix_acl_access` extended attribute.
+///
+/// Entries are kept sorted by tag, in the order the kernel requires. A valid
+/// ACL has exactly one `UserObj`, `GroupObj`, and `Other` entry, and a `Mask`
+/// entry if it has any `User` or `Group` entries; the kernel rejects ACLs
+/// which don't.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Acl {
+    entries: Vec<AclEntry>,
+}
+
+impl Acl {
+    /// Constructs a new, empty, instance of `Self`.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Constructs a new instance of `Self` which is equivalent to the
+    /// permission bits of `mode`, with just the `UserObj`, `GroupObj`, and
+    /// `Other` entries.
+    pub fn from_mode(mode: u32) -> Self {
+        let mut acl = Self::new();
+        acl.push(AclEntry::new(AclTag::UserObj, (mode >> 6) as u16));
+        acl.push(AclEntry::new(AclTag::GroupObj, (mode >> 3) as u16));
+        acl.push(AclEntry::new(AclTag::Other, mode as u16));
+        acl
+    }
+
+    /// Adds `entry`, replacing any existing entry with the same tag.
+    pub fn push(&mut self, entry: AclEntry) {
+        match self.entries.binary_search_by(|e| e.tag.cmp(&entry.tag)) {
+            Ok(index) => self.entries[index] = entry,
+            Err(index) => self.entries.insert(index, entry),
+        }
+    }
+
+    /// Returns the entries, sorted by tag.
+    #[inline]
+    pub fn entries(&self) -> &[AclEntry] {
+        &self.entries
+    }
+
+    /// Returns the entry with the given tag, if there is one.
+    #[inline]
+    pub fn get(&self, tag: AclTag) -> Option<&AclEntry> {
+        self.entries
+            .binary_search_by(|e| e.tag.cmp(&tag))
+            .ok()
+            .map(|index| &self.entries[index])
+    }
+}
+
+/// Read the POSIX access control list of `file`.
+///
+/// Files without an extended ACL report the ACL equivalent to their
+/// permission bits. This reads the `system.posix_acl_access` extended
+/// attribute on Linux; on other platforms it fails with
+/// [`io::ErrorKind::Unsupported`].
+#[inline]
+pub fn get_acl(file: &fs::File) -> io::Result<Acl> {
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        get_acl_impl(file)
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    {
+        let _ = file;
+        Err(errors::acls_unsupported())
+    }
+}
+
+/// Set the POSIX access control list of the file at `path`, relative to
+/// `start`, following symlinks, to `acl`.
+///
+/// The kernel also updates the file's permission bits to match. This writes
+/// the `system.posix_acl_access` extended attribute on Linux; on other
+/// platforms it fails with [`io::ErrorKind::Unsupported`].
+#[inline]
+pub fn set_acl(start: &fs::File, path: &Path, acl: &Acl) -> io::Result<()> {
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        set_acl_impl(start, path, acl)
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    {
+        let _ = (start, path, acl);
+        Err(errors::acls_unsupported())
+    }
+}
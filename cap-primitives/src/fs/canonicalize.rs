@@ -1,6 +1,6 @@
 //! Sandboxed path canonicalization.
 
-use crate::fs::canonicalize_impl;
+use crate::fs::{canonicalize_impl, manually};
 #[cfg(racy_asserts)]
 use crate::fs::{file_path, open, OpenOptions};
 use std::{
@@ -22,6 +22,22 @@ pub fn canonicalize(start: &fs::File, path: &Path) -> io::Result<PathBuf> {
     result
 }
 
+/// Like `canonicalize`, but also return each symlink followed during
+/// resolution, in order, paired with its target.
+///
+/// Both paths in each pair are relative to `start`: the target is the
+/// symlink's contents joined onto the directory containing the symlink, so
+/// it may contain `..` components. This always resolves the path one
+/// component at a time, since faster mechanisms such as `openat2` on Linux
+/// don't report the symlinks they follow.
+#[inline]
+pub fn canonicalize_with_chain(
+    start: &fs::File,
+    path: &Path,
+) -> io::Result<(PathBuf, Vec<(PathBuf, PathBuf)>)> {
+    manually::canonicalize_with_chain(start, path)
+}
+
 #[cfg(racy_asserts)]
 fn check_canonicalize(start: &fs::File, path: &Path, result: &io::Result<PathBuf>) {
     if let Ok(canonical_path) = result {
@@ -1,4 +1,4 @@
-use crate::fs::copy_impl;
+use crate::fs::{copy_impl, open, remove_file, stat, FollowSymlinks, OpenOptions, OverwritePolicy};
 use std::{fs, io, path::Path};
 
 /// Copies the contents of one file to another.
@@ -14,3 +14,47 @@ pub fn copy(
     // so it'd be mostly redundant.
     copy_impl(from_start, from_path, to_start, to_path)
 }
+
+/// Copies the contents of one file to another, with `policy` determining
+/// what happens if the destination already exists.
+///
+/// When nothing is copied because of `policy`, this returns `Ok(0)`.
+pub fn copy_with_policy(
+    from_start: &fs::File,
+    from_path: &Path,
+    to_start: &fs::File,
+    to_path: &Path,
+    policy: OverwritePolicy,
+) -> io::Result<u64> {
+    match policy {
+        OverwritePolicy::Overwrite => copy(from_start, from_path, to_start, to_path),
+        OverwritePolicy::Skip | OverwritePolicy::Error => {
+            // Create the destination with `create_new` so that the existence
+            // check can't race with another creator.
+            if let Err(err) = open(
+                to_start,
+                to_path,
+                OpenOptions::new().write(true).create_new(true),
+            ) {
+                return match (err.kind(), policy) {
+                    (io::ErrorKind::AlreadyExists, OverwritePolicy::Skip) => Ok(0),
+                    _ => Err(err),
+                };
+            }
+            copy(from_start, from_path, to_start, to_path).inspect_err(|_| {
+                remove_file(to_start, to_path).ok();
+            })
+        }
+        OverwritePolicy::OverwriteIfNewer => {
+            let from_modified = stat(from_start, from_path, FollowSymlinks::Yes)?.modified()?;
+            match stat(to_start, to_path, FollowSymlinks::Yes) {
+                Ok(to_metadata) if to_metadata.modified()? >= from_modified => Ok(0),
+                Ok(_) => copy(from_start, from_path, to_start, to_path),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    copy(from_start, from_path, to_start, to_path)
+                }
+                Err(err) => Err(err),
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::fs::{open, OpenOptions};
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The largest chunk `copy_throttled` reads and writes at a time.
+const MAX_CHUNK: u64 = 64 * 1024;
+
+/// Copies the contents of one file to another, pacing the copy to
+/// approximately `bytes_per_sec`.
+///
+/// The data is copied through a buffer, rather than with any accelerated
+/// copying the platform may offer, so that the copy can sleep between
+/// chunks. Each chunk is at most 64 KiB and at most a tenth of a second's
+/// worth of data at the requested rate. After each chunk is written, this
+/// sleeps until the time that the bytes copied so far should have taken, so
+/// the rate is only held on average, with bursts of up to one chunk, and
+/// time spent reading and writing counts toward the budget. A
+/// `bytes_per_sec` of zero fails with [`io::ErrorKind::InvalidInput`]. Like
+/// `copy`, this copies the permission bits of the source to the destination.
+pub fn copy_throttled(
+    from_start: &fs::File,
+    from_path: &Path,
+    to_start: &fs::File,
+    to_path: &Path,
+    bytes_per_sec: u64,
+) -> io::Result<u64> {
+    if bytes_per_sec == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the copy rate must be greater than zero",
+        ));
+    }
+
+    let mut reader = open(from_start, from_path, OpenOptions::new().read(true))?;
+    let metadata = reader.metadata()?;
+    if !metadata.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the source path is not an existing regular file",
+        ));
+    }
+
+    let mut writer = open(
+        to_start,
+        to_path,
+        OpenOptions::new().write(true).create(true).truncate(true),
+    )?;
+
+    let chunk = (bytes_per_sec / 10).clamp(1, MAX_CHUNK);
+    let mut buf = vec![0_u8; chunk as usize];
+    let start = Instant::now();
+    let mut written = 0_u64;
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        writer.write_all(&buf[..n])?;
+        written += n as u64;
+
+        let due = pace(written, bytes_per_sec);
+        let elapsed = start.elapsed();
+        if due > elapsed {
+            thread::sleep(due - elapsed);
+        }
+    }
+
+    writer.set_permissions(metadata.permissions())?;
+    Ok(written)
+}
+
+/// Compute how long copying `written` bytes should take at `bytes_per_sec`.
+fn pace(written: u64, bytes_per_sec: u64) -> Duration {
+    let secs = written / bytes_per_sec;
+    let rem = written % bytes_per_sec;
+    // `rem < bytes_per_sec`, so this doesn't overflow for any rate below
+    // about 18 PB/s, and the result is less than a billion.
+    let nanos = (u128::from(rem) * 1_000_000_000 / u128::from(bytes_per_sec)) as u32;
+    Duration::new(secs, nanos)
+}
@@ -0,0 +1,143 @@
+use crate::fs::{errors, open, remove_file, OpenOptions};
+use std::{
+    fs,
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+/// Copies the contents of one file to another, and then reads the
+/// destination back to check that it matches what was read from the source.
+///
+/// After the copy, the destination is synced, and then the source and the
+/// destination are both read back and compared byte for byte. If they
+/// differ, the destination is removed and this fails with
+/// [`io::ErrorKind::InvalidData`]. Like `copy`, this copies the permission
+/// bits of the source to the destination.
+pub fn copy_verified(
+    from_start: &fs::File,
+    from_path: &Path,
+    to_start: &fs::File,
+    to_path: &Path,
+) -> io::Result<u64> {
+    let mut reader = open(from_start, from_path, OpenOptions::new().read(true))?;
+    let metadata = reader.metadata()?;
+    if !metadata.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the source path is not an existing regular file",
+        ));
+    }
+
+    let mut writer = open(
+        to_start,
+        to_path,
+        OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true),
+    )?;
+
+    match copy_and_verify(&mut reader, &mut writer, |_| ()) {
+        Ok(len) => {
+            writer.set_permissions(metadata.permissions())?;
+            Ok(len)
+        }
+        Err(err) => {
+            if err.kind() == io::ErrorKind::InvalidData {
+                // Don't leave a corrupt copy behind.
+                drop(writer);
+                remove_file(to_start, to_path).ok();
+            }
+            Err(err)
+        }
+    }
+}
+
+/// Copy `reader` to `writer`, and then read both back from the start and
+/// compare them. `tamper` is applied to each buffer before it's written, so
+/// that tests can simulate corruption.
+fn copy_and_verify(
+    reader: &mut fs::File,
+    writer: &mut fs::File,
+    mut tamper: impl FnMut(&mut [u8]),
+) -> io::Result<u64> {
+    let mut buf = vec![0_u8; 64 * 1024];
+
+    let mut len = 0_u64;
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        tamper(&mut buf[..n]);
+        writer.write_all(&buf[..n])?;
+        len += n as u64;
+    }
+
+    // Make sure the readback sees what actually reached the file.
+    writer.sync_data()?;
+    reader.seek(SeekFrom::Start(0))?;
+    writer.seek(SeekFrom::Start(0))?;
+
+    let mut dest_buf = vec![0_u8; buf.len()];
+    loop {
+        let n = read_up_to(reader, &mut buf)?;
+        let dest_n = read_up_to(writer, &mut dest_buf)?;
+        if buf[..n] != dest_buf[..dest_n] {
+            return Err(errors::copy_mismatch());
+        }
+        if n == 0 {
+            return Ok(len);
+        }
+    }
+}
+
+/// Read from `file` until `buf` is full or the end of the file is reached,
+/// so that chunks of the source and destination line up.
+fn read_up_to(file: &mut fs::File, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match file.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled)
+}
+
+/// Test that a copy which doesn't match its source is detected, by flipping
+/// a bit in the data before it's written.
+#[test]
+fn copy_verified_mismatch() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path();
+    fs::write(path.join("from"), b"hello, world").unwrap();
+
+    let open_pair = || {
+        let reader = fs::File::open(path.join("from")).unwrap();
+        let writer = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path.join("to"))
+            .unwrap();
+        (reader, writer)
+    };
+
+    let (mut reader, mut writer) = open_pair();
+    assert_eq!(
+        copy_and_verify(&mut reader, &mut writer, |_| ()).unwrap(),
+        12
+    );
+
+    let (mut reader, mut writer) = open_pair();
+    let err = copy_and_verify(&mut reader, &mut writer, |buf| buf[0] ^= 1).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), errors::copy_mismatch().to_string());
+}
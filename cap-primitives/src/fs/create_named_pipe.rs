@@ -0,0 +1,32 @@
+//! This defines `create_named_pipe`, for creating FIFOs and named pipes.
+
+use crate::fs::{errors, via_parent, PipeOptions};
+use std::{fs, io, path::Path};
+
+/// Create a named pipe at `path`, relative to `start`, and return a file
+/// for the new pipe's server end.
+///
+/// On Unix-family platforms, this creates a FIFO with `mkfifo`, and then
+/// opens it with the access requested in `options`. The open doesn't wait
+/// for a peer, so opening a FIFO for writing only fails with `ENXIO` if
+/// nothing is reading from it. Clients connect by opening the FIFO. FIFOs
+/// have no notion of server instances, so `options.max_instances` must be 1.
+///
+/// On Windows, named pipes live in their own namespace rather than in
+/// ordinary directories, so `start` must be a handle for that namespace,
+/// such as one for `\\.\pipe\` opened with ambient authority. This creates a
+/// server instance with `CreateNamedPipeW`, in byte mode and blocking. A
+/// client connects by opening `path` in the same directory, after which the
+/// server end can be read from and written to; `ConnectNamedPipe` isn't
+/// needed.
+pub fn create_named_pipe(
+    start: &fs::File,
+    path: &Path,
+    options: &PipeOptions,
+) -> io::Result<fs::File> {
+    if !options.read && !options.write {
+        return Err(errors::invalid_pipe_options());
+    }
+
+    via_parent::create_named_pipe(start, path, options)
+}
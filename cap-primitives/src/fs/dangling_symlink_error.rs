@@ -0,0 +1,46 @@
+use std::{error, fmt, io};
+
+/// The error carried by `NotFound` errors from opening a path which names a
+/// symlink whose target doesn't exist, distinguishing it from the path itself
+/// not existing.
+///
+/// Use [`DanglingSymlinkError::from_io_error`] to retrieve it from an
+/// `io::Error`.
+#[derive(Debug)]
+pub struct DanglingSymlinkError {
+    source: io::Error,
+}
+
+impl DanglingSymlinkError {
+    /// Returns the `DanglingSymlinkError` carried by `err`, if `err` is from
+    /// opening a dangling symlink.
+    pub fn from_io_error(err: &io::Error) -> Option<&Self> {
+        err.get_ref().and_then(|inner| inner.downcast_ref::<Self>())
+    }
+
+    /// Returns the OS error code of the `NotFound` error this was made from,
+    /// which the `io::Error` carrying it doesn't report itself.
+    pub fn raw_os_error(&self) -> Option<i32> {
+        self.source.raw_os_error()
+    }
+
+    pub(crate) fn new(source: io::Error) -> Self {
+        Self { source }
+    }
+}
+
+impl fmt::Display for DanglingSymlinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (the path is a symlink whose target doesn't exist)",
+            self.source
+        )
+    }
+}
+
+impl error::Error for DanglingSymlinkError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
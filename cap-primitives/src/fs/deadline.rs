@@ -0,0 +1,44 @@
+//! A thread-local deadline for path resolution.
+
+use crate::fs::errors;
+use std::{cell::Cell, io, time::Instant};
+
+thread_local! {
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Call `f` with a deadline in effect for filesystem operations on the
+/// current thread.
+///
+/// While `f` runs, path resolution checks the deadline between steps, such
+/// as between components in manual resolution and between retries of
+/// `openat2`, and fails with [`io::ErrorKind::TimedOut`] once it has passed.
+/// System calls already in progress can't be interrupted, so a single slow
+/// call may still overrun the deadline.
+///
+/// Deadlines nest; the earliest one in effect applies.
+pub fn with_deadline<R, F: FnOnce() -> R>(deadline: Instant, f: F) -> R {
+    struct Restore(Option<Instant>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            DEADLINE.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _restore = Restore(DEADLINE.with(|cell| {
+        let prev = cell.get();
+        cell.set(Some(prev.map_or(deadline, |prev| prev.min(deadline))));
+        prev
+    }));
+    f()
+}
+
+/// Fail with `TimedOut` if a deadline set with `with_deadline` has passed.
+#[inline]
+pub(crate) fn check_deadline() -> io::Result<()> {
+    match DEADLINE.with(Cell::get) {
+        Some(deadline) if Instant::now() >= deadline => Err(errors::deadline_exceeded()),
+        _ => Ok(()),
+    }
+}
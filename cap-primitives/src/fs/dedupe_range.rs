@@ -0,0 +1,41 @@
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use crate::fs::dedupe_range_impl;
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+use crate::fs::errors;
+use std::{fs, io};
+
+/// Ask the filesystem to share the storage of `len` bytes of `dst` at
+/// `dst_offset` with the same bytes of `src` at `src_offset`, if they're
+/// identical.
+///
+/// Returns `true` if the kernel compared the ranges, found them identical,
+/// and deduplicated them, and `false` if their contents differ, in which
+/// case neither file is changed. `dst` must be open for writing.
+///
+/// The offsets must be multiples of the filesystem's block size, and so must
+/// `len`, unless the range ends at the end of `src`. Deduplication is only
+/// supported on some filesystems, such as Btrfs and XFS; elsewhere this
+/// fails with an error such as [`io::ErrorKind::Unsupported`] or
+/// [`io::ErrorKind::InvalidInput`].
+///
+/// This uses the `FIDEDUPERANGE` ioctl on Linux; on other platforms it fails
+/// with [`io::ErrorKind::Unsupported`].
+#[inline]
+pub fn dedupe_range(
+    dst: &fs::File,
+    src: &fs::File,
+    src_offset: u64,
+    len: u64,
+    dst_offset: u64,
+) -> io::Result<bool> {
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        dedupe_range_impl(dst, src, src_offset, len, dst_offset)
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    {
+        let _ = (dst, src, src_offset, len, dst_offset);
+        Err(errors::dedupe_unsupported())
+    }
+}
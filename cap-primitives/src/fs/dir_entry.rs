@@ -110,6 +110,38 @@ impl DirEntryExt for DirEntry {
     }
 }
 
+#[doc(hidden)]
+impl _DirEntryRawExt for DirEntry {
+    #[cfg(not(windows))]
+    #[inline]
+    fn ino(&self) -> io::Result<u64> {
+        Ok(self.inner.ino())
+    }
+
+    #[cfg(windows)]
+    #[inline]
+    fn ino(&self) -> io::Result<u64> {
+        self.inner.ino()
+    }
+
+    #[cfg(not(windows))]
+    #[inline]
+    fn d_type_raw(&self) -> io::Result<u32> {
+        Ok(self.inner.d_type_raw())
+    }
+
+    #[cfg(windows)]
+    #[inline]
+    fn d_type_raw(&self) -> io::Result<u32> {
+        self.inner.d_type_raw()
+    }
+
+    #[inline]
+    fn file_type_cached(&self) -> Option<FileType> {
+        self.inner.file_type_cached()
+    }
+}
+
 impl fmt::Debug for DirEntry {
     // Like libstd's version, but doesn't print the path.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -127,3 +159,15 @@ impl fmt::Debug for DirEntry {
 pub trait _WindowsDirEntryExt {
     fn full_metadata(&self) -> io::Result<Metadata>;
 }
+
+/// Extension trait to allow `d_type_raw` etc. to be exposed by the
+/// `cap-fs-ext` crate.
+///
+/// This is hidden from the main API since this functionality isn't present in `std`.
+/// Use `cap_fs_ext::DirEntryRawExt` instead of calling this directly.
+#[doc(hidden)]
+pub trait _DirEntryRawExt {
+    fn ino(&self) -> io::Result<u64>;
+    fn d_type_raw(&self) -> io::Result<u32>;
+    fn file_type_cached(&self) -> Option<FileType>;
+}
@@ -0,0 +1,27 @@
+use crate::fs::Metadata;
+use std::{fs, io};
+
+/// An identifier for a directory, usable as a key in a `HashMap` or
+/// `HashSet`, for example to deduplicate handles to the same directory.
+///
+/// This wraps the device and inode numbers on Posix-ish platforms, and the
+/// volume serial number and file index on Windows.
+///
+/// Identifiers are only unique among directories which currently exist. Once
+/// a directory is removed, the OS may reuse its identifier for a new file or
+/// directory.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct DirId {
+    dev: u64,
+    ino: u64,
+}
+
+impl DirId {
+    /// Constructs a new instance of `Self` identifying the directory (or
+    /// other file) that `file` refers to.
+    #[inline]
+    pub fn from_file(file: &fs::File) -> io::Result<Self> {
+        let (dev, ino) = Metadata::from_file(file)?.ext.file_id();
+        Ok(Self { dev, ino })
+    }
+}
@@ -17,6 +17,21 @@ impl DirOptions {
             ext: DirOptionsExt::new(),
         }
     }
+
+    /// Wrapper to allow `cap-std` to apply a `Dir`'s default directory
+    /// mode, which is used unless a mode has been set explicitly with
+    /// `DirBuilderExt::mode`. This has no effect on Windows.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_std_default_mode(&mut self, mode: u32) -> &mut Self {
+        #[cfg(any(unix, target_os = "vxworks"))]
+        self.ext.default_mode(mode);
+
+        #[cfg(not(any(unix, target_os = "vxworks")))]
+        let _ = mode;
+
+        self
+    }
 }
 
 #[cfg(unix)]
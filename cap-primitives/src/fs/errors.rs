@@ -1,4 +1,5 @@
-use std::io;
+use crate::fs::{DanglingSymlinkError, EscapeError, MissingComponentError};
+use std::{io, path::PathBuf};
 
 #[cfg(not(windows))]
 pub(crate) use crate::posish::fs::errors::*;
@@ -7,8 +8,129 @@ pub(crate) use crate::windows::fs::errors::*;
 
 #[cold]
 pub(crate) fn escape_attempt() -> io::Error {
+    io::Error::new(io::ErrorKind::PermissionDenied, EscapeError::default())
+}
+
+#[cold]
+pub(crate) fn escape_through_symlink(symlink: PathBuf, target: PathBuf) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        EscapeError::through_symlink(symlink, target),
+    )
+}
+
+#[cold]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn escape_detected_by_kernel() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        EscapeError::detected_by_kernel(),
+    )
+}
+
+#[cold]
+#[cfg(not(windows))]
+pub(crate) fn directory_cycle() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "a directory was encountered more than once while traversing",
+    )
+}
+
+#[cold]
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+pub(crate) fn acls_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "POSIX ACLs are not supported on this platform",
+    )
+}
+
+#[cold]
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+pub(crate) fn dedupe_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "deduplicating file ranges is not supported on this platform",
+    )
+}
+
+#[cold]
+#[cfg(any(target_os = "redox", target_os = "wasi"))]
+pub(crate) fn mount_flags_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "mount flags are not supported on this platform",
+    )
+}
+
+#[cold]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn append_only() -> io::Error {
     io::Error::new(
         io::ErrorKind::PermissionDenied,
-        "a path led outside of the filesystem",
+        "the file is append-only, so it can only be opened for writing in append mode",
     )
 }
+
+#[cold]
+pub(crate) fn copy_mismatch() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "the copy's contents don't match the source",
+    )
+}
+
+#[cold]
+pub(crate) fn deadline_exceeded() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::TimedOut,
+        "the filesystem operation deadline has passed",
+    )
+}
+
+#[cold]
+pub(crate) fn not_a_single_component() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "the name must be a single normal path component",
+    )
+}
+
+#[cold]
+pub(crate) fn invalid_pipe_options() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "a named pipe must be opened for reading, writing, or both",
+    )
+}
+
+#[cold]
+#[cfg(not(windows))]
+pub(crate) fn pipe_instances_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "multiple named pipe instances are not supported on this platform",
+    )
+}
+
+#[cold]
+pub(crate) fn open_if_creates() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "a conditional open can't create the file",
+    )
+}
+
+#[cold]
+pub(crate) fn missing_component(err: io::Error, component: PathBuf, parent: bool) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        MissingComponentError::new(err, component, parent),
+    )
+}
+
+#[cold]
+pub(crate) fn dangling_symlink(err: io::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, DanglingSymlinkError::new(err))
+}
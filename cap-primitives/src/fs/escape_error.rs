@@ -0,0 +1,69 @@
+use std::{
+    error, fmt, io,
+    path::{Path, PathBuf},
+};
+
+/// Details about a path which led outside of the filesystem, carried by the
+/// `PermissionDenied` errors that sandboxed operations return.
+///
+/// Use [`EscapeError::from_io_error`] to retrieve it from an `io::Error`.
+#[derive(Clone, Debug, Default)]
+pub struct EscapeError {
+    symlink: Option<(PathBuf, PathBuf)>,
+    kernel_detected: bool,
+}
+
+impl EscapeError {
+    /// Returns the `EscapeError` carried by `err`, if it's an escape error.
+    pub fn from_io_error(err: &io::Error) -> Option<&Self> {
+        err.get_ref().and_then(|inner| inner.downcast_ref::<Self>())
+    }
+
+    /// Returns the path of the symlink whose target led outside of the
+    /// filesystem, relative to the start of the lookup, if known.
+    pub fn symlink(&self) -> Option<&Path> {
+        self.symlink.as_ref().map(|(symlink, _)| symlink.as_path())
+    }
+
+    /// Returns the raw target of the symlink returned by
+    /// [`EscapeError::symlink`], if known.
+    pub fn target(&self) -> Option<&Path> {
+        self.symlink.as_ref().map(|(_, target)| target.as_path())
+    }
+
+    /// Returns `true` if the escape was detected by the kernel, as with
+    /// `openat2` on Linux, which doesn't report which component or symlink
+    /// was responsible.
+    pub fn kernel_detected(&self) -> bool {
+        self.kernel_detected
+    }
+
+    pub(crate) fn through_symlink(symlink: PathBuf, target: PathBuf) -> Self {
+        Self {
+            symlink: Some((symlink, target)),
+            kernel_detected: false,
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub(crate) fn detected_by_kernel() -> Self {
+        Self {
+            symlink: None,
+            kernel_detected: true,
+        }
+    }
+}
+
+impl fmt::Display for EscapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a path led outside of the filesystem")?;
+        if let Some((symlink, target)) = &self.symlink {
+            write!(f, " through symlink {:?} with target {:?}", symlink, target)?;
+        } else if self.kernel_detected {
+            f.write_str(" (detected by the kernel)")?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for EscapeError {}
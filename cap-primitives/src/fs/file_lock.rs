@@ -0,0 +1,25 @@
+use crate::fs::{lock_file_exclusive_impl, try_lock_file_exclusive_impl, unlock_file_impl};
+use std::{fs, io};
+
+/// Take an exclusive advisory lock on the whole of `file`, blocking until
+/// it's available.
+///
+/// This uses `flock` on Posix-ish platforms and `LockFileEx` on Windows.
+#[inline]
+pub fn lock_file_exclusive(file: &fs::File) -> io::Result<()> {
+    lock_file_exclusive_impl(file)
+}
+
+/// Like [`lock_file_exclusive`], but returns `Ok(false)` rather than
+/// blocking if the lock is held elsewhere.
+#[inline]
+pub fn try_lock_file_exclusive(file: &fs::File) -> io::Result<bool> {
+    try_lock_file_exclusive_impl(file)
+}
+
+/// Release a lock taken by [`lock_file_exclusive`] or
+/// [`try_lock_file_exclusive`].
+#[inline]
+pub fn unlock_file(file: &fs::File) -> io::Result<()> {
+    unlock_file_impl(file)
+}
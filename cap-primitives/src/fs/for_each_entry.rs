@@ -0,0 +1,26 @@
+use crate::fs::{for_each_entry_impl, FileType};
+use std::{ffi::OsStr, fs, io, ops::ControlFlow};
+
+/// Call `f` with the name and type of each entry in the directory `start`,
+/// stopping early if it returns [`ControlFlow::Break`].
+///
+/// This is a lighter-weight alternative to [`read_base_dir`] for scans which
+/// only need names and types: it doesn't construct a [`DirEntry`] for each
+/// entry. On Unix-family platforms it reads the directory stream directly,
+/// without allocating per entry; on Windows it currently reads the directory
+/// through `std::fs::read_dir`, which still allocates each entry and its
+/// name. The `.` and `..` entries are skipped.
+///
+/// The file type comes from the directory entry itself, so it's
+/// [`FileType::unknown`] on filesystems which don't report types there, and
+/// symlinks are reported as symlinks rather than as what they point to.
+///
+/// [`read_base_dir`]: crate::fs::read_base_dir
+/// [`DirEntry`]: crate::fs::DirEntry
+#[inline]
+pub fn for_each_entry(
+    start: &fs::File,
+    f: impl FnMut(&OsStr, FileType) -> ControlFlow<()>,
+) -> io::Result<()> {
+    for_each_entry_impl(start, f)
+}
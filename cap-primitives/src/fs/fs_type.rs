@@ -0,0 +1,69 @@
+use crate::fs::filesystem_type_impl;
+use std::{fs, io};
+
+/// The kind of filesystem a file is on, as returned by [`filesystem_type`].
+///
+/// This classification is best-effort. It's based on `fstatfs`'s `f_type`
+/// on Linux, and on the remote protocol or the volume's filesystem name on
+/// Windows. Filesystems which aren't recognized, including all filesystems
+/// on other platforms, are reported as `Other`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum FsType {
+    /// NFS.
+    Nfs,
+
+    /// SMB, including CIFS and SMB2.
+    Smb,
+
+    /// A FUSE filesystem, which may be local or remote.
+    Fuse,
+
+    /// tmpfs.
+    Tmpfs,
+
+    /// ext2, ext3, or ext4.
+    Ext,
+
+    /// XFS.
+    Xfs,
+
+    /// Btrfs.
+    Btrfs,
+
+    /// ZFS.
+    Zfs,
+
+    /// OverlayFS.
+    Overlay,
+
+    /// NTFS.
+    Ntfs,
+
+    /// ReFS.
+    Refs,
+
+    /// FAT or exFAT.
+    Fat,
+
+    /// Some other filesystem, with the raw platform value: `f_type` on
+    /// Posix-ish platforms, the remote protocol for network filesystems on
+    /// Windows, or 0 for unrecognized local filesystems on Windows.
+    Other(u64),
+}
+
+impl FsType {
+    /// Returns `true` if this is a network filesystem.
+    ///
+    /// FUSE filesystems may or may not be backed by the network, so this
+    /// returns `false` for them.
+    #[inline]
+    pub fn is_network(&self) -> bool {
+        matches!(self, Self::Nfs | Self::Smb)
+    }
+}
+
+/// Determine the kind of filesystem that `file` is on.
+#[inline]
+pub fn filesystem_type(file: &fs::File) -> io::Result<FsType> {
+    filesystem_type_impl(file)
+}
@@ -24,6 +24,12 @@ impl<'path_buf> CanonicalPath<'path_buf> {
         }
     }
 
+    /// Did the user request the canonical path?
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub(super) fn is_requested(&self) -> bool {
+        self.path.is_some()
+    }
+
     pub(super) fn push(&mut self, one: &OsStr) {
         #[cfg(racy_asserts)]
         self.debug.push(one);
@@ -2,10 +2,10 @@
 //! resolution, in order to enforce sandboxing.
 
 use super::internal_open;
-use crate::fs::{canonicalize_options, FollowSymlinks, MaybeOwnedFile};
+use crate::fs::{canonicalize_options, FollowSymlinks, ResolutionDecision, ResolutionStep};
 use std::{
     fs, io,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
 };
 
 /// Implement `canonicalize` by breaking up the path into components and
@@ -24,7 +24,6 @@ pub(crate) fn canonicalize_with(
 ) -> io::Result<PathBuf> {
     let mut symlink_count = 0;
     let mut canonical_path = PathBuf::new();
-    let start = MaybeOwnedFile::borrowed(start);
 
     if let Err(e) = internal_open(
         start,
@@ -32,6 +31,7 @@ pub(crate) fn canonicalize_with(
         canonicalize_options().follow(follow),
         &mut symlink_count,
         Some(&mut canonical_path),
+        None,
     ) {
         if canonical_path.as_os_str().is_empty() {
             return Err(e);
@@ -40,3 +40,53 @@ pub(crate) fn canonicalize_with(
 
     Ok(canonical_path)
 }
+
+/// Like `canonicalize`, but also return each symlink followed, and its
+/// target, relative to `start`, in resolution order.
+pub(crate) fn canonicalize_with_chain(
+    start: &fs::File,
+    path: &Path,
+) -> io::Result<(PathBuf, Vec<(PathBuf, PathBuf)>)> {
+    let mut symlink_count = 0;
+    let mut canonical_path = PathBuf::new();
+    let mut trace = Vec::new();
+
+    if let Err(e) = internal_open(
+        start,
+        path,
+        canonicalize_options().follow(FollowSymlinks::Yes),
+        &mut symlink_count,
+        Some(&mut canonical_path),
+        Some(&mut trace),
+    ) {
+        if canonical_path.as_os_str().is_empty() {
+            return Err(e);
+        }
+    }
+
+    Ok((canonical_path, chain_from_trace(&trace)))
+}
+
+/// Replay the steps of a resolution to find the path of each symlink
+/// followed, and of its target, relative to the start.
+fn chain_from_trace(trace: &[ResolutionStep]) -> Vec<(PathBuf, PathBuf)> {
+    let mut base = PathBuf::new();
+    let mut chain = Vec::new();
+    for step in trace {
+        match step.decision() {
+            ResolutionDecision::Descend => base.push(step.component()),
+            ResolutionDecision::Ascend if step.component() == Component::RootDir.as_os_str() => {
+                base.clear()
+            }
+            ResolutionDecision::Ascend => {
+                base.pop();
+            }
+            ResolutionDecision::Follow => {
+                let target = step.symlink_target().unwrap();
+                chain.push((base.join(step.component()), base.join(target)));
+            }
+            ResolutionDecision::Reject | ResolutionDecision::Kernel => (),
+        }
+    }
+    chain
+}
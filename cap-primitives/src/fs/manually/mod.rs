@@ -14,10 +14,12 @@ use cow_component::CowComponent;
 use open::internal_open;
 use read_link_one::read_link_one;
 
-#[cfg(racy_asserts)]
-pub(super) use canonicalize::canonicalize_with;
+#[cfg(any(racy_asserts, target_os = "android", target_os = "linux"))]
+pub(crate) use canonicalize::canonicalize_with;
 
-pub(crate) use canonicalize::canonicalize;
-pub(crate) use open::{open, stat};
+pub(crate) use canonicalize::{canonicalize, canonicalize_with_chain};
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+pub(crate) use open::open_and_resolve;
+pub(crate) use open::{open, open_in_root, open_traced, stat, stat_in_root};
 #[cfg(not(windows))]
 pub(crate) use open_entry::open_entry;
@@ -3,17 +3,19 @@
 
 use super::{read_link_one, CanonicalPath, CowComponent};
 use crate::fs::{
-    dir_options, errors, open_unchecked, path_has_trailing_dot, path_has_trailing_slash,
-    stat_unchecked, FollowSymlinks, MaybeOwnedFile, Metadata, OpenOptions, OpenUncheckedError,
+    check_deadline, dir_options, errors, lone_root_as_cur_dir, open_unchecked,
+    path_has_trailing_dot, path_has_trailing_slash, stat_unchecked, DanglingSymlinkError,
+    EscapeError, FollowSymlinks, MaybeOwnedFile, Metadata, MissingComponentError, OpenOptions,
+    OpenUncheckedError, ResolutionDecision, ResolutionStep,
 };
-#[cfg(any(target_os = "android", target_os = "linux"))]
-use posish::fs::OFlags;
 use std::{
     borrow::Cow,
     ffi::OsStr,
     fs, io, mem,
     path::{Component, Path, PathBuf},
 };
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use {crate::fs::casefolded_name, posish::fs::OFlags};
 #[cfg(windows)]
 use {
     crate::fs::{open_dir_unchecked, path_really_has_trailing_dot, SymlinkKind},
@@ -23,12 +25,76 @@ use {
 /// Implement `open` by breaking up the path into components, resolving each
 /// component individually, and resolving symbolic links manually.
 pub(crate) fn open(start: &fs::File, path: &Path, options: &OpenOptions) -> io::Result<fs::File> {
+    let path = lone_root_as_cur_dir(path);
+    let mut symlink_count = 0;
+    let maybe_owned = internal_open(start, path, options, &mut symlink_count, None, None)?;
+    maybe_owned.into_file(options)
+}
+
+/// Like `open`, but also record each resolution step in `trace`, even if the
+/// open fails.
+pub(crate) fn open_traced(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+    trace: &mut Vec<ResolutionStep>,
+) -> io::Result<fs::File> {
+    let path = lone_root_as_cur_dir(path);
+    let mut symlink_count = 0;
+    let maybe_owned = internal_open(start, path, options, &mut symlink_count, None, Some(trace))?;
+    maybe_owned.into_file(options)
+}
+
+/// Implement `open_in_root` by resolving `path` as `open` does, except that
+/// absolute paths and symlink targets restart at `start`, and `..` at `start`
+/// stays at `start`, as with `RESOLVE_IN_ROOT`.
+pub(crate) fn open_in_root(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+) -> io::Result<fs::File> {
+    let path = lone_root_as_cur_dir(path);
+    if path.as_os_str().is_empty() {
+        return Err(errors::no_such_file_or_directory());
+    }
+
     let mut symlink_count = 0;
-    let start = MaybeOwnedFile::borrowed(start);
-    let maybe_owned = internal_open(start, path, options, &mut symlink_count, None)?;
+    let mut ctx = Context::new(MaybeOwnedFile::borrowed(start), path, options, None);
+    ctx.in_root = true;
+    let maybe_owned = resolve(ctx, path, options, &mut symlink_count)
+        .map_err(|err| report_missing_component(err, start, path, options, true))?;
     maybe_owned.into_file(options)
 }
 
+/// Like `open`, but also return the canonical path of the opened file,
+/// relative to `start`, as computed during resolution.
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+pub(crate) fn open_and_resolve(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+) -> io::Result<(fs::File, PathBuf)> {
+    let mut symlink_count = 0;
+    let mut canonical_path = PathBuf::new();
+    let maybe_owned = internal_open(
+        start,
+        path,
+        options,
+        &mut symlink_count,
+        Some(&mut canonical_path),
+        None,
+    )?;
+    let file = maybe_owned.into_file(options)?;
+
+    // Replace "" with ".", since "" as a relative path is interpreted as an
+    // error.
+    if canonical_path.as_os_str().is_empty() {
+        canonical_path.push(Component::CurDir);
+    }
+
+    Ok((file, canonical_path))
+}
+
 /// Context for performing manual component-at-a-time path resolution.
 struct Context<'start> {
     /// The current base directory handle for path lookups.
@@ -43,6 +109,27 @@ struct Context<'start> {
     /// If requested, the canonical path is constructed here.
     canonical_path: CanonicalPath<'start>,
 
+    /// Are `base_path` and `symlinks` maintained, and missing components
+    /// reported? This costs allocations for each component, so it's only
+    /// done when the paths are needed for a result, or for an error which
+    /// has already been encountered once.
+    track_paths: bool,
+
+    /// If `track_paths` is set, the path of `base` relative to the start.
+    base_path: PathBuf,
+
+    /// If `track_paths` is set, the symlinks whose targets are currently
+    /// being resolved, innermost last, with the length of `components`
+    /// before each target's components were pushed.
+    symlinks: Vec<(PathBuf, PathBuf, usize)>,
+
+    /// If requested, each resolution step is recorded here.
+    trace: Option<&'start mut Vec<ResolutionStep>>,
+
+    /// Is `start` treated as the root of the filesystem, so that absolute
+    /// paths restart at it and `..` at it stays at it?
+    in_root: bool,
+
     /// Does the path end in `/` or similar, so it requires a directory?
     dir_required: bool,
 
@@ -62,6 +149,10 @@ struct Context<'start> {
     /// allocations.
     reuse: PathBuf,
 
+    /// Is the last component of the path a symlink whose target is being
+    /// resolved, so that a missing component means the symlink dangles?
+    following_last_symlink: bool,
+
     #[cfg(racy_asserts)]
     start_clone: MaybeOwnedFile<'start>,
 }
@@ -115,6 +206,11 @@ impl<'start> Context<'start> {
             dirs: Vec::with_capacity(components.len()),
             components,
             canonical_path: CanonicalPath::new(canonical_path),
+            track_paths: false,
+            base_path: PathBuf::new(),
+            symlinks: Vec::new(),
+            trace: None,
+            in_root: false,
             dir_required: trailing_slash,
 
             #[cfg(not(windows))]
@@ -129,6 +225,8 @@ impl<'start> Context<'start> {
 
             reuse: PathBuf::new(),
 
+            following_last_symlink: false,
+
             #[cfg(racy_asserts)]
             start_clone,
         }
@@ -201,9 +299,21 @@ impl<'start> Context<'start> {
                 // Looks good.
                 self.base = dir;
             }
-            None => return Err(errors::escape_attempt()),
+            // At the root, `..` stays at the root.
+            None if self.in_root => {
+                self.record(Component::ParentDir.as_os_str(), ResolutionDecision::Ascend);
+                return Ok(());
+            }
+            None => {
+                self.record(Component::ParentDir.as_os_str(), ResolutionDecision::Reject);
+                return Err(self.escape_attempt());
+            }
         }
+        self.record(Component::ParentDir.as_os_str(), ResolutionDecision::Ascend);
         assert!(self.canonical_path.pop());
+        if self.track_paths {
+            self.base_path.pop();
+        }
 
         Ok(())
     }
@@ -237,6 +347,10 @@ impl<'start> Context<'start> {
 
         let dir_required = self.dir_required || use_options.dir_required;
 
+        // Leave the lookup of `one` to the OS, rather than matching names
+        // ourselves, so that directories with case-insensitive lookups, such
+        // as casefolded directories on Linux, match the same names here as
+        // they do with `openat2`. See `stored_name` for the canonical path.
         #[allow(clippy::redundant_clone)]
         match open_unchecked(
             &self.base,
@@ -258,7 +372,7 @@ impl<'start> Context<'start> {
                         mem::take(&mut self.reuse),
                     ) {
                         Ok(destination) => {
-                            return self.push_symlink_destination(destination);
+                            return self.push_symlink_destination(one, destination);
                         }
                         // If it isn't a symlink, handle it as normal.
                         // `readlinkat` returns `ENOENT` if the file isn't a
@@ -270,9 +384,14 @@ impl<'start> Context<'start> {
                 }
 
                 // Normal case
+                let one = self.stored_name(one);
                 let prev_base = self.base.descend_to(MaybeOwnedFile::owned(file));
                 self.dirs.push(prev_base);
-                self.canonical_path.push(one);
+                self.canonical_path.push(&one);
+                if self.track_paths {
+                    self.base_path.push(&one);
+                }
+                self.record(&one, ResolutionDecision::Descend);
 
                 Ok(())
             }
@@ -292,14 +411,23 @@ impl<'start> Context<'start> {
                 self.dir_precluded = true;
                 self.maybe_last_component_symlink(one, symlink_count, options.follow, err)
             }
-            Err(OpenUncheckedError::NotFound(err)) => Err(err),
+            Err(OpenUncheckedError::NotFound(err)) => {
+                if self.track_paths
+                    && options.report_missing_component
+                    && err.kind() == io::ErrorKind::NotFound
+                {
+                    return Err(self.missing_component(one, err));
+                }
+                Err(err)
+            }
             Err(OpenUncheckedError::Other(err)) => {
                 // An error occurred. If this was the last component, and the
                 // error wasn't due to invalid inputs (eg. the path has an
                 // embedded NUL), record it as the last component of the
                 // canonical path, even if we couldn't open it.
                 if self.components.is_empty() && err.kind() != io::ErrorKind::InvalidInput {
-                    self.canonical_path.push(one);
+                    let one = self.stored_name(one);
+                    self.canonical_path.push(&one);
                     self.canonical_path.complete();
                 }
                 Err(err)
@@ -311,11 +439,27 @@ impl<'start> Context<'start> {
     fn symlink(&mut self, one: &OsStr, symlink_count: &mut u8) -> io::Result<()> {
         let destination =
             read_link_one(&self.base, one, symlink_count, mem::take(&mut self.reuse))?;
-        self.push_symlink_destination(destination)
+        self.push_symlink_destination(one, destination)
     }
 
-    /// Push the components of `destination` onto the worklist stack.
-    fn push_symlink_destination(&mut self, destination: PathBuf) -> io::Result<()> {
+    /// Push the components of `destination`, the target of the symlink
+    /// `one`, onto the worklist stack.
+    fn push_symlink_destination(&mut self, one: &OsStr, destination: PathBuf) -> io::Result<()> {
+        self.following_last_symlink |= self.components.is_empty();
+        let one = &*self.stored_name(one);
+
+        // Forget symlinks whose targets have been fully consumed, and record
+        // this one.
+        if self.track_paths {
+            let len = self.components.len();
+            self.symlinks.retain(|(_, _, start)| *start <= len);
+            self.symlinks
+                .push((self.base_path.join(one), destination.clone(), len));
+        }
+        if let Some(trace) = &mut self.trace {
+            trace.push(ResolutionStep::follow(one.as_ref(), &destination));
+        }
+
         let trailing_slash = path_has_trailing_slash(&destination);
         let trailing_dot = path_has_trailing_dot(&destination);
         let trailing_dotdot = destination.ends_with(Component::ParentDir);
@@ -374,6 +518,94 @@ impl<'start> Context<'start> {
         Ok(())
     }
 
+    /// Construct an error saying that `one` doesn't exist, and whether it's
+    /// a parent of the final component, which it is if anything other than
+    /// `.` remains on the worklist.
+    #[cold]
+    fn missing_component(&self, one: &OsStr, err: io::Error) -> io::Error {
+        let parent = self
+            .components
+            .iter()
+            .any(|component| !matches!(component, CowComponent::CurDir));
+        errors::missing_component(err, self.base_path.join(one), parent)
+    }
+
+    /// If `err` is a `NotFound` error from resolving the target of the path's
+    /// last component, mark it with a `DanglingSymlinkError`.
+    fn flag_dangling_symlink(&self, err: io::Error) -> io::Error {
+        if self.following_last_symlink
+            && err.kind() == io::ErrorKind::NotFound
+            && DanglingSymlinkError::from_io_error(&err).is_none()
+        {
+            errors::dangling_symlink(err)
+        } else {
+            err
+        }
+    }
+
+    /// The name under which the entry `one` in `base` is stored, if the
+    /// canonical path is being computed. This differs from `one` when a
+    /// casefolded directory on Linux matched it case-insensitively.
+    fn stored_name<'one>(&self, one: &'one OsStr) -> Cow<'one, OsStr> {
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        if self.canonical_path.is_requested() {
+            if let Some(name) = casefolded_name(&self.base, one) {
+                return Cow::Owned(name);
+            }
+        }
+        Cow::Borrowed(one)
+    }
+
+    /// Record that `component` was handled with `decision`, if a trace was
+    /// requested.
+    fn record(&mut self, component: &OsStr, decision: ResolutionDecision) {
+        if let Some(trace) = &mut self.trace {
+            trace.push(ResolutionStep::new(component.as_ref(), decision));
+        }
+    }
+
+    /// Handle a prefix or root path component, by returning to the start if
+    /// it's treated as the root, or rejecting it otherwise.
+    fn root_dir(&mut self) -> io::Result<()> {
+        if !self.in_root {
+            return Err(self.reject_root());
+        }
+
+        while let Some(dir) = self.dirs.pop() {
+            self.base = dir;
+            self.canonical_path.pop();
+        }
+        self.base_path = PathBuf::new();
+        self.record(Component::RootDir.as_os_str(), ResolutionDecision::Ascend);
+
+        Ok(())
+    }
+
+    /// Reject an absolute path or symlink target.
+    #[cold]
+    fn reject_root(&mut self) -> io::Error {
+        self.record(Component::RootDir.as_os_str(), ResolutionDecision::Reject);
+        self.escape_attempt()
+    }
+
+    /// Construct an escape error, naming the symlink responsible for the
+    /// component just popped from the worklist, if any.
+    #[cold]
+    fn escape_attempt(&self) -> io::Error {
+        let len = self.components.len();
+        match self
+            .symlinks
+            .iter()
+            .rev()
+            .find(|(_, _, start)| *start <= len)
+        {
+            Some((symlink, target, _)) => {
+                errors::escape_through_symlink(symlink.clone(), target.clone())
+            }
+            None => errors::escape_attempt(),
+        }
+    }
+
     /// Check whether this is the last component and we don't need
     /// to dereference; otherwise call `Self::symlink`.
     fn maybe_last_component_symlink(
@@ -384,7 +616,8 @@ impl<'start> Context<'start> {
         err: io::Error,
     ) -> io::Result<()> {
         if follow == FollowSymlinks::No && !self.trailing_slash && self.components.is_empty() {
-            self.canonical_path.push(one);
+            let one = self.stored_name(one);
+            self.canonical_path.push(&one);
             self.canonical_path.complete();
             return Err(err);
         }
@@ -402,15 +635,19 @@ impl<'start> Context<'start> {
 /// a failure occurs before the complete canonical path is processed, the
 /// provided `&mut PathBuf` is cleared to empty.
 ///
-/// A note on lifetimes: `path` and `canonical_path` here don't strictly
-/// need `'start`, but using them makes it easier to store them in the
-/// `Context` struct.
+/// Callers can request a trace of the resolution steps by passing `Some` to
+/// `trace`.
+///
+/// A note on lifetimes: `path`, `canonical_path`, and `trace` here don't
+/// strictly need `'start`, but using them makes it easier to store them in
+/// the `Context` struct.
 pub(super) fn internal_open<'start>(
-    start: MaybeOwnedFile<'start>,
+    start: &'start fs::File,
     path: &'start Path,
     options: &OpenOptions,
     symlink_count: &mut u8,
     canonical_path: Option<&'start mut PathBuf>,
+    trace: Option<&'start mut Vec<ResolutionStep>>,
 ) -> io::Result<MaybeOwnedFile<'start>> {
     // POSIX returns `ENOENT` on an empty path. TODO: On Windows, we should
     // be compatible with what Windows does instead.
@@ -418,14 +655,90 @@ pub(super) fn internal_open<'start>(
         return Err(errors::no_such_file_or_directory());
     }
 
-    let mut ctx = Context::new(start, path, options, canonical_path);
+    let mut ctx = Context::new(
+        MaybeOwnedFile::borrowed(start),
+        path,
+        options,
+        canonical_path,
+    );
+    ctx.trace = trace;
+    let track_paths = ctx.track_paths;
+    resolve(ctx, path, options, symlink_count).map_err(|err| {
+        if track_paths {
+            err
+        } else {
+            let err = report_escape(err, start, path, options.follow);
+            report_missing_component(err, start, path, options, false)
+        }
+    })
+}
+
+/// Escapes are first reported without naming the symlink responsible, so
+/// that resolution doesn't need to track paths. If `err` is such an escape,
+/// resolve `path` again, without opening it, and tracking paths this time,
+/// to find the symlink.
+#[cold]
+fn report_escape(
+    err: io::Error,
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+) -> io::Error {
+    match EscapeError::from_io_error(&err) {
+        Some(escape) if escape.symlink().is_none() && !escape.kernel_detected() => (),
+        _ => return err,
+    }
+    match internal_stat(start, path, follow, false, true) {
+        Err(report) if EscapeError::from_io_error(&report).is_some() => report,
+        _ => err,
+    }
+}
+
+/// Likewise, missing components are first reported without naming them. If
+/// `err` is such an error and `options` asks for missing components to be
+/// reported, resolve `path` again, without opening it, to name the missing
+/// component.
+#[cold]
+fn report_missing_component(
+    err: io::Error,
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+    in_root: bool,
+) -> io::Error {
+    if !options.report_missing_component
+        || err.kind() != io::ErrorKind::NotFound
+        || MissingComponentError::from_io_error(&err).is_some()
+    {
+        return err;
+    }
+    match internal_stat(start, path, options.follow, in_root, true) {
+        Err(report)
+            if MissingComponentError::from_io_error(&report).is_some()
+                || DanglingSymlinkError::from_io_error(&report).is_some() =>
+        {
+            report
+        }
+        _ => err,
+    }
+}
 
+/// Resolve the components in `ctx`, and open the last one with `options`.
+fn resolve<'start>(
+    mut ctx: Context<'start>,
+    _path: &Path,
+    options: &OpenOptions,
+    symlink_count: &mut u8,
+) -> io::Result<MaybeOwnedFile<'start>> {
     while let Some(c) = ctx.components.pop() {
+        check_deadline()?;
         match c {
-            CowComponent::PrefixOrRootDir => return Err(errors::escape_attempt()),
+            CowComponent::PrefixOrRootDir => ctx.root_dir()?,
             CowComponent::CurDir => ctx.cur_dir()?,
             CowComponent::ParentDir => ctx.parent_dir()?,
-            CowComponent::Normal(one) => ctx.normal(&one, options, symlink_count)?,
+            CowComponent::Normal(one) => ctx
+                .normal(&one, options, symlink_count)
+                .map_err(|err| ctx.flag_dangling_symlink(err))?,
         }
     }
 
@@ -448,13 +761,33 @@ pub(super) fn internal_open<'start>(
     }
 
     #[cfg(racy_asserts)]
-    check_internal_open(&ctx, path, options);
+    check_internal_open(&ctx, _path, options);
 
     Ok(ctx.base)
 }
 
 /// Implement manual `stat` in a similar manner as manual `open`.
 pub(crate) fn stat(start: &fs::File, path: &Path, follow: FollowSymlinks) -> io::Result<Metadata> {
+    internal_stat(start, path, follow, false, false)
+        .map_err(|err| report_escape(err, start, path, follow))
+}
+
+/// Like `stat`, but resolve `path` as `open_in_root` does.
+pub(crate) fn stat_in_root(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+) -> io::Result<Metadata> {
+    internal_stat(start, lone_root_as_cur_dir(path), follow, true, false)
+}
+
+fn internal_stat(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+    in_root: bool,
+    track_paths: bool,
+) -> io::Result<Metadata> {
     // POSIX returns `ENOENT` on an empty path. TODO: On Windows, we should
     // be compatible with what Windows does instead.
     if path.as_os_str().is_empty() {
@@ -462,21 +795,31 @@ pub(crate) fn stat(start: &fs::File, path: &Path, follow: FollowSymlinks) -> io:
     }
 
     let mut options = OpenOptions::new();
-    options.follow(follow);
+    options.follow(follow).report_missing_component(track_paths);
     let mut symlink_count = 0;
     let mut ctx = Context::new(MaybeOwnedFile::borrowed(start), path, &options, None);
+    ctx.in_root = in_root;
+    ctx.track_paths = track_paths;
     assert!(!ctx.dir_precluded);
 
     while let Some(c) = ctx.components.pop() {
+        check_deadline()?;
         match c {
-            CowComponent::PrefixOrRootDir => return Err(errors::escape_attempt()),
+            CowComponent::PrefixOrRootDir => ctx.root_dir()?,
             CowComponent::CurDir => ctx.cur_dir()?,
             CowComponent::ParentDir => ctx.parent_dir()?,
             CowComponent::Normal(one) => {
                 if ctx.components.is_empty() {
                     // If this is the last component, do a non-following
                     // `stat_unchecked` on it.
-                    let stat = stat_unchecked(&ctx.base, one.as_ref(), FollowSymlinks::No)?;
+                    let stat = stat_unchecked(&ctx.base, one.as_ref(), FollowSymlinks::No)
+                        .map_err(|err| {
+                            if track_paths && err.kind() == io::ErrorKind::NotFound {
+                                ctx.flag_dangling_symlink(ctx.missing_component(&one, err))
+                            } else {
+                                err
+                            }
+                        })?;
 
                     // If we weren't asked to follow symlinks, or it wasn't a
                     // symlink, we're done.
@@ -505,7 +848,8 @@ pub(crate) fn stat(start: &fs::File, path: &Path, follow: FollowSymlinks) -> io:
                     ctx.symlink(&one, &mut symlink_count)?
                 } else {
                     // Otherwise open the path component normally.
-                    ctx.normal(&one, &options, &mut symlink_count)?
+                    ctx.normal(&one, &options, &mut symlink_count)
+                        .map_err(|err| ctx.flag_dangling_symlink(err))?
                 }
             }
         }
@@ -570,3 +914,202 @@ fn check_internal_open(ctx: &Context, path: &Path, options: &OpenOptions) {
         }
     }
 }
+
+/// Test that escapes through symlinks name the symlink and its target.
+#[cfg(not(windows))]
+#[test]
+fn open_escape_through_symlink() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path();
+    fs::create_dir_all(path.join("a/b")).unwrap();
+    std::os::unix::fs::symlink("../../..", path.join("a/b/up")).unwrap();
+    std::os::unix::fs::symlink("/etc", path.join("a/abs")).unwrap();
+    std::os::unix::fs::symlink("b/up", path.join("a/chain")).unwrap();
+    std::os::unix::fs::symlink("b", path.join("a/inside")).unwrap();
+    let dir = fs::File::open(path).unwrap();
+    let mut options = OpenOptions::new();
+    options.read(true);
+
+    let cases = [
+        ("a/b/up/x", "a/b/up", "../../.."),
+        ("a/abs", "a/abs", "/etc"),
+        // The innermost symlink is the one that escapes.
+        ("a/chain", "a/b/up", "../../.."),
+    ];
+    for (open_path, symlink, target) in &cases {
+        let err = open(&dir, Path::new(open_path), &options).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        let escape = EscapeError::from_io_error(&err).unwrap();
+        assert_eq!(escape.symlink(), Some(Path::new(symlink)));
+        assert_eq!(escape.target(), Some(Path::new(target)));
+        assert!(!escape.kernel_detected());
+        assert!(err.to_string().contains(symlink));
+        assert!(err.to_string().contains(target));
+    }
+
+    // An escape that doesn't involve symlinks has no symlink to report,
+    // including after a symlink within the sandbox has been resolved.
+    for open_path in &["..", "a/inside/../../.."] {
+        let err = open(&dir, Path::new(open_path), &options).unwrap_err();
+        let escape = EscapeError::from_io_error(&err).unwrap();
+        assert_eq!(escape.symlink(), None);
+    }
+}
+
+/// Test that in-root resolution follows symlinks before `..`, restarts
+/// absolute paths and symlink targets at the root, and keeps `..` at the
+/// root, as `RESOLVE_IN_ROOT` does.
+#[cfg(not(windows))]
+#[test]
+fn open_in_root_resolves_physically() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path();
+    fs::create_dir_all(path.join("a/b")).unwrap();
+    fs::write(path.join("x"), b"root").unwrap();
+    fs::write(path.join("a/x"), b"a").unwrap();
+    std::os::unix::fs::symlink("a/b", path.join("link")).unwrap();
+    std::os::unix::fs::symlink("/a/x", path.join("abs")).unwrap();
+    let dir = fs::File::open(path).unwrap();
+    let mut options = OpenOptions::new();
+    options.read(true);
+
+    let cases = [
+        ("link/../x", "a"),
+        ("/link/../x", "a"),
+        ("abs", "a"),
+        ("../../x", "root"),
+        ("a/../../x", "root"),
+    ];
+    for (open_path, contents) in &cases {
+        let mut file = open_in_root(&dir, Path::new(open_path), &options).unwrap();
+        let mut read = String::new();
+        io::Read::read_to_string(&mut file, &mut read).unwrap();
+        assert_eq!(read, *contents, "{}", open_path);
+
+        let metadata = stat_in_root(&dir, Path::new(open_path), FollowSymlinks::Yes).unwrap();
+        assert_eq!(metadata.len(), contents.len() as u64, "{}", open_path);
+    }
+
+    let metadata = stat_in_root(&dir, Path::new("/abs"), FollowSymlinks::No).unwrap();
+    assert!(metadata.file_type().is_symlink());
+    assert!(stat_in_root(&dir, Path::new("/"), FollowSymlinks::Yes)
+        .unwrap()
+        .is_dir());
+}
+
+/// Test that traces record each component, including symlinks and their
+/// targets.
+#[cfg(not(windows))]
+#[test]
+fn open_traced_through_symlink() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path();
+    fs::create_dir_all(path.join("a/b")).unwrap();
+    fs::write(path.join("a/b/file"), b"").unwrap();
+    std::os::unix::fs::symlink("b", path.join("a/link")).unwrap();
+    let dir = fs::File::open(path).unwrap();
+    let mut options = OpenOptions::new();
+    options.read(true);
+
+    let mut trace = Vec::new();
+    open_traced(&dir, Path::new("a/link/file"), &options, &mut trace).unwrap();
+    let steps: Vec<_> = trace
+        .iter()
+        .map(|step| (step.component(), step.symlink_target(), step.decision()))
+        .collect();
+    assert_eq!(
+        steps,
+        [
+            (Path::new("a"), None, ResolutionDecision::Descend),
+            (
+                Path::new("link"),
+                Some(Path::new("b")),
+                ResolutionDecision::Follow
+            ),
+            (Path::new("b"), None, ResolutionDecision::Descend),
+            (Path::new("file"), None, ResolutionDecision::Descend),
+        ]
+    );
+
+    let mut trace = Vec::new();
+    open_traced(&dir, Path::new("a/../.."), &options, &mut trace).unwrap_err();
+    let decisions: Vec<_> = trace.iter().map(ResolutionStep::decision).collect();
+    assert_eq!(
+        decisions,
+        [
+            ResolutionDecision::Descend,
+            ResolutionDecision::Ascend,
+            ResolutionDecision::Reject
+        ]
+    );
+}
+
+/// Test that names in a casefolded directory match case-insensitively, as
+/// they do with `openat2`. This needs a filesystem with casefolding enabled,
+/// such as ext4 made with `-O casefold`, and is skipped elsewhere.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+#[allow(unsafe_code)]
+fn open_casefolded_dir() {
+    use crate::fs::is_same_file;
+    use std::os::unix::io::AsRawFd;
+
+    /// `FS_CASEFOLD_FL` from <linux/fs.h>.
+    const FS_CASEFOLD_FL: libc::c_int = 0x4000_0000;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path();
+    fs::create_dir_all(path.join("folded")).unwrap();
+
+    // The flag can only be set on an empty directory.
+    let folded = fs::File::open(path.join("folded")).unwrap();
+    let mut flags: libc::c_int = 0;
+    // Safety: `folded` owns a valid file descriptor for the duration of the
+    // calls, and the flags are an `int`.
+    let supported = unsafe {
+        libc::ioctl(folded.as_raw_fd(), libc::FS_IOC_GETFLAGS as _, &mut flags) == 0 && {
+            flags |= FS_CASEFOLD_FL;
+            libc::ioctl(folded.as_raw_fd(), libc::FS_IOC_SETFLAGS as _, &flags) == 0
+        }
+    };
+    if !supported {
+        eprintln!("skipping open_casefolded_dir: unable to set +F");
+        return;
+    }
+
+    fs::write(path.join("folded/File.txt"), b"").unwrap();
+    let expected = fs::File::open(path.join("folded/File.txt")).unwrap();
+    let dir = fs::File::open(path).unwrap();
+    let mut options = OpenOptions::new();
+    options.read(true);
+
+    let file = open(&dir, Path::new("folded/FILE.TXT"), &options).unwrap();
+    assert!(is_same_file(&file, &expected).unwrap());
+    let file = crate::fs::open(&dir, Path::new("folded/file.txt"), &options).unwrap();
+    assert!(is_same_file(&file, &expected).unwrap());
+
+    // Only the casefolded directory itself is case-insensitive.
+    open(&dir, Path::new("FOLDED/File.txt"), &options).unwrap_err();
+
+    // Canonical paths use the names as stored, as "/proc/self/fd" does.
+    let canonical = Path::new("folded/File.txt");
+    assert_eq!(
+        super::canonicalize(&dir, Path::new("folded/FILE.TXT")).unwrap(),
+        canonical
+    );
+    assert_eq!(
+        crate::fs::canonicalize(&dir, Path::new("folded/file.txt")).unwrap(),
+        canonical
+    );
+
+    std::os::unix::fs::symlink("FILE.TXT", path.join("folded/Link")).unwrap();
+    let (path, chain) = super::canonicalize_with_chain(&dir, Path::new("folded/LINK")).unwrap();
+    assert_eq!(path, canonical);
+    assert_eq!(
+        chain,
+        vec![(
+            PathBuf::from("folded/Link"),
+            PathBuf::from("folded/FILE.TXT")
+        )]
+    );
+}
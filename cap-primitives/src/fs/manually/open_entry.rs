@@ -16,8 +16,7 @@ pub(crate) fn open_entry(
         Err(OpenUncheckedError::Symlink(_, _)) if options.follow == FollowSymlinks::Yes => {
             let mut symlink_count = 0;
             let destination = read_link_one(start, path, &mut symlink_count, PathBuf::new())?;
-            let maybe = MaybeOwnedFile::borrowed(start);
-            internal_open(maybe, &destination, options, &mut symlink_count, None)
+            internal_open(start, &destination, options, &mut symlink_count, None, None)
                 .map(MaybeOwnedFile::unwrap_owned)
         }
         Err(OpenUncheckedError::NotFound(err))
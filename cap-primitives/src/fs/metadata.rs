@@ -2,7 +2,7 @@ use crate::{
     fs::{FileType, FileTypeExt, MetadataExt, Permissions},
     time::SystemTime,
 };
-use std::{fs, io};
+use std::{fs, io, time::UNIX_EPOCH};
 
 /// Metadata information about a file.
 ///
@@ -162,6 +162,66 @@ impl Metadata {
         })
     }
 
+    /// Returns `true` if the file appears to have changed between when
+    /// `other` and `self` were obtained.
+    ///
+    /// This compares the modification times, and since some filesystems
+    /// record them with coarse resolution, also the sizes. Where the file's
+    /// identity (device and inode numbers, or volume serial number and file
+    /// index) is available in both, it is compared too, to detect the file
+    /// being replaced by another one.
+    ///
+    /// A change which leaves the size and the modification time the same,
+    /// such as an in-place rewrite within the filesystem's timestamp
+    /// granularity, can't be detected.
+    pub fn is_modified_since(&self, other: &Self) -> bool {
+        self.modified != other.modified
+            || self.len != other.len
+            || match (self.ext.maybe_file_id(), other.ext.maybe_file_id()) {
+                (Some(a), Some(b)) => a != b,
+                _ => false,
+            }
+    }
+
+    /// Returns a hash of the fields compared by
+    /// [`Metadata::is_modified_since`], for use as a cheap change-detection
+    /// key.
+    ///
+    /// Two `Metadata`s obtained the same way for which `is_modified_since`
+    /// is `false` have the same fingerprint. The hash is 64-bit FNV-1a over a
+    /// fixed encoding of the fields, so fingerprints don't change between
+    /// builds or Rust versions, and may be stored.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = Fnv1a::new();
+        match self
+            .modified
+            .map(|modified| modified.into_std().duration_since(UNIX_EPOCH))
+        {
+            None => hash.write(&[0]),
+            Some(Ok(after)) => {
+                hash.write(&[1]);
+                hash.write(&after.as_secs().to_le_bytes());
+                hash.write(&after.subsec_nanos().to_le_bytes());
+            }
+            Some(Err(before)) => {
+                let before = before.duration();
+                hash.write(&[2]);
+                hash.write(&before.as_secs().to_le_bytes());
+                hash.write(&before.subsec_nanos().to_le_bytes());
+            }
+        }
+        hash.write(&self.len.to_le_bytes());
+        match self.ext.maybe_file_id() {
+            None => hash.write(&[0]),
+            Some((dev, ino)) => {
+                hash.write(&[1]);
+                hash.write(&dev.to_le_bytes());
+                hash.write(&ino.to_le_bytes());
+            }
+        }
+        hash.0
+    }
+
     /// Determine if `self` and `other` refer to the same inode on the same device.
     #[cfg(any(not(windows), windows_by_handle))]
     pub(crate) fn is_same_file(&self, other: &Self) -> bool {
@@ -177,6 +237,37 @@ impl Metadata {
     }
 }
 
+/// The 64-bit FNV-1a hash, used by [`Metadata::fingerprint`] because, unlike
+/// `DefaultHasher`, it's specified.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(Self::PRIME);
+        }
+    }
+}
+
+#[test]
+fn fnv1a_vectors() {
+    let hash = |bytes: &[u8]| {
+        let mut hash = Fnv1a::new();
+        hash.write(bytes);
+        hash.0
+    };
+    assert_eq!(hash(b""), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+    assert_eq!(hash(b"foobar"), 0x8594_4171_f739_67e8);
+}
+
 #[cfg(unix)]
 impl std::os::unix::fs::MetadataExt for Metadata {
     #[inline]
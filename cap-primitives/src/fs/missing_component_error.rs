@@ -0,0 +1,64 @@
+use std::{
+    error, fmt, io,
+    path::{Path, PathBuf},
+};
+
+/// The error carried by `NotFound` errors from opening a path with
+/// `report_missing_component` set, saying which component of the path was
+/// missing.
+///
+/// Use [`MissingComponentError::from_io_error`] to retrieve it from an
+/// `io::Error`.
+#[derive(Debug)]
+pub struct MissingComponentError {
+    source: io::Error,
+    component: PathBuf,
+    parent: bool,
+}
+
+impl MissingComponentError {
+    /// Returns the `MissingComponentError` carried by `err`, if any.
+    pub fn from_io_error(err: &io::Error) -> Option<&Self> {
+        err.get_ref().and_then(|inner| inner.downcast_ref::<Self>())
+    }
+
+    /// Returns the path of the missing component, relative to the start of
+    /// the lookup, with any symlinks before it resolved.
+    pub fn component(&self) -> &Path {
+        &self.component
+    }
+
+    /// Returns `true` if the missing component is a parent directory of the
+    /// final component, rather than the final component itself.
+    pub fn is_parent(&self) -> bool {
+        self.parent
+    }
+
+    pub(crate) fn new(source: io::Error, component: PathBuf, parent: bool) -> Self {
+        Self {
+            source,
+            component,
+            parent,
+        }
+    }
+}
+
+impl fmt::Display for MissingComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.parent {
+            write!(
+                f,
+                "{} (parent directory {:?} doesn't exist)",
+                self.source, self.component
+            )
+        } else {
+            write!(f, "{} ({:?} doesn't exist)", self.source, self.component)
+        }
+    }
+}
+
+impl error::Error for MissingComponentError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
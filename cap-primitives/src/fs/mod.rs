@@ -4,37 +4,63 @@
 #[macro_use]
 pub(crate) mod assert_same_file;
 
+mod acl;
 mod canonicalize;
 mod copy;
+mod copy_throttled;
+mod copy_verified;
 mod create_dir;
+mod create_named_pipe;
+mod dangling_symlink_error;
+mod deadline;
+mod dedupe_range;
 mod dir_builder;
 mod dir_entry;
+mod dir_id;
 mod dir_options;
+mod escape_error;
 #[cfg(not(any(target_os = "android", target_os = "linux", windows)))]
 mod file_path_by_searching;
+mod file_lock;
 mod file_type;
+mod for_each_entry;
 mod follow_symlinks;
+mod fs_type;
 mod hard_link;
 mod is_file_read_write;
 mod maybe_owned_file;
 mod metadata;
+mod missing_component_error;
+mod move_file;
 mod open;
+mod open_and_resolve;
+mod open_child;
 mod open_dir;
+mod open_if;
+mod open_in_root;
 mod open_options;
+mod open_traced;
 mod open_unchecked_error;
+mod overwrite_policy;
 mod permissions;
+mod pipe_options;
 mod read_dir;
 mod read_link;
+mod read_only_mount;
 mod remove_dir;
 mod remove_dir_all;
 mod remove_file;
 mod remove_open_dir;
 mod rename;
 mod reopen;
+mod resolution_step;
 mod set_permissions;
+mod set_sparse;
 mod set_times;
 mod stat;
+mod storage_error_kind;
 mod symlink;
+mod symlink_target_type;
 mod system_time_spec;
 
 pub(crate) mod errors;
@@ -43,8 +69,12 @@ pub(crate) mod via_parent;
 
 use maybe_owned_file::MaybeOwnedFile;
 
+pub(crate) use deadline::check_deadline;
 #[cfg(not(any(target_os = "android", target_os = "linux", windows)))]
 pub(crate) use file_path_by_searching::file_path_by_searching;
+pub(crate) use open::{lone_root_as_cur_dir, open_with};
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) use open_if::open_then_check;
 pub(crate) use open_unchecked_error::*;
 
 #[cfg(not(windows))]
@@ -54,42 +84,69 @@ pub(crate) use super::windows::fs::*;
 
 pub(crate) use read_dir::read_dir_unchecked;
 
-pub use canonicalize::canonicalize;
-pub use copy::copy;
+pub use acl::{get_acl, set_acl, Acl, AclEntry, AclTag, ACL_EXECUTE, ACL_READ, ACL_WRITE};
+pub use canonicalize::{canonicalize, canonicalize_with_chain};
+pub use copy::{copy, copy_with_policy};
+pub use copy_throttled::copy_throttled;
+pub use copy_verified::copy_verified;
 pub use create_dir::create_dir;
+pub use create_named_pipe::create_named_pipe;
+pub use dangling_symlink_error::DanglingSymlinkError;
+pub use deadline::with_deadline;
+pub use dedupe_range::dedupe_range;
 pub use dir_builder::DirBuilder;
-pub use dir_entry::DirEntry;
+pub use dir_entry::{DirEntry, _DirEntryRawExt};
 #[cfg(windows)]
 pub use dir_entry::_WindowsDirEntryExt;
+pub use dir_id::DirId;
 pub use dir_options::DirOptions;
+pub use escape_error::EscapeError;
+pub use file_lock::{lock_file_exclusive, try_lock_file_exclusive, unlock_file};
 pub use file_type::FileType;
 #[cfg(windows)]
 pub use file_type::_WindowsFileTypeExt;
 pub use follow_symlinks::FollowSymlinks;
+pub use for_each_entry::for_each_entry;
+pub use fs_type::{filesystem_type, FsType};
 pub use hard_link::hard_link;
 pub use is_file_read_write::is_file_read_write;
 pub use metadata::Metadata;
 #[cfg(windows)]
 pub use metadata::_WindowsByHandle;
+pub use missing_component_error::MissingComponentError;
+pub use move_file::move_file;
 pub use open::open;
+pub use open_and_resolve::open_and_resolve;
+pub use open_child::open_child;
 pub use open_dir::*;
+pub use open_if::open_if;
+pub use open_in_root::{open_dir_in_root, open_in_root, stat_in_root};
 pub use open_options::OpenOptions;
+pub use open_traced::open_traced;
+pub use overwrite_policy::OverwritePolicy;
 pub use permissions::Permissions;
+pub use pipe_options::PipeOptions;
 pub use read_dir::{read_base_dir, read_dir, ReadDir};
 pub use read_link::read_link;
+pub use read_only_mount::is_read_only_mount;
 pub use remove_dir::remove_dir;
 pub use remove_dir_all::remove_dir_all;
 pub use remove_file::remove_file;
 pub use remove_open_dir::{remove_open_dir, remove_open_dir_all};
 pub use rename::rename;
 pub use reopen::reopen;
+pub use resolution_step::{ResolutionDecision, ResolutionStep};
 pub use set_permissions::set_permissions;
+pub use set_sparse::set_sparse;
 pub use set_times::{set_times, set_times_nofollow};
 pub use stat::stat;
+pub use storage_error_kind::StorageErrorKind;
 #[cfg(not(windows))]
 pub use symlink::symlink;
 #[cfg(windows)]
 pub use symlink::{symlink_dir, symlink_file};
+pub use symlink::symlink_with_type;
+pub use symlink_target_type::SymlinkTargetType;
 pub use system_time_spec::SystemTimeSpec;
 
 #[cfg(racy_asserts)]
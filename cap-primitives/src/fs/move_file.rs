@@ -0,0 +1,99 @@
+//! This defines `move_file`, a `rename` which falls back to copying when the
+//! source and destination are on different filesystems.
+
+use crate::fs::{
+    check_deadline, copy, errors, open, remove_file, rename, stat, FollowSymlinks, OpenOptions,
+};
+use std::{
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+/// Move a file from `from_path` to `to_path`, ensuring that the resolution of
+/// both paths never escape the directory tree rooted at their respective
+/// starts.
+///
+/// If both paths are on the same filesystem, this is a `rename`. Otherwise,
+/// the file is copied to a temporary name in the destination directory,
+/// synced, renamed over `to_path`, and then `from_path` is removed. This
+/// ensures that `to_path` is never observed partially written; however the
+/// move as a whole is not atomic, and if the final removal fails, the file
+/// is left at both paths.
+pub fn move_file(
+    from_start: &fs::File,
+    from_path: &Path,
+    to_start: &fs::File,
+    to_path: &Path,
+) -> io::Result<()> {
+    let err = match rename(from_start, from_path, to_start, to_path) {
+        Err(err) if errors::is_cross_device(&err) => err,
+        other => return other,
+    };
+
+    // Only regular files can be moved by copying.
+    if !stat(from_start, from_path, FollowSymlinks::No)?.is_file() {
+        return Err(err);
+    }
+
+    let tmp_path = create_temporary(to_start, to_path)?;
+    if let Err(err) = copy_and_sync(from_start, from_path, to_start, &tmp_path)
+        .and_then(|()| rename(to_start, &tmp_path, to_start, to_path))
+    {
+        remove_file(to_start, &tmp_path).ok();
+        return Err(err);
+    }
+
+    remove_file(from_start, from_path)
+}
+
+fn copy_and_sync(
+    from_start: &fs::File,
+    from_path: &Path,
+    to_start: &fs::File,
+    to_path: &Path,
+) -> io::Result<()> {
+    copy(from_start, from_path, to_start, to_path)?;
+    open(to_start, to_path, OpenOptions::new().write(true))?.sync_all()
+}
+
+/// Exclusively create a new empty file next to `path`, with a name derived
+/// from it, and return its path.
+fn create_temporary(start: &fs::File, path: &Path) -> io::Result<PathBuf> {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(errors::no_such_file_or_directory)?;
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+
+    // The number of attempts here is an arbitrarily chosen guess; collisions
+    // should only happen if some other process is using the same names.
+    for _ in 0..16 {
+        check_deadline()?;
+        let mut tmp_name = OsString::from(".");
+        tmp_name.push(file_name);
+        tmp_name.push(format!(
+            ".{}-{}.tmp",
+            process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let tmp_path = parent.join(tmp_name);
+        match open(
+            start,
+            &tmp_path,
+            OpenOptions::new().write(true).create_new(true),
+        ) {
+            Ok(_) => return Ok(tmp_path),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "too many temporary files exist",
+    ))
+}
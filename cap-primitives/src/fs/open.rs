@@ -1,21 +1,53 @@
 //! This defines `open`, the primary entrypoint to sandboxed file and directory opening.
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use crate::fs::recover_append_only;
 #[cfg(racy_asserts)]
 use crate::fs::{file_path, open_unchecked, stat_unchecked, Metadata};
 use crate::fs::{open_impl, OpenOptions};
-use std::{fs, io, path::Path};
+use std::{
+    fs, io,
+    path::{Component, Path},
+};
 
 /// Perform an `openat`-like operation, ensuring that the resolution of the path
 /// never escapes the directory tree rooted at `start`.
-#[cfg_attr(not(racy_asserts), allow(clippy::let_and_return))]
+///
+/// An empty path fails with [`io::ErrorKind::NotFound`], as it does in POSIX.
+/// A path consisting only of a root, such as `/`, refers to `start` itself,
+/// since `start` is the root of the sandbox. Any other absolute path, such as
+/// `/foo`, is an escape attempt and fails.
+///
+/// If `path` names a symlink whose target doesn't exist, the error is still
+/// [`io::ErrorKind::NotFound`], but it carries a [`DanglingSymlinkError`].
+///
+/// [`DanglingSymlinkError`]: crate::fs::DanglingSymlinkError
 #[inline]
 pub fn open(start: &fs::File, path: &Path, options: &OpenOptions) -> io::Result<fs::File> {
+    open_with(start, path, options, open_impl)
+}
+
+/// Like `open`, but open the file with `open_impl`, so that variants of
+/// `open` share its handling of the result.
+#[cfg_attr(not(racy_asserts), allow(clippy::let_and_return))]
+pub(crate) fn open_with(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+    open_impl: impl FnOnce(&fs::File, &Path, &OpenOptions) -> io::Result<fs::File>,
+) -> io::Result<fs::File> {
+    let path = lone_root_as_cur_dir(path);
+
     #[cfg(racy_asserts)]
     let stat_before = stat_unchecked(start, path, options.follow);
 
     // Call the underlying implementation.
     let result = open_impl(start, path, options);
 
+    // Explain, or work around, failures due to the file being append-only.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    let result = result.or_else(|err| recover_append_only(start, path, options, err));
+
     #[cfg(racy_asserts)]
     let stat_after = stat_unchecked(start, path, options.follow);
 
@@ -25,6 +57,18 @@ pub fn open(start: &fs::File, path: &Path, options: &OpenOptions) -> io::Result<
     result
 }
 
+/// If `path` consists of nothing but a root, such as `/`, return `.`, so
+/// that it refers to the root of the sandbox rather than the host root.
+/// Otherwise return `path` unchanged.
+pub(crate) fn lone_root_as_cur_dir(path: &Path) -> &Path {
+    let mut components = path.components();
+    if components.next() == Some(Component::RootDir) && components.next().is_none() {
+        Component::CurDir.as_os_str().as_ref()
+    } else {
+        path
+    }
+}
+
 #[cfg(racy_asserts)]
 fn check_open(
     start: &fs::File,
@@ -104,3 +148,34 @@ fn check_open(
         }
     }
 }
+
+/// Test that empty and absolute paths are handled the same way by `open`,
+/// which uses `openat2` where available, and by the manual resolver.
+#[cfg(not(windows))]
+#[test]
+fn open_empty_and_root_paths() {
+    use crate::fs::{manually, Metadata};
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path();
+    fs::create_dir_all(path.join("foo")).unwrap();
+    let dir = fs::File::open(path).unwrap();
+    let dir_metadata = Metadata::from_file(&dir).unwrap();
+    let mut options = OpenOptions::new();
+    options.read(true);
+
+    type OpenFn = fn(&fs::File, &Path, &OpenOptions) -> io::Result<fs::File>;
+
+    let strategies: [OpenFn; 2] = [open, manually::open];
+    for open in &strategies {
+        let err = open(&dir, Path::new(""), &options).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        let root = open(&dir, Path::new("/"), &options).unwrap();
+        let root_metadata = Metadata::from_file(&root).unwrap();
+        assert_eq!(root_metadata.ext.file_id(), dir_metadata.ext.file_id());
+
+        let err = open(&dir, Path::new("/foo"), &options).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    }
+}
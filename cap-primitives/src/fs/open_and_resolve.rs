@@ -0,0 +1,23 @@
+//! Sandboxed opening which also reports the canonical path of what was
+//! opened.
+
+use crate::fs::{open_and_resolve_impl, OpenOptions};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Perform an `openat`-like operation, ensuring that the resolution of the
+/// path never escapes the directory tree rooted at `start`, and also return
+/// the canonical path of the opened file, relative to `start`.
+///
+/// This avoids a separate `canonicalize` call, which would have to resolve
+/// the path again.
+#[inline]
+pub fn open_and_resolve(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+) -> io::Result<(fs::File, PathBuf)> {
+    open_and_resolve_impl(start, path, options)
+}
@@ -0,0 +1,55 @@
+//! This defines `open_child`, a variant of `open` for paths consisting of a
+//! single normal component.
+
+use crate::fs::{errors, open, OpenOptions};
+#[cfg(not(windows))]
+use crate::fs::{open_unchecked, FollowSymlinks};
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use posish::fs::OFlags;
+use std::{
+    ffi::OsStr,
+    fs, io,
+    path::{Component, Path},
+};
+
+/// Open the entry named `name` in `start`, where `name` must be a single
+/// normal path component: not empty, not `.` or `..`, not absolute, and
+/// containing no path separators. Anything else fails with
+/// [`io::ErrorKind::InvalidInput`].
+///
+/// Since a single component can't lead outside of `start` unless it's a
+/// symlink, this first tries a plain `openat` of `name` with symlinks not
+/// followed. Only if `name` turns out to be a symlink, or the open fails,
+/// does it fall back to [`open`], so the result is the same as `open` would
+/// give, without the cost of full path resolution in the common case. On
+/// Windows, `name` is checked and then opened with `open`.
+pub fn open_child(start: &fs::File, name: &OsStr, options: &OpenOptions) -> io::Result<fs::File> {
+    let path = Path::new(name);
+    let mut components = path.components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(one)), None) if one == name => (),
+        _ => return Err(errors::not_a_single_component()),
+    }
+
+    #[cfg(not(windows))]
+    if !needs_full_resolution(options) {
+        if let Ok(file) = open_unchecked(start, path, options.clone().follow(FollowSymlinks::No)) {
+            return Ok(file);
+        }
+    }
+
+    open(start, path, options)
+}
+
+/// Test whether a non-following open with `options` would open a symlink
+/// itself rather than failing, as `O_PATH` does on Linux, in which case its
+/// success doesn't tell us that `name` isn't a symlink to be followed.
+#[cfg(not(windows))]
+fn needs_full_resolution(_options: &OpenOptions) -> bool {
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    if OFlags::from_bits_truncate(_options.ext.custom_flags as _).contains(OFlags::PATH) {
+        return _options.follow == FollowSymlinks::Yes;
+    }
+
+    false
+}
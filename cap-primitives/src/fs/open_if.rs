@@ -0,0 +1,65 @@
+//! This defines `open_if`, which opens a file only if its metadata satisfies
+//! a predicate.
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use crate::fs::open_if_impl;
+use crate::fs::{errors, open, Metadata, OpenOptions};
+use std::{fs, io, path::Path};
+
+/// Open the file at `path`, relative to `start`, with `options`, but only if
+/// `predicate` returns `true` for its metadata, returning `Ok(None)` if it
+/// doesn't.
+///
+/// The metadata is read from the opened file itself, so the check and the
+/// open are from a single path resolution, and can't race with the path
+/// being replaced in between. On Linux, the file is first opened with
+/// `O_PATH`, and only reopened with `options` once the predicate passes, so
+/// opening a file which doesn't qualify has no side effects, and doesn't
+/// need it to be readable or writable. Elsewhere, the file is opened with
+/// `options`, but truncation is deferred until after the predicate passes,
+/// so a file which doesn't qualify is left untouched. Since the predicate
+/// needs an existing file, `options` may not request `create` or
+/// `create_new`; that fails with [`io::ErrorKind::InvalidInput`].
+pub fn open_if(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+    predicate: impl FnOnce(&Metadata) -> bool,
+) -> io::Result<Option<fs::File>> {
+    if options.create || options.create_new {
+        return Err(errors::open_if_creates());
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        open_if_impl(start, path, options, predicate)
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    {
+        open_then_check(start, path, options, predicate)
+    }
+}
+
+/// Open the file at `path` with `options`, except for truncation, and then
+/// truncate it if `predicate` returns `true` for its metadata, or close it
+/// and return `Ok(None)` otherwise.
+pub(crate) fn open_then_check(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+    predicate: impl FnOnce(&Metadata) -> bool,
+) -> io::Result<Option<fs::File>> {
+    let mut untruncated = options.clone();
+    untruncated.truncate(false);
+    let file = open(start, path, &untruncated)?;
+
+    if !predicate(&Metadata::from_file(&file)?) {
+        return Ok(None);
+    }
+
+    if options.truncate {
+        file.set_len(0)?;
+    }
+    Ok(Some(file))
+}
@@ -0,0 +1,32 @@
+//! Sandboxed opening which treats the starting directory as the root of the
+//! filesystem.
+
+use crate::fs::{
+    dir_options, open_in_root_impl, stat_in_root_impl, FollowSymlinks, Metadata, OpenOptions,
+};
+use std::{fs, io, path::Path};
+
+/// Perform an `openat`-like operation, treating `start` as the root of the
+/// filesystem, as a `chroot` would.
+///
+/// Absolute paths, and absolute symlink targets, are resolved relative to
+/// `start`, and `..` at `start` stays at `start` rather than failing as an
+/// escape attempt. This uses `openat2` with `RESOLVE_IN_ROOT` where
+/// available, and otherwise resolves the path manually with the same rules.
+#[inline]
+pub fn open_in_root(start: &fs::File, path: &Path, options: &OpenOptions) -> io::Result<fs::File> {
+    open_in_root_impl(start, path, options)
+}
+
+/// Like `open_in_root`, but opens `path` as a directory.
+#[inline]
+pub fn open_dir_in_root(start: &fs::File, path: &Path) -> io::Result<fs::File> {
+    open_in_root(start, path, &dir_options())
+}
+
+/// Perform an `fstatat`-like operation, resolving `path` as `open_in_root`
+/// does.
+#[inline]
+pub fn stat_in_root(start: &fs::File, path: &Path, follow: FollowSymlinks) -> io::Result<Metadata> {
+    stat_in_root_impl(start, path, follow)
+}
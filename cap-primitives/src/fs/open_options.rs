@@ -26,6 +26,12 @@ pub struct OpenOptions {
     pub(crate) dir_required: bool,
     pub(crate) maybe_dir: bool,
     pub(crate) readdir_required: bool,
+    pub(crate) append_if_append_only: bool,
+    pub(crate) drop_cache_on_close: bool,
+    pub(crate) truncate_to_written: bool,
+    pub(crate) create_parents: bool,
+    pub(crate) report_missing_component: bool,
+    pub(crate) backup_semantics: bool,
     pub(crate) follow: FollowSymlinks,
 
     #[cfg(any(unix, windows, target_os = "vxworks"))]
@@ -49,6 +55,12 @@ impl OpenOptions {
             dir_required: false,
             maybe_dir: false,
             readdir_required: false,
+            append_if_append_only: false,
+            drop_cache_on_close: false,
+            truncate_to_written: false,
+            create_parents: false,
+            report_missing_component: false,
+            backup_semantics: false,
             follow: FollowSymlinks::Yes,
 
             #[cfg(any(unix, windows, target_os = "vxworks"))]
@@ -133,6 +145,53 @@ impl OpenOptions {
         self
     }
 
+    /// Sets the option to open append-only files in append mode, rather than
+    /// failing, when write access is requested without append mode.
+    #[inline]
+    pub(crate) fn append_if_append_only(&mut self, append_if_append_only: bool) -> &mut Self {
+        self.append_if_append_only = append_if_append_only;
+        self
+    }
+
+    /// Sets the option to drop the file's pages from the page cache when the
+    /// file is closed.
+    #[inline]
+    pub(crate) fn drop_cache_on_close(&mut self, drop_cache_on_close: bool) -> &mut Self {
+        self.drop_cache_on_close = drop_cache_on_close;
+        self
+    }
+
+    /// Sets the option to truncate the file to the end of the furthest write
+    /// through it when the file is closed.
+    #[inline]
+    pub(crate) fn truncate_to_written(&mut self, truncate_to_written: bool) -> &mut Self {
+        self.truncate_to_written = truncate_to_written;
+        self
+    }
+
+    /// Sets the option to create any missing parent directories when
+    /// creating a file.
+    #[inline]
+    pub(crate) fn create_parents(&mut self, create_parents: bool) -> &mut Self {
+        self.create_parents = create_parents;
+        self
+    }
+
+    /// Sets the option to say which path component was missing in
+    /// `NotFound` errors.
+    #[inline]
+    pub(crate) fn report_missing_component(&mut self, report_missing_component: bool) -> &mut Self {
+        self.report_missing_component = report_missing_component;
+        self
+    }
+
+    /// Sets the option to open with Windows' backup semantics.
+    #[inline]
+    pub(crate) fn backup_semantics(&mut self, backup_semantics: bool) -> &mut Self {
+        self.backup_semantics = backup_semantics;
+        self
+    }
+
     /// Sets the option to request the ability to read directory entries.
     #[inline]
     pub(crate) fn readdir_required(&mut self, readdir_required: bool) -> &mut Self {
@@ -159,6 +218,139 @@ impl OpenOptions {
     pub fn _cap_fs_ext_maybe_dir(&mut self, maybe_dir: bool) -> &mut Self {
         self.maybe_dir(maybe_dir)
     }
+
+    /// Wrapper to allow `append_if_append_only` to be exposed by the
+    /// `cap-fs-ext` crate.
+    ///
+    /// This is hidden from the main API since this functionality isn't present in `std`.
+    /// Use `cap_fs_ext::OpenOptionsAppendOnlyExt` instead of calling this directly.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_fs_ext_append_if_append_only(&mut self, append_if_append_only: bool) -> &mut Self {
+        self.append_if_append_only(append_if_append_only)
+    }
+
+    /// Wrapper to allow `drop_cache_on_close` to be exposed by the
+    /// `cap-fs-ext` crate.
+    ///
+    /// This is hidden from the main API since this functionality isn't present in `std`.
+    /// Use `cap_fs_ext::OpenOptionsDropCacheExt` instead of calling this directly.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_fs_ext_drop_cache_on_close(&mut self, drop_cache_on_close: bool) -> &mut Self {
+        self.drop_cache_on_close(drop_cache_on_close)
+    }
+
+    /// Wrapper to allow `cap-std` to query whether `drop_cache_on_close` is
+    /// set, since the `File` it returns is what applies it.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_std_drops_cache_on_close(&self) -> bool {
+        self.drop_cache_on_close
+    }
+
+    /// Wrapper to allow `truncate_to_written` to be exposed by the
+    /// `cap-fs-ext` crate.
+    ///
+    /// This is hidden from the main API since this functionality isn't present in `std`.
+    /// Use `cap_fs_ext::OpenOptionsTruncateToWrittenExt` instead of calling this directly.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_fs_ext_truncate_to_written(&mut self, truncate_to_written: bool) -> &mut Self {
+        self.truncate_to_written(truncate_to_written)
+    }
+
+    /// Wrapper to allow `cap-std` to query whether `truncate_to_written` is
+    /// set, since the `File` it returns is what applies it.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_std_truncates_to_written(&self) -> bool {
+        self.truncate_to_written
+    }
+
+    /// Wrapper to allow `cap-std` to query whether the file is opened in
+    /// append mode, so that it knows where writes will go.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_std_appends(&self) -> bool {
+        self.append
+    }
+
+    /// Wrapper to allow `cap-std` to query whether the open may create the
+    /// file, for `Dir`s which disallow creating files.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_std_creates(&self) -> bool {
+        self.create || self.create_new
+    }
+
+    /// Wrapper to allow `cap-std` to query whether the file is opened for
+    /// writing, so that it can classify the access.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_std_writes(&self) -> bool {
+        self.write || self.append
+    }
+
+    /// Wrapper to allow `create_parents` to be exposed by the `cap-fs-ext`
+    /// crate.
+    ///
+    /// This is hidden from the main API since this functionality isn't present in `std`.
+    /// Use `cap_fs_ext::OpenOptionsCreateParentsExt` instead of calling this directly.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_fs_ext_create_parents(&mut self, create_parents: bool) -> &mut Self {
+        self.create_parents(create_parents)
+    }
+
+    /// Wrapper to allow `cap-std` to query whether missing parent directories
+    /// should be created, since it's `cap-std` that has `create_dir_all`.
+    /// This is only true when the open would create the file.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_std_creates_parents(&self) -> bool {
+        self.create_parents && (self.create || self.create_new)
+    }
+
+    /// Wrapper to allow `report_missing_component` to be exposed by the
+    /// `cap-fs-ext` crate.
+    ///
+    /// This is hidden from the main API since this functionality isn't present in `std`.
+    /// Use `cap_fs_ext::OpenOptionsMissingComponentExt` instead of calling this directly.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_fs_ext_report_missing_component(
+        &mut self,
+        report_missing_component: bool,
+    ) -> &mut Self {
+        self.report_missing_component(report_missing_component)
+    }
+
+    /// Wrapper to allow `backup_semantics` to be exposed by the `cap-fs-ext`
+    /// crate.
+    ///
+    /// This is hidden from the main API since this functionality isn't present in `std`.
+    /// Use `cap_fs_ext::OpenOptionsBackupSemanticsExt` instead of calling this directly.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_fs_ext_backup_semantics(&mut self, backup_semantics: bool) -> &mut Self {
+        self.backup_semantics(backup_semantics)
+    }
+
+    /// Wrapper to allow `cap-std` to apply a `Dir`'s default file mode,
+    /// which is used unless a mode has been set explicitly with
+    /// `OpenOptionsExt::mode`. This has no effect on Windows.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_std_default_mode(&mut self, mode: u32) -> &mut Self {
+        #[cfg(any(unix, target_os = "vxworks"))]
+        self.ext.default_mode(mode);
+
+        #[cfg(not(any(unix, target_os = "vxworks")))]
+        let _ = mode;
+
+        self
+    }
 }
 
 #[cfg(unix)]
@@ -0,0 +1,95 @@
+//! This defines `open_traced`, a variant of `open` which records how the
+//! path was resolved.
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use crate::fs::open_impl_or;
+use crate::fs::{
+    lone_root_as_cur_dir, manually, open_with, OpenOptions, ResolutionDecision, ResolutionStep,
+};
+use std::{fs, io, path::Path};
+
+/// Like [`open`], but also return the steps taken to resolve `path`, for
+/// diagnosing why a path was rejected.
+///
+/// The steps are returned whether or not the open succeeds. Where the
+/// kernel can resolve the path itself, as with `openat2` on Linux, the
+/// individual components aren't observed, and a single step with
+/// [`ResolutionDecision::Kernel`] is returned. Otherwise, each path
+/// component other than `.` is recorded as the manual resolver processes it,
+/// including the components of symlink targets.
+///
+/// [`open`]: crate::fs::open
+/// [`ResolutionDecision::Kernel`]: crate::fs::ResolutionDecision::Kernel
+pub fn open_traced(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+) -> (io::Result<fs::File>, Vec<ResolutionStep>) {
+    let mut trace = None;
+    let manual_open = |start: &fs::File, path: &Path, options: &OpenOptions| {
+        manually::open_traced(start, path, options, trace.get_or_insert_with(Vec::new))
+    };
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    let result = open_with(start, path, options, |start, path, options| {
+        open_impl_or(start, path, options, manual_open)
+    });
+
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    let result = open_with(start, path, options, manual_open);
+
+    // If the manual resolver wasn't used, the kernel resolved the path.
+    let trace = trace.unwrap_or_else(|| {
+        let path = lone_root_as_cur_dir(path);
+        vec![ResolutionStep::new(path, ResolutionDecision::Kernel)]
+    });
+    (result, trace)
+}
+
+/// Test that when `openat2` is unavailable, each step is recorded, and that
+/// the result is handled as `open` handles it.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn open_traced_without_openat2() {
+    use crate::fs::{deny_openat2_on_this_thread, DanglingSymlinkError};
+
+    let tmp = tempfile::tempdir().unwrap();
+    fs::create_dir_all(tmp.path().join("a/b")).unwrap();
+    fs::write(tmp.path().join("a/b/file"), b"").unwrap();
+    std::os::unix::fs::symlink("b", tmp.path().join("a/link")).unwrap();
+    std::os::unix::fs::symlink("missing", tmp.path().join("a/dangling")).unwrap();
+    let start = fs::File::open(tmp.path()).unwrap();
+
+    // Seccomp filters can't be removed, so install it on a thread of its own.
+    std::thread::spawn(move || {
+        let mut options = OpenOptions::new();
+        options.read(true);
+
+        // With `openat2`, if it's available, the kernel resolves the path.
+        let (result, _trace) = open_traced(&start, Path::new("a/dangling"), &options);
+        assert!(DanglingSymlinkError::from_io_error(&result.unwrap_err()).is_some());
+
+        if !deny_openat2_on_this_thread() {
+            return;
+        }
+
+        let (result, trace) = open_traced(&start, Path::new("a/link/file"), &options);
+        result.unwrap();
+        let decisions: Vec<_> = trace.iter().map(ResolutionStep::decision).collect();
+        assert_eq!(
+            decisions,
+            [
+                ResolutionDecision::Descend,
+                ResolutionDecision::Follow,
+                ResolutionDecision::Descend,
+                ResolutionDecision::Descend,
+            ]
+        );
+
+        let (result, trace) = open_traced(&start, Path::new("a/dangling"), &options);
+        assert!(DanglingSymlinkError::from_io_error(&result.unwrap_err()).is_some());
+        assert_eq!(trace[1].symlink_target(), Some(Path::new("missing")));
+    })
+    .join()
+    .unwrap();
+}
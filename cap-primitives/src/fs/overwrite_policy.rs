@@ -0,0 +1,20 @@
+/// What should a copy do if its destination already exists?
+///
+/// This is used by `copy_with_policy`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum OverwritePolicy {
+    /// Replace the contents of the destination.
+    Overwrite,
+
+    /// Leave the destination as it is, and succeed without copying.
+    Skip,
+
+    /// Fail with [`std::io::ErrorKind::AlreadyExists`].
+    Error,
+
+    /// Replace the contents of the destination only if the source was
+    /// modified more recently than the destination; otherwise leave it as
+    /// it is and succeed without copying.
+    OverwriteIfNewer,
+}
@@ -0,0 +1,73 @@
+/// Options which can be used to configure how a named pipe is created,
+/// for use with [`create_named_pipe`].
+///
+/// [`create_named_pipe`]: crate::fs::create_named_pipe
+#[derive(Debug, Clone)]
+pub struct PipeOptions {
+    pub(crate) read: bool,
+    pub(crate) write: bool,
+    pub(crate) max_instances: u32,
+    #[cfg(unix)]
+    pub(crate) mode: u32,
+}
+
+impl PipeOptions {
+    /// Creates a blank new set of options ready for configuration.
+    ///
+    /// At least one of `read` and `write` must be set before the options
+    /// are used.
+    #[allow(clippy::new_without_default)]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            read: false,
+            write: false,
+            max_instances: 1,
+            #[cfg(unix)]
+            mode: 0o666,
+        }
+    }
+
+    /// Sets the option for reading from the pipe through the returned file.
+    #[inline]
+    pub fn read(&mut self, read: bool) -> &mut Self {
+        self.read = read;
+        self
+    }
+
+    /// Sets the option for writing to the pipe through the returned file.
+    #[inline]
+    pub fn write(&mut self, write: bool) -> &mut Self {
+        self.write = write;
+        self
+    }
+
+    /// Sets the maximum number of server instances of the pipe that may
+    /// exist at once. The default is 1.
+    ///
+    /// On Windows, this must be between 1 and 255, where 255 means there's
+    /// no limit. Other platforms don't support multiple instances, so any
+    /// value other than 1 causes creation to fail.
+    #[inline]
+    pub fn max_instances(&mut self, max_instances: u32) -> &mut Self {
+        self.max_instances = max_instances;
+        self
+    }
+
+    /// Wrapper to allow `cap-std` to apply a `Dir`'s default file mode,
+    /// which is otherwise 0o666, before the umask. This has no effect on
+    /// Windows.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_std_default_mode(&mut self, mode: u32) -> &mut Self {
+        #[cfg(unix)]
+        {
+            self.mode = mode;
+        }
+
+        #[cfg(not(unix))]
+        let _ = mode;
+
+        self
+    }
+}
@@ -0,0 +1,11 @@
+use crate::fs::is_read_only_mount_impl;
+use std::{fs, io};
+
+/// Test whether the filesystem that `file` is on is mounted read-only.
+///
+/// This uses `fstatvfs`'s `ST_RDONLY` flag on Unix-family platforms, and
+/// the volume's `FILE_READ_ONLY_VOLUME` flag on Windows.
+#[inline]
+pub fn is_read_only_mount(file: &fs::File) -> io::Result<bool> {
+    is_read_only_mount_impl(file)
+}
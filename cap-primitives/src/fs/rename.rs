@@ -16,6 +16,10 @@ use {
 /// Perform a `renameat`-like operation, ensuring that the resolution of both
 /// the old and new paths never escape the directory tree rooted at their
 /// respective starts.
+///
+/// If the two paths are on different filesystems, this fails with the OS's
+/// cross-device error (`EXDEV` on Unix-family platforms); see `move_file` for
+/// a variant which falls back to copying.
 #[cfg_attr(not(racy_asserts), allow(clippy::let_and_return))]
 #[inline]
 pub fn rename(
@@ -0,0 +1,74 @@
+use std::path::{Path, PathBuf};
+
+/// What the resolver decided to do with a path component, as recorded in a
+/// [`ResolutionStep`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ResolutionDecision {
+    /// The component named a directory, or the final file, which was opened.
+    Descend,
+
+    /// The component was `..`, and resolution moved up to the parent.
+    Ascend,
+
+    /// The component was a symlink, and resolution continued with its
+    /// target.
+    Follow,
+
+    /// The component would have led outside of the filesystem, so
+    /// resolution stopped with an escape error.
+    Reject,
+
+    /// The whole path was resolved by the kernel, as with `openat2` on
+    /// Linux, so individual components weren't observed.
+    Kernel,
+}
+
+/// One step taken while resolving a path, as recorded by [`open_traced`].
+///
+/// [`open_traced`]: crate::fs::open_traced
+#[derive(Clone, Debug)]
+pub struct ResolutionStep {
+    component: PathBuf,
+    symlink_target: Option<PathBuf>,
+    decision: ResolutionDecision,
+}
+
+impl ResolutionStep {
+    /// Returns the path component this step processed. For
+    /// [`ResolutionDecision::Kernel`] steps, this is the whole path.
+    pub fn component(&self) -> &Path {
+        &self.component
+    }
+
+    /// Returns `true` if the component was a symlink which was followed.
+    pub fn is_symlink(&self) -> bool {
+        self.symlink_target.is_some()
+    }
+
+    /// Returns the raw target of the symlink, if the component was a symlink
+    /// which was followed.
+    pub fn symlink_target(&self) -> Option<&Path> {
+        self.symlink_target.as_deref()
+    }
+
+    /// Returns what the resolver decided to do with the component.
+    pub fn decision(&self) -> ResolutionDecision {
+        self.decision
+    }
+
+    pub(crate) fn new(component: &Path, decision: ResolutionDecision) -> Self {
+        Self {
+            component: component.to_path_buf(),
+            symlink_target: None,
+            decision,
+        }
+    }
+
+    pub(crate) fn follow(component: &Path, target: &Path) -> Self {
+        Self {
+            component: component.to_path_buf(),
+            symlink_target: Some(target.to_path_buf()),
+            decision: ResolutionDecision::Follow,
+        }
+    }
+}
@@ -0,0 +1,26 @@
+#[cfg(windows)]
+use crate::fs::set_sparse_impl;
+use std::{fs, io};
+
+/// Set whether `file` is sparse, so that ranges of zeros, such as holes
+/// punched with `FSCTL_SET_ZERO_DATA`, don't need to be allocated.
+///
+/// On Windows, files aren't sparse unless they're marked so, and this uses
+/// `FSCTL_SET_SPARSE`, which needs `file` to be open for writing. Clearing
+/// the flag allocates any unallocated ranges, and fails if there isn't
+/// enough space to do so. On Unix-family platforms, filesystems which
+/// support sparse files treat every file as potentially sparse, so this does
+/// nothing.
+#[inline]
+pub fn set_sparse(file: &fs::File, sparse: bool) -> io::Result<()> {
+    #[cfg(windows)]
+    {
+        set_sparse_impl(file, sparse)
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = (file, sparse);
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use std::io;
+#[cfg(windows)]
+use winapi::shared::winerror::{
+    ERROR_DISK_FULL, ERROR_DISK_QUOTA_EXCEEDED, ERROR_HANDLE_DISK_FULL,
+};
+
+/// Which kind of space a filesystem ran out of, for errors from creating or
+/// writing files.
+///
+/// `std::io::ErrorKind` doesn't distinguish these on stable Rust, so the
+/// errors are left with their usual kinds, and this classifies them by the
+/// OS error code they carry.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum StorageErrorKind {
+    /// The filesystem is full: `ENOSPC`, or `ERROR_DISK_FULL` or
+    /// `ERROR_HANDLE_DISK_FULL` on Windows.
+    StorageFull,
+
+    /// A user or group quota on the filesystem is exhausted, although the
+    /// filesystem itself may have space left: `EDQUOT`, or
+    /// `ERROR_DISK_QUOTA_EXCEEDED` on Windows.
+    QuotaExceeded,
+}
+
+impl StorageErrorKind {
+    /// Classify `err`, returning `None` if it isn't an out-of-space error.
+    pub fn from_io_error(err: &io::Error) -> Option<Self> {
+        #[cfg(not(windows))]
+        {
+            use posish::io::Errno;
+
+            match Errno::from_io_error(err) {
+                Some(Errno::NOSPC) => Some(Self::StorageFull),
+                Some(Errno::DQUOT) => Some(Self::QuotaExceeded),
+                _ => None,
+            }
+        }
+
+        #[cfg(windows)]
+        {
+            match err.raw_os_error().map(|code| code as u32) {
+                Some(ERROR_DISK_FULL) | Some(ERROR_HANDLE_DISK_FULL) => Some(Self::StorageFull),
+                Some(ERROR_DISK_QUOTA_EXCEEDED) => Some(Self::QuotaExceeded),
+                _ => None,
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@
 use crate::fs::errors;
 #[cfg(all(racy_asserts, not(windows)))]
 use crate::fs::symlink_unchecked;
+use crate::fs::SymlinkTargetType;
 #[cfg(racy_asserts)]
 use crate::fs::{canonicalize, manually, map_result, stat_unchecked, FollowSymlinks, Metadata};
 #[cfg(all(racy_asserts, windows))]
@@ -118,6 +119,59 @@ pub fn symlink_dir(old_path: &Path, new_start: &fs::File, new_path: &Path) -> io
     result
 }
 
+/// Perform a `symlink`-like operation with an explicit hint about what kind
+/// of object the target is, ensuring that the resolution of the path never
+/// escapes the directory tree rooted at `start`.
+///
+/// Only Windows distinguishes file and directory symlinks, so on other
+/// platforms the hint is ignored.
+#[cfg(not(windows))]
+#[inline]
+pub fn symlink_with_type(
+    old_path: &Path,
+    new_start: &fs::File,
+    new_path: &Path,
+    _target_type: SymlinkTargetType,
+) -> io::Result<()> {
+    symlink(old_path, new_start, new_path)
+}
+
+/// Perform a `symlink`-like operation with an explicit hint about what kind
+/// of object the target is, ensuring that the resolution of the path never
+/// escapes the directory tree rooted at `start`.
+///
+/// On Windows, `SymlinkTargetType::File` and `SymlinkTargetType::Dir` select
+/// `symlink_file` or `symlink_dir` regardless of whether the target exists,
+/// and `SymlinkTargetType::Auto` stats the target, relative to the link's
+/// parent directory, to decide.
+#[cfg(windows)]
+pub fn symlink_with_type(
+    old_path: &Path,
+    new_start: &fs::File,
+    new_path: &Path,
+    target_type: SymlinkTargetType,
+) -> io::Result<()> {
+    let is_dir = match target_type {
+        SymlinkTargetType::File => false,
+        SymlinkTargetType::Dir => true,
+        SymlinkTargetType::Auto => {
+            // Resolve the target the way the link will: relative to the
+            // directory containing it. If it can't be resolved, including
+            // because it doesn't exist yet, fall back to a file symlink.
+            let target = new_path.parent().unwrap_or(Path::new("")).join(old_path);
+            crate::fs::stat(new_start, &target, crate::fs::FollowSymlinks::Yes)
+                .map(|metadata| metadata.is_dir())
+                .unwrap_or(false)
+        }
+    };
+
+    if is_dir {
+        symlink_dir(old_path, new_start, new_path)
+    } else {
+        symlink_file(old_path, new_start, new_path)
+    }
+}
+
 #[cfg(all(not(windows), racy_asserts))]
 #[allow(clippy::enum_glob_use)]
 fn check_symlink(
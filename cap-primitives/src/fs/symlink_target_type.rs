@@ -0,0 +1,18 @@
+/// What kind of object a symlink's target is expected to be.
+///
+/// On Windows, a symlink must declare whether its target is a file or a
+/// directory when it's created. This is used by `symlink_with_type`; on
+/// other platforms, it's ignored.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum SymlinkTargetType {
+    /// Create a file symlink, whether or not the target exists.
+    File,
+
+    /// Create a directory symlink, whether or not the target exists.
+    Dir,
+
+    /// Stat the target to decide, creating a file symlink if it isn't a
+    /// directory or doesn't exist.
+    Auto,
+}
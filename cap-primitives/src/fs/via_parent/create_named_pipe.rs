@@ -0,0 +1,18 @@
+use super::open_parent;
+use crate::fs::{create_named_pipe_unchecked, MaybeOwnedFile, PipeOptions};
+use std::{fs, io, path::Path};
+
+/// Implement `create_named_pipe` by `open`ing up the parent component of the
+/// path and then calling `create_named_pipe_unchecked` on the last
+/// component.
+pub(crate) fn create_named_pipe(
+    start: &fs::File,
+    path: &Path,
+    options: &PipeOptions,
+) -> io::Result<fs::File> {
+    let start = MaybeOwnedFile::borrowed(start);
+
+    let (dir, basename) = open_parent(start, path)?;
+
+    create_named_pipe_unchecked(&dir, basename.as_ref(), options)
+}
@@ -3,6 +3,7 @@
 //! rest of the components just name the place to create it in.
 
 mod create_dir;
+mod create_named_pipe;
 mod hard_link;
 mod open_parent;
 #[cfg(not(windows))] // doesn't work on windows; use a windows-specific impl
@@ -19,6 +20,7 @@ mod symlink;
 use open_parent::open_parent;
 
 pub(crate) use create_dir::create_dir;
+pub(crate) use create_named_pipe::create_named_pipe;
 pub(crate) use hard_link::hard_link;
 #[cfg(not(windows))] // doesn't work on windows; use a windows-specific impl
 pub(crate) use read_link::read_link;
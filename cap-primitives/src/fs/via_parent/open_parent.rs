@@ -14,12 +14,24 @@ use std::{
 /// the single remaining path component. This last component will not be `..`,
 /// though it may be `.` or a symbolic link to anywhere (possibly
 /// including `..` or an absolute path).
+///
+/// The parent is only used as the directory argument of a `*at` call, so
+/// it's opened with `open_dir`'s options, which use `O_PATH` on platforms
+/// which have it. That way no read access to the parent, or to the
+/// directories leading to it, is needed beyond searching them.
 pub(super) fn open_parent<'path, 'borrow>(
     start: MaybeOwnedFile<'borrow>,
     path: &'path Path,
 ) -> io::Result<(MaybeOwnedFile<'borrow>, &'path OsStr)> {
     let (dirname, basename) = split_parent(path).ok_or_else(errors::no_such_file_or_directory)?;
 
+    // `open` treats a lone `/` as `start` itself, but the `via_parent`
+    // operations create, remove, or rename names, and neither `/` nor any
+    // name under it is within the sandbox.
+    if dirname.has_root() {
+        return Err(errors::escape_attempt());
+    }
+
     let dir = if dirname.as_os_str().is_empty() {
         start
     } else {
@@ -4,6 +4,12 @@ use std::{fs, io, path::Path};
 
 /// Implement `rename` by `open`ing up the parent component of the path and then
 /// calling `rename_unchecked` on the last component.
+///
+/// `old_path` is resolved within `old_start` and `new_path` within
+/// `new_start`, so the two may be in different sandboxes. The final
+/// `renameat` still requires both parents to be on the same filesystem; if
+/// they aren't, the cross-device error from the OS is returned unchanged so
+/// that callers such as `move_file` can detect it and fall back to copying.
 pub(crate) fn rename(
     old_start: &fs::File,
     old_path: &Path,
@@ -0,0 +1,70 @@
+use crate::fs::{errors, PipeOptions};
+use std::{fs, io, path::Path};
+
+/// *Unsandboxed* function similar to `create_named_pipe`, but which does not
+/// perform sandboxing.
+#[cfg(not(target_os = "wasi"))]
+#[allow(unsafe_code)]
+pub(crate) fn create_named_pipe_unchecked(
+    start: &fs::File,
+    path: &Path,
+    options: &PipeOptions,
+) -> io::Result<fs::File> {
+    use crate::fs::{open_unchecked, remove_file_unchecked, FollowSymlinks, OpenOptions};
+    use posish::fs::{fcntl_getfl, fcntl_setfl, OFlags};
+    use std::os::unix::{fs::OpenOptionsExt, io::AsRawFd};
+
+    if options.max_instances != 1 {
+        return Err(errors::pipe_instances_unsupported());
+    }
+
+    let c_path = super::c_str(path)?;
+
+    // posish doesn't wrap `mkfifoat` or `mknodat` yet, so call libc
+    // directly.
+    //
+    // Safety: `start` owns a valid file descriptor for the duration of the
+    // call, and `c_path` is a NUL-terminated string.
+    let mode = options.mode as libc::mode_t;
+    if unsafe { libc::mkfifoat(start.as_raw_fd(), c_path.as_ptr(), mode) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // Open without waiting for a peer, and then switch back to blocking
+    // mode so that the file behaves like one opened normally.
+    let file = open_unchecked(
+        start,
+        path,
+        OpenOptions::new()
+            .read(options.read)
+            .write(options.write)
+            .follow(FollowSymlinks::No)
+            .custom_flags(OFlags::NONBLOCK.bits() as _),
+    )
+    .map_err(io::Error::from)
+    .and_then(|file| {
+        fcntl_setfl(&file, fcntl_getfl(&file)? & !OFlags::NONBLOCK)?;
+        Ok(file)
+    });
+
+    match file {
+        Ok(file) => Ok(file),
+        Err(err) => {
+            // Don't leave a FIFO behind that the caller doesn't know about.
+            remove_file_unchecked(start, path).ok();
+            Err(err)
+        }
+    }
+}
+
+#[cfg(target_os = "wasi")]
+pub(crate) fn create_named_pipe_unchecked(
+    _start: &fs::File,
+    _path: &Path,
+    _options: &PipeOptions,
+) -> io::Result<fs::File> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "named pipes are not supported on this platform",
+    ))
+}
@@ -1,9 +1,15 @@
 use crate::fs::{FileType, FileTypeExt, Metadata, OpenOptions, ReadDir, ReadDirInner};
 use posish::fs::Entry;
 #[cfg(unix)]
-use std::os::unix::{ffi::OsStrExt, fs::MetadataExt};
+use std::os::unix::{
+    ffi::OsStrExt,
+    fs::{DirEntryExt, FileTypeExt as _, MetadataExt},
+};
 #[cfg(target_os = "wasi")]
-use std::os::wasi::{ffi::OsStrExt, fs::MetadataExt};
+use std::os::wasi::{
+    ffi::OsStrExt,
+    fs::{DirEntryExt, FileTypeExt as _, MetadataExt},
+};
 use std::{
     ffi::{OsStr, OsString},
     fmt, fs, io,
@@ -43,16 +49,7 @@ impl DirEntryInner {
     #[inline]
     #[allow(clippy::unnecessary_wraps)]
     pub(crate) fn file_type(&self) -> io::Result<FileType> {
-        Ok(match self.posish.file_type() {
-            posish::fs::FileType::Directory => FileType::dir(),
-            posish::fs::FileType::RegularFile => FileType::file(),
-            posish::fs::FileType::Symlink => FileType::ext(FileTypeExt::symlink()),
-            posish::fs::FileType::Fifo => FileType::ext(FileTypeExt::fifo()),
-            posish::fs::FileType::Socket => FileType::ext(FileTypeExt::socket()),
-            posish::fs::FileType::CharacterDevice => FileType::ext(FileTypeExt::char_device()),
-            posish::fs::FileType::BlockDevice => FileType::ext(FileTypeExt::block_device()),
-            posish::fs::FileType::Unknown => FileType::unknown(),
-        })
+        Ok(FileTypeExt::from_posish(self.posish.file_type()))
     }
 
     #[inline]
@@ -65,6 +62,28 @@ impl DirEntryInner {
         self.posish.ino()
     }
 
+    #[inline]
+    pub(crate) fn d_type_raw(&self) -> u32 {
+        u32::from(match self.posish.file_type() {
+            posish::fs::FileType::Directory => libc::DT_DIR,
+            posish::fs::FileType::RegularFile => libc::DT_REG,
+            posish::fs::FileType::Symlink => libc::DT_LNK,
+            posish::fs::FileType::Fifo => libc::DT_FIFO,
+            posish::fs::FileType::Socket => libc::DT_SOCK,
+            posish::fs::FileType::CharacterDevice => libc::DT_CHR,
+            posish::fs::FileType::BlockDevice => libc::DT_BLK,
+            posish::fs::FileType::Unknown => libc::DT_UNKNOWN,
+        })
+    }
+
+    #[inline]
+    pub(crate) fn file_type_cached(&self) -> Option<FileType> {
+        match self.posish.file_type() {
+            posish::fs::FileType::Unknown => None,
+            _ => self.file_type().ok(),
+        }
+    }
+
     #[inline]
     pub(crate) fn is_same_file(&self, metadata: &Metadata) -> io::Result<bool> {
         Ok(self.ino() == metadata.ino() && self.metadata()?.dev() == metadata.dev())
@@ -81,3 +100,40 @@ impl fmt::Debug for DirEntryInner {
         f.debug_tuple("DirEntry").field(&self.file_name()).finish()
     }
 }
+
+/// `std::fs::DirEntry` doesn't say whether its file type came from the
+/// directory stream or from a `stat`, so `d_type_raw` reconstructs the value
+/// from the file type, and `file_type_cached` never claims a cached type.
+#[doc(hidden)]
+impl crate::fs::_DirEntryRawExt for fs::DirEntry {
+    #[inline]
+    fn ino(&self) -> io::Result<u64> {
+        Ok(DirEntryExt::ino(self))
+    }
+
+    fn d_type_raw(&self) -> io::Result<u32> {
+        let file_type = self.file_type()?;
+        Ok(u32::from(if file_type.is_dir() {
+            libc::DT_DIR
+        } else if file_type.is_file() {
+            libc::DT_REG
+        } else if file_type.is_symlink() {
+            libc::DT_LNK
+        } else if file_type.is_fifo() {
+            libc::DT_FIFO
+        } else if file_type.is_socket() {
+            libc::DT_SOCK
+        } else if file_type.is_char_device() {
+            libc::DT_CHR
+        } else if file_type.is_block_device() {
+            libc::DT_BLK
+        } else {
+            libc::DT_UNKNOWN
+        }))
+    }
+
+    #[inline]
+    fn file_type_cached(&self) -> Option<FileType> {
+        None
+    }
+}
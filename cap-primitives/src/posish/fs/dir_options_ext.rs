@@ -1,6 +1,7 @@
 #[derive(Debug, Clone)]
 pub(crate) struct DirOptionsExt {
     pub(super) mode: u32,
+    mode_set: bool,
 }
 
 impl DirOptionsExt {
@@ -9,13 +10,23 @@ impl DirOptionsExt {
             // The default value; see
             // <https://doc.rust-lang.org/std/os/unix/fs/trait.DirBuilderExt.html#tymethod.mode>
             mode: 0o777,
+            mode_set: false,
         }
     }
+
+    /// Set the mode to `mode`, unless a mode has been set explicitly.
+    pub(crate) fn default_mode(&mut self, mode: u32) -> &mut Self {
+        if !self.mode_set {
+            self.mode = mode;
+        }
+        self
+    }
 }
 
 impl std::os::unix::fs::DirBuilderExt for DirOptionsExt {
     fn mode(&mut self, mode: u32) -> &mut Self {
         self.mode = mode;
+        self.mode_set = true;
         self
     }
 }
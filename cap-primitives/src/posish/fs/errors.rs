@@ -25,3 +25,9 @@ pub(crate) fn is_not_directory() -> io::Error {
 pub(crate) fn too_many_symlinks() -> io::Error {
     Errno::LOOP.io_error()
 }
+
+/// Test whether `err` indicates an attempt to link or rename across
+/// filesystems.
+pub(crate) fn is_cross_device(err: &io::Error) -> bool {
+    Errno::from_io_error(err) == Some(Errno::XDEV)
+}
@@ -0,0 +1,48 @@
+use std::{fs, io};
+
+#[inline]
+pub(crate) fn lock_file_exclusive_impl(file: &fs::File) -> io::Result<()> {
+    flock(file, libc::LOCK_EX)
+}
+
+pub(crate) fn try_lock_file_exclusive_impl(file: &fs::File) -> io::Result<bool> {
+    match flock(file, libc::LOCK_EX | libc::LOCK_NB) {
+        Ok(()) => Ok(true),
+        Err(err) if err.raw_os_error() == Some(libc::EWOULDBLOCK) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+#[inline]
+pub(crate) fn unlock_file_impl(file: &fs::File) -> io::Result<()> {
+    flock(file, libc::LOCK_UN)
+}
+
+/// Call `flock`, retrying on `EINTR`.
+///
+/// posish doesn't wrap `flock` yet, so this calls libc directly.
+#[cfg(not(target_os = "wasi"))]
+#[allow(unsafe_code)]
+fn flock(file: &fs::File, operation: libc::c_int) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    loop {
+        // Safety: `file` owns a valid file descriptor for the duration of
+        // the call.
+        if unsafe { libc::flock(file.as_raw_fd(), operation) } == 0 {
+            return Ok(());
+        }
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+}
+
+#[cfg(target_os = "wasi")]
+fn flock(_file: &fs::File, _operation: libc::c_int) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "file locking is not supported on this platform",
+    ))
+}
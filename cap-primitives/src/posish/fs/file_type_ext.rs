@@ -57,7 +57,14 @@ impl FileTypeExt {
     /// [`RawMode`].
     #[inline]
     pub(crate) const fn from_raw_mode(mode: RawMode) -> FileType {
-        match posish::fs::FileType::from_raw_mode(mode) {
+        Self::from_posish(posish::fs::FileType::from_raw_mode(mode))
+    }
+
+    /// Constructs a new instance of `FileType` from the given
+    /// [`posish::fs::FileType`].
+    #[inline]
+    pub(crate) const fn from_posish(posish: posish::fs::FileType) -> FileType {
+        match posish {
             posish::fs::FileType::RegularFile => FileType::file(),
             posish::fs::FileType::Directory => FileType::dir(),
             posish::fs::FileType::Symlink => FileType::ext(Self::symlink()),
@@ -0,0 +1,62 @@
+use crate::fs::FsType;
+use std::{fs, io};
+
+// Filesystem magic numbers, from <linux/magic.h> and the `statfs` man page.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod magic {
+    pub(super) const NFS_SUPER_MAGIC: u64 = 0x6969;
+    pub(super) const SMB_SUPER_MAGIC: u64 = 0x517b;
+    pub(super) const CIFS_MAGIC_NUMBER: u64 = 0xff53_4d42;
+    pub(super) const SMB2_MAGIC_NUMBER: u64 = 0xfe53_4d42;
+    pub(super) const FUSE_SUPER_MAGIC: u64 = 0x6573_5546;
+    pub(super) const TMPFS_MAGIC: u64 = 0x0102_1994;
+    pub(super) const EXT4_SUPER_MAGIC: u64 = 0xef53;
+    pub(super) const XFS_SUPER_MAGIC: u64 = 0x5846_5342;
+    pub(super) const BTRFS_SUPER_MAGIC: u64 = 0x9123_683e;
+    pub(super) const ZFS_SUPER_MAGIC: u64 = 0x2fc1_2fc1;
+    pub(super) const OVERLAYFS_SUPER_MAGIC: u64 = 0x794c_7630;
+    pub(super) const NTFS_SB_MAGIC: u64 = 0x5346_544e;
+    pub(super) const MSDOS_SUPER_MAGIC: u64 = 0x4d44;
+    pub(super) const EXFAT_SUPER_MAGIC: u64 = 0x2011_bab0;
+}
+
+#[cfg(not(any(target_os = "netbsd", target_os = "redox", target_os = "wasi")))]
+pub(crate) fn filesystem_type_impl(file: &fs::File) -> io::Result<FsType> {
+    // `f_type` is signed on some platforms; the magic numbers are 32-bit, so
+    // truncate it to avoid sign extension.
+    #[allow(clippy::unnecessary_cast)]
+    let f_type = posish::fs::fstatfs(file)?.f_type as u32 as u64;
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        use magic::*;
+
+        Ok(match f_type {
+            NFS_SUPER_MAGIC => FsType::Nfs,
+            SMB_SUPER_MAGIC | CIFS_MAGIC_NUMBER | SMB2_MAGIC_NUMBER => FsType::Smb,
+            FUSE_SUPER_MAGIC => FsType::Fuse,
+            TMPFS_MAGIC => FsType::Tmpfs,
+            EXT4_SUPER_MAGIC => FsType::Ext,
+            XFS_SUPER_MAGIC => FsType::Xfs,
+            BTRFS_SUPER_MAGIC => FsType::Btrfs,
+            ZFS_SUPER_MAGIC => FsType::Zfs,
+            OVERLAYFS_SUPER_MAGIC => FsType::Overlay,
+            NTFS_SB_MAGIC => FsType::Ntfs,
+            MSDOS_SUPER_MAGIC | EXFAT_SUPER_MAGIC => FsType::Fat,
+            other => FsType::Other(other),
+        })
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    {
+        Ok(FsType::Other(f_type))
+    }
+}
+
+#[cfg(any(target_os = "netbsd", target_os = "redox", target_os = "wasi"))]
+pub(crate) fn filesystem_type_impl(_file: &fs::File) -> io::Result<FsType> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "filesystem types are not supported on this platform",
+    ))
+}
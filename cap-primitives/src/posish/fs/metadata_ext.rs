@@ -192,6 +192,17 @@ impl MetadataExt {
     pub(crate) const fn is_same_file(&self, other: &Self) -> bool {
         self.dev == other.dev && self.ino == other.ino
     }
+
+    /// Return the device and inode numbers, which together identify a file.
+    pub(crate) const fn file_id(&self) -> (u64, u64) {
+        (self.dev, self.ino)
+    }
+
+    /// Like `file_id`, but returns `None` where the identity isn't available.
+    /// It's always available on Unix-family platforms.
+    pub(crate) const fn maybe_file_id(&self) -> Option<(u64, u64)> {
+        Some(self.file_id())
+    }
 }
 
 #[allow(clippy::similar_names)]
@@ -1,12 +1,15 @@
 mod c_str;
 mod copy_impl;
 mod create_dir_unchecked;
+mod create_named_pipe_unchecked;
 mod dir_entry_inner;
 mod dir_options_ext;
 mod dir_utils;
 #[cfg(not(any(target_os = "android", target_os = "linux")))]
 mod file_path;
+mod file_lock_impl;
 mod file_type_ext;
+mod fs_type_impl;
 mod hard_link_unchecked;
 mod is_file_read_write_impl;
 mod is_root_dir;
@@ -18,6 +21,7 @@ mod open_unchecked;
 mod permissions_ext;
 mod read_dir_inner;
 mod read_link_unchecked;
+mod read_only_mount_impl;
 mod remove_dir_all_impl;
 mod remove_dir_unchecked;
 mod remove_file_unchecked;
@@ -50,6 +54,9 @@ pub(crate) use crate::posish::linux::fs::*;
 pub(crate) use crate::fs::{
     manually::open_entry as open_entry_impl,
     manually::open as open_impl,
+    manually::open_and_resolve as open_and_resolve_impl,
+    manually::open_in_root as open_in_root_impl,
+    manually::stat_in_root as stat_in_root_impl,
     manually::stat as stat_impl,
     manually::canonicalize as canonicalize_impl,
     via_parent::set_times_nofollow as set_times_nofollow_impl,
@@ -82,10 +89,15 @@ pub(crate) use crate::fs::{
 pub(crate) use c_str::c_str;
 pub(crate) use copy_impl::copy_impl;
 pub(crate) use create_dir_unchecked::create_dir_unchecked;
+pub(crate) use create_named_pipe_unchecked::create_named_pipe_unchecked;
 pub(crate) use dir_entry_inner::DirEntryInner;
 pub(crate) use dir_options_ext::DirOptionsExt;
 pub(crate) use dir_utils::*;
+pub(crate) use file_lock_impl::{
+    lock_file_exclusive_impl, try_lock_file_exclusive_impl, unlock_file_impl,
+};
 pub(crate) use file_type_ext::FileTypeExt;
+pub(crate) use fs_type_impl::filesystem_type_impl;
 pub(crate) use hard_link_unchecked::hard_link_unchecked;
 pub(crate) use is_file_read_write_impl::is_file_read_write_impl;
 pub(crate) use is_root_dir::is_root_dir;
@@ -95,8 +107,9 @@ pub(crate) use metadata_ext::MetadataExt;
 pub(crate) use open_options_ext::OpenOptionsExt;
 pub(crate) use open_unchecked::open_unchecked;
 pub(crate) use permissions_ext::PermissionsExt;
-pub(crate) use read_dir_inner::ReadDirInner;
+pub(crate) use read_dir_inner::{for_each_entry_impl, ReadDirInner};
 pub(crate) use read_link_unchecked::read_link_unchecked;
+pub(crate) use read_only_mount_impl::is_read_only_mount_impl;
 pub(crate) use remove_dir_all_impl::{remove_dir_all_impl, remove_open_dir_all_impl};
 pub(crate) use remove_dir_unchecked::remove_dir_unchecked;
 pub(crate) use remove_file_unchecked::remove_file_unchecked;
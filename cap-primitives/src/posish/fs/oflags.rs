@@ -19,12 +19,31 @@ pub(in super::super) fn compute_oflags(options: &OpenOptions) -> io::Result<OFla
             oflags |= target_o_path();
         }
     }
+    // On 32-bit Linux, `openat` and `openat2` don't imply `O_LARGEFILE` as
+    // they do on 64-bit platforms, and we call them directly rather than
+    // through a libc which would add it for us, so add it here, so that
+    // files larger than 2 GiB can be opened.
+    #[cfg(all(
+        any(target_os = "android", target_os = "linux"),
+        target_pointer_width = "32"
+    ))]
+    {
+        oflags |= O_LARGEFILE;
+    }
     // Use `RWMODE` here instead of `ACCMODE` so that we preserve the `O_PATH` flag.
     oflags |= OFlags::from_bits(options.ext.custom_flags as _).expect("unrecognized OFlags")
         & !OFlags::RWMODE;
     Ok(oflags)
 }
 
+/// `O_LARGEFILE`, which posish doesn't define yet.
+#[cfg(all(
+    any(target_os = "android", target_os = "linux"),
+    target_pointer_width = "32"
+))]
+#[allow(unsafe_code)]
+const O_LARGEFILE: OFlags = unsafe { OFlags::from_bits_unchecked(libc::O_LARGEFILE as _) };
+
 // `OpenOptions` translation code derived from Rust's
 // library/std/src/sys/unix/fs.rs at revision
 // 108e90ca78f052c0c1c49c42a22c85620be19712.
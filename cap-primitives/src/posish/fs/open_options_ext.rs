@@ -1,6 +1,7 @@
 #[derive(Debug, Clone)]
 pub(crate) struct OpenOptionsExt {
     pub(crate) mode: u32,
+    pub(crate) mode_set: bool,
     pub(crate) custom_flags: i32,
 }
 
@@ -8,12 +9,22 @@ impl OpenOptionsExt {
     pub(crate) const fn new() -> Self {
         Self {
             mode: 0o666,
+            mode_set: false,
             custom_flags: 0,
         }
     }
 
     pub(crate) fn mode(&mut self, mode: u32) -> &mut Self {
         self.mode = mode;
+        self.mode_set = true;
+        self
+    }
+
+    /// Set the mode to `mode`, unless a mode has been set explicitly.
+    pub(crate) fn default_mode(&mut self, mode: u32) -> &mut Self {
+        if !self.mode_set {
+            self.mode = mode;
+        }
         self
     }
 
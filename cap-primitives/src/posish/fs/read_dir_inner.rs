@@ -1,7 +1,7 @@
 use crate::fs::{
     open_dir_for_reading, open_dir_for_reading_unchecked, open_entry_impl, read_dir_unchecked,
-    remove_dir_unchecked, remove_file_unchecked, stat_unchecked, DirEntryInner, FollowSymlinks,
-    Metadata, OpenOptions, ReadDir,
+    remove_dir_unchecked, remove_file_unchecked, stat_unchecked, DirEntryInner, FileType,
+    FileTypeExt, FollowSymlinks, Metadata, OpenOptions, ReadDir,
 };
 use posish::fs::Dir;
 #[cfg(unix)]
@@ -12,6 +12,7 @@ use std::{
     ffi::OsStr,
     fmt, fs, io,
     mem::ManuallyDrop,
+    ops::ControlFlow,
     path::{Component, Path},
     sync::Arc,
     sync::Mutex,
@@ -87,6 +88,33 @@ impl ReadDirInner {
     }
 }
 
+/// Call `f` with the name and type of each entry in `start`, reading the
+/// directory stream directly rather than constructing `DirEntryInner`s.
+pub(crate) fn for_each_entry_impl(
+    start: &fs::File,
+    mut f: impl FnMut(&OsStr, FileType) -> ControlFlow<()>,
+) -> io::Result<()> {
+    // As in `read_base_dir`, open "." to get an independent position.
+    let mut dir = Dir::from(open_dir_for_reading_unchecked(
+        start,
+        Component::CurDir.as_ref(),
+    )?)?;
+    while let Some(entry) = dir.read() {
+        let entry = entry?;
+        let file_name = entry.file_name().to_bytes();
+        if file_name == Component::CurDir.as_os_str().as_bytes()
+            || file_name == Component::ParentDir.as_os_str().as_bytes()
+        {
+            continue;
+        }
+        let file_type = FileTypeExt::from_posish(entry.file_type());
+        if let ControlFlow::Break(()) = f(OsStr::from_bytes(file_name), file_type) {
+            break;
+        }
+    }
+    Ok(())
+}
+
 impl Iterator for ReadDirInner {
     type Item = io::Result<DirEntryInner>;
 
@@ -0,0 +1,27 @@
+use std::{fs, io};
+
+#[cfg(not(any(target_os = "redox", target_os = "wasi")))]
+#[allow(unsafe_code)]
+pub(crate) fn is_read_only_mount_impl(file: &fs::File) -> io::Result<bool> {
+    use std::{mem::MaybeUninit, os::unix::io::AsRawFd};
+
+    let mut buf = MaybeUninit::<libc::statvfs>::uninit();
+
+    // Safety: `file` owns a valid file descriptor for the duration of the
+    // call, and `buf` is a `statvfs` for `fstatvfs` to fill in.
+    let buf = unsafe {
+        if libc::fstatvfs(file.as_raw_fd(), buf.as_mut_ptr()) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        buf.assume_init()
+    };
+
+    // `f_flag` and `ST_RDONLY` have different types on some platforms.
+    #[allow(clippy::unnecessary_cast)]
+    Ok(buf.f_flag as u64 & libc::ST_RDONLY as u64 != 0)
+}
+
+#[cfg(any(target_os = "redox", target_os = "wasi"))]
+pub(crate) fn is_read_only_mount_impl(_file: &fs::File) -> io::Result<bool> {
+    Err(crate::fs::errors::mount_flags_unsupported())
+}
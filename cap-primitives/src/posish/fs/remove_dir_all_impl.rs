@@ -1,6 +1,6 @@
 use crate::fs::{
-    read_dir, read_dir_unchecked, remove_dir, remove_file, remove_open_dir, stat, FollowSymlinks,
-    ReadDir,
+    errors, read_dir, read_dir_unchecked, remove_dir, remove_file, remove_open_dir, stat,
+    FollowSymlinks, ReadDir,
 };
 use std::{
     fs, io,
@@ -15,24 +15,40 @@ pub(crate) fn remove_dir_all_impl(start: &fs::File, path: &Path) -> io::Result<(
     if filetype.is_symlink() {
         remove_file(start, path)
     } else {
-        remove_dir_all_recursive(read_dir(start, path)?)?;
+        remove_dir_all_recursive(read_dir(start, path)?, &mut Vec::new())?;
         remove_dir(start, path)
     }
 }
 
 pub(crate) fn remove_open_dir_all_impl(dir: fs::File) -> io::Result<()> {
-    remove_dir_all_recursive(read_dir_unchecked(
-        &dir,
-        Component::CurDir.as_os_str().as_ref(),
-    )?)?;
+    remove_dir_all_recursive(
+        read_dir_unchecked(&dir, Component::CurDir.as_os_str().as_ref())?,
+        &mut Vec::new(),
+    )?;
     remove_open_dir(dir)
 }
 
-fn remove_dir_all_recursive(children: ReadDir) -> io::Result<()> {
+/// Remove the contents of `children`. `ancestors` holds the identities of
+/// the directories we're currently inside; symlinks aren't followed, but
+/// some filesystems permit hard links to directories, which could otherwise
+/// form a cycle, so fail if we ever enter one of our own ancestors.
+fn remove_dir_all_recursive(children: ReadDir, ancestors: &mut Vec<(u64, u64)>) -> io::Result<()> {
+    let id = children.inner.self_metadata()?.ext.file_id();
+    if ancestors.contains(&id) {
+        return Err(errors::directory_cycle());
+    }
+
+    ancestors.push(id);
+    let result = remove_children(children, ancestors);
+    ancestors.pop();
+    result
+}
+
+fn remove_children(children: ReadDir, ancestors: &mut Vec<(u64, u64)>) -> io::Result<()> {
     for child in children {
         let child = child?;
         if child.file_type()?.is_dir() {
-            remove_dir_all_recursive(child.read_dir()?)?;
+            remove_dir_all_recursive(child.read_dir()?, ancestors)?;
             child.remove_dir()?;
         } else {
             child.remove_file()?;
@@ -40,3 +56,29 @@ fn remove_dir_all_recursive(children: ReadDir) -> io::Result<()> {
     }
     Ok(())
 }
+
+/// Test that revisiting a directory trips the cycle check, rather than
+/// removing anything. Real cycles need hard-linked directories, which most
+/// filesystems don't support, so simulate one by pre-populating `ancestors`.
+#[test]
+fn remove_dir_all_cycle() {
+    use crate::fs::Metadata;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path();
+    fs::create_dir_all(path.join("sub")).unwrap();
+    fs::write(path.join("sub/file"), b"").unwrap();
+    let dir = fs::File::open(path).unwrap();
+    let sub = fs::File::open(path.join("sub")).unwrap();
+
+    let mut ancestors = vec![Metadata::from_file(&sub).unwrap().ext.file_id()];
+    let err = remove_dir_all_recursive(
+        read_dir_unchecked(&dir, Component::CurDir.as_os_str().as_ref()).unwrap(),
+        &mut ancestors,
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), errors::directory_cycle().to_string());
+    assert!(path.join("sub/file").exists());
+    assert_eq!(ancestors.len(), 1);
+}
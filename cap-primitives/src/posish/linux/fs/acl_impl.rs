@@ -0,0 +1,236 @@
+//! posish doesn't wrap the xattr functions yet, so this calls libc directly.
+
+use super::procfs::reopen_through_proc_self_fd;
+use crate::fs::{open, Acl, AclEntry, AclTag, OpenOptions};
+use posish::{fs::OFlags, io::Errno};
+use std::{
+    ffi::CStr,
+    fs, io,
+    os::unix::{
+        fs::{MetadataExt, OpenOptionsExt},
+        io::AsRawFd,
+    },
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering::Relaxed},
+};
+
+/// The extended attribute holding a file's access ACL.
+const ACL_ACCESS_XATTR: &[u8] = b"system.posix_acl_access\0";
+
+/// The version in the header of the extended attribute encoding.
+const ACL_XATTR_VERSION: u32 = 2;
+
+// Tag values in the extended attribute encoding, from <linux/posix_acl.h>.
+const ACL_USER_OBJ: u16 = 0x01;
+const ACL_USER: u16 = 0x02;
+const ACL_GROUP_OBJ: u16 = 0x04;
+const ACL_GROUP: u16 = 0x08;
+const ACL_MASK: u16 = 0x10;
+const ACL_OTHER: u16 = 0x20;
+
+/// The id stored in entries which don't name a user or group.
+const ACL_UNDEFINED_ID: u32 = u32::MAX;
+
+/// Each entry is a 16-bit tag, 16-bit permissions, and a 32-bit id, after a
+/// 32-bit version header, all little-endian.
+const HEADER_SIZE: usize = 4;
+const ENTRY_SIZE: usize = 8;
+
+pub(crate) fn get_acl_impl(file: &fs::File) -> io::Result<Acl> {
+    let bytes = match read_acl_xattr(file) {
+        // If it fails with `EBADF`, `file` may be an `O_PATH` file
+        // descriptor, which `fgetxattr` doesn't accept, so reopen it through
+        // `/proc`.
+        Err(err) if Errno::from_io_error(&err) == Some(Errno::BADF) => {
+            read_acl_xattr(&reopen_through_proc_self_fd(file)?)?
+        }
+        result => result?,
+    };
+
+    match bytes {
+        Some(bytes) => decode(&bytes),
+        None => Ok(Acl::from_mode(file.metadata()?.mode())),
+    }
+}
+
+#[allow(clippy::unnecessary_cast)]
+pub(crate) fn set_acl_impl(start: &fs::File, path: &Path, acl: &Acl) -> io::Result<()> {
+    // Record whether we've seen an `EBADF` from an `fsetxattr` on an `O_PATH`
+    // file descriptor, meaning we're on a Linux that doesn't support it.
+    static FSETXATTR_PATH_BADF: AtomicBool = AtomicBool::new(false);
+
+    // Setting an ACL only requires owning the file, not being able to read
+    // or write it, so open it with `O_PATH`. Use `read(true)` even though we
+    // don't need `read` permissions, because Rust's libstd requires an
+    // access mode, and Linux ignores `O_RDONLY` with `O_PATH`.
+    let file = open(
+        start,
+        path,
+        OpenOptions::new()
+            .read(true)
+            .custom_flags(OFlags::PATH.bits() as i32),
+    )?;
+    let bytes = encode(acl);
+
+    if !FSETXATTR_PATH_BADF.load(Relaxed) {
+        match write_acl_xattr(&file, &bytes) {
+            // If it fails with `EBADF`, `fsetxattr` didn't like `O_PATH`, so
+            // reopen the file through `/proc`, which does need `read`
+            // permissions.
+            Err(err) if Errno::from_io_error(&err) == Some(Errno::BADF) => {
+                FSETXATTR_PATH_BADF.store(true, Relaxed)
+            }
+            result => return result,
+        }
+    }
+
+    write_acl_xattr(&reopen_through_proc_self_fd(&file)?, &bytes)
+}
+
+/// Read the ACL extended attribute of `file`, or `None` if it doesn't have
+/// one.
+#[allow(unsafe_code)]
+fn read_acl_xattr(file: &fs::File) -> io::Result<Option<Vec<u8>>> {
+    let name = CStr::from_bytes_with_nul(ACL_ACCESS_XATTR).unwrap();
+    loop {
+        // Safety: `file` owns a valid file descriptor for the duration of
+        // the call, and a null buffer of length 0 asks for the size.
+        let size =
+            unsafe { libc::fgetxattr(file.as_raw_fd(), name.as_ptr(), std::ptr::null_mut(), 0) };
+        if size < 0 {
+            return xattr_error(io::Error::last_os_error());
+        }
+
+        let mut buf = vec![0_u8; size as usize];
+
+        // Safety: `buf` is a buffer of the length we pass.
+        let len = unsafe {
+            libc::fgetxattr(
+                file.as_raw_fd(),
+                name.as_ptr(),
+                buf.as_mut_ptr().cast(),
+                buf.len(),
+            )
+        };
+        if len < 0 {
+            let err = io::Error::last_os_error();
+            // The attribute grew between the calls; try again.
+            if Errno::from_io_error(&err) == Some(Errno::RANGE) {
+                continue;
+            }
+            return xattr_error(err);
+        }
+
+        buf.truncate(len as usize);
+        return Ok(Some(buf));
+    }
+}
+
+/// Map a missing attribute to `None`.
+fn xattr_error(err: io::Error) -> io::Result<Option<Vec<u8>>> {
+    match Errno::from_io_error(&err) {
+        Some(Errno::NODATA) => Ok(None),
+        _ => Err(err),
+    }
+}
+
+#[allow(unsafe_code)]
+fn write_acl_xattr(file: &fs::File, bytes: &[u8]) -> io::Result<()> {
+    let name = CStr::from_bytes_with_nul(ACL_ACCESS_XATTR).unwrap();
+
+    // Safety: `file` owns a valid file descriptor for the duration of the
+    // call, and `bytes` is a buffer of the length we pass.
+    let result = unsafe {
+        libc::fsetxattr(
+            file.as_raw_fd(),
+            name.as_ptr(),
+            bytes.as_ptr().cast(),
+            bytes.len(),
+            0,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn encode(acl: &Acl) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + ENTRY_SIZE * acl.entries().len());
+    bytes.extend_from_slice(&ACL_XATTR_VERSION.to_le_bytes());
+
+    // `Acl` keeps its entries sorted in the order the kernel requires.
+    for entry in acl.entries() {
+        let (tag, id) = match entry.tag() {
+            AclTag::UserObj => (ACL_USER_OBJ, ACL_UNDEFINED_ID),
+            AclTag::User(uid) => (ACL_USER, uid),
+            AclTag::GroupObj => (ACL_GROUP_OBJ, ACL_UNDEFINED_ID),
+            AclTag::Group(gid) => (ACL_GROUP, gid),
+            AclTag::Mask => (ACL_MASK, ACL_UNDEFINED_ID),
+            AclTag::Other => (ACL_OTHER, ACL_UNDEFINED_ID),
+        };
+        bytes.extend_from_slice(&tag.to_le_bytes());
+        bytes.extend_from_slice(&entry.perms().to_le_bytes());
+        bytes.extend_from_slice(&id.to_le_bytes());
+    }
+
+    bytes
+}
+
+fn decode(bytes: &[u8]) -> io::Result<Acl> {
+    if bytes.len() < HEADER_SIZE || u32_at(bytes, 0) != ACL_XATTR_VERSION {
+        return Err(invalid_acl());
+    }
+    let entries = bytes[HEADER_SIZE..].chunks_exact(ENTRY_SIZE);
+    if !entries.remainder().is_empty() {
+        return Err(invalid_acl());
+    }
+
+    let mut acl = Acl::new();
+    for entry in entries {
+        let id = u32_at(entry, 4);
+        let tag = match u16_at(entry, 0) {
+            ACL_USER_OBJ => AclTag::UserObj,
+            ACL_USER => AclTag::User(id),
+            ACL_GROUP_OBJ => AclTag::GroupObj,
+            ACL_GROUP => AclTag::Group(id),
+            ACL_MASK => AclTag::Mask,
+            ACL_OTHER => AclTag::Other,
+            _ => return Err(invalid_acl()),
+        };
+        acl.push(AclEntry::new(tag, u16_at(entry, 2)));
+    }
+    Ok(acl)
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+#[cold]
+fn invalid_acl() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "the file's ACL extended attribute is malformed",
+    )
+}
+
+#[test]
+fn acl_round_trip() {
+    let mut acl = Acl::from_mode(0o640);
+    acl.push(AclEntry::new(AclTag::Group(1234), 0o4));
+    acl.push(AclEntry::new(AclTag::User(42), 0o6));
+    acl.push(AclEntry::new(AclTag::Mask, 0o6));
+
+    let bytes = encode(&acl);
+    assert_eq!(bytes.len(), HEADER_SIZE + 6 * ENTRY_SIZE);
+    assert_eq!(u16_at(&bytes, HEADER_SIZE), ACL_USER_OBJ);
+    assert_eq!(u16_at(&bytes, HEADER_SIZE + ENTRY_SIZE), ACL_USER);
+    assert_eq!(decode(&bytes).unwrap(), acl);
+
+    assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+}
@@ -0,0 +1,55 @@
+use super::open_impl;
+use crate::fs::{errors, OpenOptions};
+use posish::io::Errno;
+use std::{fs, io, os::unix::io::AsRawFd, path::Path};
+
+/// `FS_APPEND_FL`, which libc doesn't define.
+const FS_APPEND_FL: libc::c_int = 0x20;
+
+/// Opening an append-only file for writing without `O_APPEND` fails with a
+/// bare `EPERM`. If that's why opening `path` failed with `err`, either
+/// retry with `O_APPEND`, if `options` allows it, or return an error which
+/// says so.
+pub(crate) fn recover_append_only(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+    err: io::Error,
+) -> io::Result<fs::File> {
+    if !options.write || options.append || Errno::from_io_error(&err) != Some(Errno::PERM) {
+        return Err(err);
+    }
+    match is_append_only(start, path, options) {
+        Ok(true) => (),
+        _ => return Err(err),
+    }
+
+    // An append-only file can't be truncated, so don't quietly drop that
+    // part of the request.
+    if options.append_if_append_only && !options.truncate {
+        return open_impl(start, path, options.clone().append(true));
+    }
+
+    Err(errors::append_only())
+}
+
+fn is_append_only(start: &fs::File, path: &Path, options: &OpenOptions) -> io::Result<bool> {
+    let file = open_impl(
+        start,
+        path,
+        OpenOptions::new().read(true).follow(options.follow),
+    )?;
+    let mut flags: libc::c_int = 0;
+
+    // posish doesn't wrap `FS_IOC_GETFLAGS`, or `ioctl` in general, yet, so
+    // call libc directly.
+    //
+    // Safety: `file` owns a valid file descriptor for the duration of the
+    // call, and `FS_IOC_GETFLAGS` writes an `int` to its argument.
+    #[allow(unsafe_code)]
+    let result = unsafe { libc::ioctl(file.as_raw_fd(), libc::FS_IOC_GETFLAGS, &mut flags) };
+    if result == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(flags & FS_APPEND_FL != 0)
+}
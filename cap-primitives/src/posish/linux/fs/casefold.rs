@@ -0,0 +1,79 @@
+//! Recovering the stored names of entries in casefolded directories.
+//!
+//! Lookups in a directory with `FS_CASEFOLD_FL` set match names
+//! case-insensitively, so a path may spell an entry's name differently from
+//! how it's stored. The kernel reports the stored name, for example in
+//! "/proc/self/fd", so canonical paths we compute ourselves need it too.
+
+use crate::fs::open_dir_for_reading_unchecked;
+use posish::fs::{statat, AtFlags, Dir};
+use std::{
+    ffi::{OsStr, OsString},
+    fs, io,
+    os::unix::{ffi::OsStrExt, io::AsRawFd},
+    path::Component,
+};
+
+/// `FS_CASEFOLD_FL`, which libc doesn't define.
+const FS_CASEFOLD_FL: libc::c_int = 0x4000_0000;
+
+/// If `dir` is casefolded, and `name` matches an entry in it which is stored
+/// under a different name, return the stored name.
+///
+/// This needs to be able to read `dir`; if anything fails along the way,
+/// this returns `None` and `name` is used as is.
+pub(crate) fn casefolded_name(dir: &fs::File, name: &OsStr) -> Option<OsString> {
+    let reading = open_dir_for_reading_unchecked(dir, Component::CurDir.as_ref()).ok()?;
+    if !is_casefolded(&reading).ok()? {
+        return None;
+    }
+
+    // Don't match names ourselves; ask the kernel which inode `name` is, and
+    // find the entries with that inode.
+    let stat = statat(dir, name, AtFlags::SYMLINK_NOFOLLOW).ok()?;
+    let mut candidates = Vec::new();
+    let mut entries = Dir::from(reading).ok()?;
+    while let Some(entry) = entries.read() {
+        let entry = entry.ok()?;
+        let stored = OsStr::from_bytes(entry.file_name().to_bytes());
+        if entry.ino() != stat.st_ino as u64
+            || stored == Component::CurDir.as_os_str()
+            || stored == Component::ParentDir.as_os_str()
+        {
+            continue;
+        }
+        if stored == name {
+            return None;
+        }
+        candidates.push(stored.to_os_string());
+    }
+
+    // Hard links within the directory share an inode. Prefer the one which
+    // matches `name` ignoring case, which is right at least for ASCII.
+    let lower = name.to_string_lossy().to_lowercase();
+    let position = candidates
+        .iter()
+        .position(|stored| stored.to_string_lossy().to_lowercase() == lower)
+        .unwrap_or(0);
+    if position < candidates.len() {
+        Some(candidates.swap_remove(position))
+    } else {
+        None
+    }
+}
+
+fn is_casefolded(dir: &fs::File) -> io::Result<bool> {
+    let mut flags: libc::c_int = 0;
+
+    // posish doesn't wrap `FS_IOC_GETFLAGS`, or `ioctl` in general, yet, so
+    // call libc directly.
+    //
+    // Safety: `dir` owns a valid file descriptor for the duration of the
+    // call, and `FS_IOC_GETFLAGS` writes an `int` to its argument.
+    #[allow(unsafe_code)]
+    let result = unsafe { libc::ioctl(dir.as_raw_fd(), libc::FS_IOC_GETFLAGS, &mut flags) };
+    if result == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(flags & FS_CASEFOLD_FL != 0)
+}
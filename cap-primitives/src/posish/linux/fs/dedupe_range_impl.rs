@@ -0,0 +1,124 @@
+use std::{fs, io, mem::size_of, os::unix::io::AsRawFd};
+
+/// `FIDEDUPERANGE` from <linux/fs.h>, which is
+/// `_IOWR(0x94, 54, struct file_dedupe_range)`, which libc doesn't define.
+/// `struct file_dedupe_range` ends in a flexible array of destinations,
+/// which doesn't count towards its size.
+const FIDEDUPERANGE: u32 = iowr(
+    0x94,
+    54,
+    size_of::<FileDedupeRange>() - size_of::<[FileDedupeRangeInfo; 1]>(),
+);
+
+/// `_IOWR(ty, nr, size)` from <asm-generic/ioctl.h>. Architectures differ in
+/// how many bits they use for the direction and the size, but on all of
+/// them, `_IOC_READ | _IOC_WRITE` sets the top two bits, and sizes under
+/// 8 KiB are encoded the same way.
+const fn iowr(ty: u8, nr: u8, size: usize) -> u32 {
+    (3 << 30) | ((size as u32) << 16) | ((ty as u32) << 8) | nr as u32
+}
+
+/// Values of `FileDedupeRangeInfo::status`, besides negated errno values.
+const FILE_DEDUPE_RANGE_SAME: i32 = 0;
+const FILE_DEDUPE_RANGE_DIFFERS: i32 = 1;
+
+/// `struct file_dedupe_range`, with room for a single destination.
+#[repr(C)]
+struct FileDedupeRange {
+    src_offset: u64,
+    src_length: u64,
+    dest_count: u16,
+    reserved1: u16,
+    reserved2: u32,
+    info: [FileDedupeRangeInfo; 1],
+}
+
+/// `struct file_dedupe_range_info`.
+#[repr(C)]
+struct FileDedupeRangeInfo {
+    dest_fd: i64,
+    dest_offset: u64,
+    bytes_deduped: u64,
+    status: i32,
+    reserved: u32,
+}
+
+pub(crate) fn dedupe_range_impl(
+    dst: &fs::File,
+    src: &fs::File,
+    mut src_offset: u64,
+    mut len: u64,
+    mut dst_offset: u64,
+) -> io::Result<bool> {
+    // Filesystems may deduplicate less than was asked for in one call, so
+    // keep going until the whole range is done.
+    while len != 0 {
+        let deduped = dedupe_once(dst, src, src_offset, len, dst_offset)?;
+        match deduped {
+            None => return Ok(false),
+            // The range ends at the end of a file.
+            Some(0) => break,
+            Some(n) => {
+                let n = n.min(len);
+                src_offset += n;
+                dst_offset += n;
+                len -= n;
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// Perform one `FIDEDUPERANGE` call, returning the number of bytes
+/// deduplicated, or `None` if the ranges differ.
+#[allow(unsafe_code)]
+fn dedupe_once(
+    dst: &fs::File,
+    src: &fs::File,
+    src_offset: u64,
+    len: u64,
+    dst_offset: u64,
+) -> io::Result<Option<u64>> {
+    let mut range = FileDedupeRange {
+        src_offset,
+        src_length: len,
+        dest_count: 1,
+        reserved1: 0,
+        reserved2: 0,
+        info: [FileDedupeRangeInfo {
+            dest_fd: dst.as_raw_fd().into(),
+            dest_offset: dst_offset,
+            bytes_deduped: 0,
+            status: 0,
+            reserved: 0,
+        }],
+    };
+
+    // posish doesn't wrap `ioctl` yet, so call libc directly.
+    //
+    // Safety: `src` and `dst` own valid file descriptors for the duration of
+    // the call, and `range` is a `file_dedupe_range` with `dest_count`
+    // entries.
+    let result = unsafe { libc::ioctl(src.as_raw_fd(), FIDEDUPERANGE as _, &mut range) };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let info = &range.info[0];
+    match info.status {
+        FILE_DEDUPE_RANGE_SAME => Ok(Some(info.bytes_deduped)),
+        FILE_DEDUPE_RANGE_DIFFERS => Ok(None),
+        status if status < 0 => Err(io::Error::from_raw_os_error(-status)),
+        status => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unexpected FIDEDUPERANGE status {}", status),
+        )),
+    }
+}
+
+#[test]
+fn fideduperange() {
+    // The value from <linux/fs.h>.
+    assert_eq!(FIDEDUPERANGE, 0xc018_9436);
+    assert_eq!(size_of::<FileDedupeRangeInfo>(), 32);
+}
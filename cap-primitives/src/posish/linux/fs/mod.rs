@@ -1,8 +1,14 @@
+mod acl_impl;
+mod append_only;
 mod canonicalize_impl;
+mod casefold;
+mod dedupe_range_impl;
 mod ensure_cloexec;
 mod file_metadata;
 mod file_path;
+mod open_and_resolve_impl;
 mod open_entry_impl;
+mod open_if_impl;
 mod open_impl;
 mod procfs;
 mod set_permissions_impl;
@@ -10,14 +16,22 @@ mod set_times_impl;
 mod stat_impl;
 
 pub(crate) use crate::fs::via_parent::set_times_nofollow as set_times_nofollow_impl;
+pub(crate) use acl_impl::{get_acl_impl, set_acl_impl};
+pub(crate) use append_only::recover_append_only;
 pub(crate) use canonicalize_impl::canonicalize_impl;
+pub(crate) use casefold::casefolded_name;
+pub(crate) use dedupe_range_impl::dedupe_range_impl;
 pub(crate) use ensure_cloexec::ensure_cloexec;
 pub(crate) use file_path::file_path;
+pub(crate) use open_and_resolve_impl::open_and_resolve_impl;
 pub(crate) use open_entry_impl::open_entry_impl;
-pub(crate) use open_impl::{open_beneath, open_impl};
+pub(crate) use open_if_impl::open_if_impl;
+#[cfg(test)]
+pub(crate) use open_impl::deny_openat2_on_this_thread;
+pub(crate) use open_impl::{open_beneath, open_impl, open_impl_or, open_in_root_impl};
 pub(crate) use set_permissions_impl::set_permissions_impl;
 pub(crate) use set_times_impl::set_times_impl;
-pub(crate) use stat_impl::stat_impl;
+pub(crate) use stat_impl::{stat_impl, stat_in_root_impl};
 
 use file_metadata::file_metadata;
 
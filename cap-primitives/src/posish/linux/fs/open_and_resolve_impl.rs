@@ -0,0 +1,55 @@
+//! Opening and resolving a path, using `/proc/self/fd` to find the canonical
+//! path of the opened file.
+
+use super::procfs::get_path_from_proc_self_fd;
+use crate::fs::{manually, open, OpenOptions};
+use std::{
+    fs, io,
+    os::unix::fs::MetadataExt,
+    path::{Component, Path, PathBuf},
+};
+
+/// Open `path` with `open`, and then call `readlink` on `/proc/self/fd/*` for
+/// both `start` and the new file to compute the canonical path.
+pub(crate) fn open_and_resolve_impl(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+) -> io::Result<(fs::File, PathBuf)> {
+    let file = open(start, path, options)?;
+
+    let canonical_path = match path_within(start, &file) {
+        Some(canonical_path) => canonical_path,
+
+        // If `/proc` isn't available, or the paths it reports aren't
+        // usable, fall back to resolving the path again.
+        None => manually::canonicalize_with(start, path, options.follow)?,
+    };
+
+    Ok((file, canonical_path))
+}
+
+/// Return the path of `file` relative to `start`, if `/proc/self/fd` knows
+/// both and `file` is within `start`.
+fn path_within(start: &fs::File, file: &fs::File) -> Option<PathBuf> {
+    let start_path = get_path_from_proc_self_fd(start).ok()?;
+    let file_path = get_path_from_proc_self_fd(file).ok()?;
+
+    // Linux appends the string " (deleted)" when a file is deleted, as happens
+    // with `O_TMPFILE` files; avoid treating that as the actual name. Check
+    // this after doing the `readlink`s above so that we're conservative about
+    // concurrent deletions.
+    if file.metadata().ok()?.nlink() == 0 {
+        return None;
+    }
+
+    let mut path_buf = file_path.strip_prefix(start_path).ok()?.to_path_buf();
+
+    // Replace "" with ".", since "" as a relative path is interpreted as an
+    // error.
+    if path_buf.as_os_str().is_empty() {
+        path_buf.push(Component::CurDir);
+    }
+
+    Some(path_buf)
+}
@@ -0,0 +1,46 @@
+use super::procfs::proc_self_fd;
+use crate::fs::{open, open_then_check, open_unchecked, FollowSymlinks, Metadata, OpenOptions};
+use posish::{fs::OFlags, path::DecInt};
+use std::{fs, io, os::unix::fs::OpenOptionsExt, path::Path};
+
+/// Implementation of `open_if`. Open the file with `O_PATH`, which needs no
+/// access to the file and has none of the side effects of a real open, such
+/// as blocking on a FIFO, check it, and only then reopen it with `options`.
+#[allow(clippy::unnecessary_cast)]
+pub(crate) fn open_if_impl(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+    predicate: impl FnOnce(&Metadata) -> bool,
+) -> io::Result<Option<fs::File>> {
+    // Reopening needs `/proc`; without it, open the file for real.
+    let proc_self_fd = match proc_self_fd() {
+        Ok(proc_self_fd) => proc_self_fd,
+        Err(_) => return open_then_check(start, path, options, predicate),
+    };
+
+    // Use `read(true)` even though we don't need `read` permissions, because
+    // Rust's libstd requires an access mode, and Linux ignores `O_RDONLY`
+    // with `O_PATH`.
+    let file = open(
+        start,
+        path,
+        OpenOptions::new()
+            .read(true)
+            .follow(options.follow)
+            .custom_flags(OFlags::PATH.bits() as i32),
+    )?;
+
+    if !predicate(&Metadata::from_file(&file)?) {
+        return Ok(None);
+    }
+
+    // Reopen the same inode through its entry in `/proc/self/fd`. `path` has
+    // already been resolved, so follow the entry, which is a magic link.
+    let file = open_unchecked(
+        proc_self_fd,
+        &DecInt::from_fd(&file),
+        options.clone().follow(FollowSymlinks::Yes),
+    )?;
+    Ok(Some(file))
+}
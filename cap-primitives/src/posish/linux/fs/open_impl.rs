@@ -8,9 +8,13 @@
 //! On older Linux, fall back to `manually::open`.
 
 use super::super::super::fs::{c_str, compute_oflags};
+use super::stat_impl::stat_impl;
 #[cfg(racy_asserts)]
 use crate::fs::is_same_file;
-use crate::fs::{errors, manually, OpenOptions};
+use crate::fs::{
+    check_deadline, errors, manually, DanglingSymlinkError, FollowSymlinks, MissingComponentError,
+    OpenOptions,
+};
 use io_lifetimes::FromFd;
 use posish::fs::{openat2, Mode, OFlags, ResolveFlags};
 use posish::io::Errno;
@@ -25,19 +29,112 @@ pub(crate) fn open_impl(
     start: &fs::File,
     path: &Path,
     options: &OpenOptions,
+) -> io::Result<fs::File> {
+    open_impl_or(start, path, options, manually::open)
+}
+
+/// Like `open_impl`, but call `fallback` instead of `manually::open` if
+/// `openat2` is unavailable.
+pub(crate) fn open_impl_or(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+    fallback: impl FnOnce(&fs::File, &Path, &OpenOptions) -> io::Result<fs::File>,
 ) -> io::Result<fs::File> {
     let result = open_beneath(start, path, options);
 
     // If that returned `ENOSYS`, use a fallback strategy.
     if let Err(err) = &result {
         if let Some(Errno::NOSYS) = Errno::from_io_error(err) {
-            return manually::open(start, path, options);
+            return fallback(start, path, options);
+        }
+    }
+
+    // `openat2` doesn't say which component was missing, or whether it was
+    // in the target of a symlink, so if we've been asked to report it, ask
+    // the manual resolver. Otherwise, if the path may end in a dangling
+    // symlink, just check whether the last component is a symlink.
+    match result {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            if options.report_missing_component {
+                Err(explain_not_found(start, path, options, err))
+            } else if options.follow == FollowSymlinks::Yes {
+                Err(flag_dangling_symlink(start, path, err))
+            } else {
+                Err(err)
+            }
+        }
+        result => result,
+    }
+}
+
+/// `openat2` failed with `NotFound` error `err` while following symlinks; if
+/// `path` names a symlink, its target must be what's missing, so mark `err`
+/// with a `DanglingSymlinkError`.
+#[cold]
+fn flag_dangling_symlink(start: &fs::File, path: &Path, err: io::Error) -> io::Error {
+    match stat_impl(start, path, FollowSymlinks::No) {
+        Ok(metadata) if metadata.file_type().is_symlink() => errors::dangling_symlink(err),
+        _ => err,
+    }
+}
+
+/// `openat2` failed with `NotFound` error `err`; resolve `path` again
+/// manually, with `O_PATH` so that nothing is created, truncated, or
+/// required to be readable, to find which component is missing, and whether
+/// it's missing from the target of a symlink.
+#[cold]
+fn explain_not_found(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+    err: io::Error,
+) -> io::Error {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut path_options = OpenOptions::new();
+    path_options
+        .read(true)
+        .follow(options.follow)
+        .report_missing_component(options.report_missing_component)
+        .custom_flags(OFlags::PATH.bits() as _);
+    match manually::open(start, path, &path_options) {
+        Err(manual_err)
+            if MissingComponentError::from_io_error(&manual_err).is_some()
+                || DanglingSymlinkError::from_io_error(&manual_err).is_some() =>
+        {
+            manual_err
+        }
+        _ => err,
+    }
+}
+
+/// Call the `openat2` system call with `RESOLVE_IN_ROOT`, or use a fallback
+/// if that's unavailable.
+pub(crate) fn open_in_root_impl(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+) -> io::Result<fs::File> {
+    let result = openat2_in_root(start, path, options);
+
+    // If that returned `ENOSYS`, use a fallback strategy.
+    if let Err(err) = &result {
+        if let Some(Errno::NOSYS) = Errno::from_io_error(err) {
+            return manually::open_in_root(start, path, options);
         }
     }
 
     result
 }
 
+/// `RESOLVE_IN_ROOT`, which posish doesn't define yet.
+#[allow(unsafe_code)]
+const RESOLVE_IN_ROOT: ResolveFlags = unsafe { ResolveFlags::from_bits_unchecked(0x10) };
+
+/// Set when `openat2` is found to be permanently unavailable.
+static INVALID: AtomicBool = AtomicBool::new(false);
+
 /// Call the `openat2` system call with `RESOLVE_BENEATH`. If the syscall is
 /// unavailable, mark it so for future calls. If `openat2` is unavailable
 /// either permanently or temporarily, return `ENOSYS`.
@@ -46,7 +143,36 @@ pub(crate) fn open_beneath(
     path: &Path,
     options: &OpenOptions,
 ) -> io::Result<fs::File> {
-    static INVALID: AtomicBool = AtomicBool::new(false);
+    openat2_with_resolve(
+        start,
+        path,
+        options,
+        ResolveFlags::BENEATH | ResolveFlags::NO_MAGICLINKS,
+    )
+}
+
+/// Like `open_beneath`, but with `RESOLVE_IN_ROOT`, so that absolute paths
+/// and absolute symlink targets are resolved relative to `start`, and `..`
+/// at `start` stays at `start`.
+pub(super) fn openat2_in_root(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+) -> io::Result<fs::File> {
+    openat2_with_resolve(
+        start,
+        path,
+        options,
+        RESOLVE_IN_ROOT | ResolveFlags::NO_MAGICLINKS,
+    )
+}
+
+fn openat2_with_resolve(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+    resolve: ResolveFlags,
+) -> io::Result<fs::File> {
     if !INVALID.load(Relaxed) {
         let oflags = compute_oflags(options)?;
 
@@ -67,13 +193,8 @@ pub(crate) fn open_beneath(
         // times, because there's no limit on how often this can happen. The actual
         // number here is currently an arbitrarily chosen guess.
         for _ in 0..4 {
-            match openat2(
-                start,
-                path_c_str.as_c_str(),
-                oflags,
-                mode,
-                ResolveFlags::BENEATH | ResolveFlags::NO_MAGICLINKS,
-            ) {
+            check_deadline()?;
+            match openat2(start, path_c_str.as_c_str(), oflags, mode, resolve) {
                 Ok(file) => {
                     let file = fs::File::from_into_fd(file);
                     // Note that we don't bother with `ensure_cloexec` here
@@ -81,13 +202,16 @@ pub(crate) fn open_beneath(
                     // and `openat2` was introduced in 5.6.
 
                     #[cfg(racy_asserts)]
-                    check_open(start, path, options, &file);
+                    if resolve.contains(ResolveFlags::BENEATH) {
+                        check_open(start, path, options, &file);
+                    }
 
                     return Ok(file);
                 }
                 Err(err) => match Errno::from_io_error(&err) {
                     Some(Errno::AGAIN) => continue,
-                    Some(Errno::XDEV) => return Err(errors::escape_attempt()),
+                    // The kernel doesn't say which component or symlink escaped.
+                    Some(Errno::XDEV) => return Err(errors::escape_detected_by_kernel()),
 
                     // `EPERM` is used by some `seccomp` sandboxes to indicate
                     // that `openat2` is unimplemented:
@@ -95,9 +219,10 @@ pub(crate) fn open_beneath(
                     //
                     // However, `EPERM` may also indicate a failed `O_NOATIME`
                     // or a file seal prevented the operation, and it's complex
-                    // to detect those cases, so exit the loop and use the
-                    // fallback.
-                    Some(Errno::PERM) => break,
+                    // to detect those cases. If the fallback can reproduce
+                    // the open faithfully, exit the loop and use it;
+                    // otherwise report the error.
+                    Some(Errno::PERM) if eperm_uses_fallback(oflags) => break,
 
                     // `ENOSYS` means `openat2` is permanently unavailable;
                     // mark it so and exit the loop.
@@ -116,6 +241,134 @@ pub(crate) fn open_beneath(
     Err(Errno::NOSYS.io_error())
 }
 
+/// Decide whether an `EPERM` from `openat2` with `oflags` should be handled
+/// by using the fallback, or returned as is.
+///
+/// | `oflags` include | On `EPERM`                     |
+/// | ---------------- | ------------------------------ |
+/// | `O_TMPFILE`      | Return the `EPERM`.            |
+/// | Anything else    | Use the fallback, as `ENOSYS`. |
+///
+/// The fallback resolves the path one component at a time and then opens
+/// the last component with `oflags`. For `O_TMPFILE` the last component is a
+/// directory in which an unnamed file is created, and the fallback's sequence
+/// of opens of it can fail differently, or succeed where `openat2` didn't,
+/// so we don't let it silently diverge. Other `EPERM` causes, such as
+/// `O_NOATIME` or seals, are reproduced by the fallback's final open, so
+/// they're still reported, just from the fallback.
+fn eperm_uses_fallback(oflags: OFlags) -> bool {
+    !oflags.contains(OFlags::TMPFILE)
+}
+
+#[test]
+#[allow(clippy::unnecessary_cast)]
+fn eperm_fallback_decision() {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    // Plain opens use the fallback.
+    let oflags = compute_oflags(OpenOptions::new().read(true)).unwrap();
+    assert!(eperm_uses_fallback(oflags));
+    let oflags =
+        compute_oflags(OpenOptions::new().write(true).create(true).truncate(true)).unwrap();
+    assert!(eperm_uses_fallback(oflags));
+
+    // `O_TMPFILE` opens report the error directly.
+    let oflags = compute_oflags(
+        OpenOptions::new()
+            .write(true)
+            .custom_flags(OFlags::TMPFILE.bits() as i32),
+    )
+    .unwrap();
+    assert!(!eperm_uses_fallback(oflags));
+}
+
+/// Install a seccomp filter on the current thread which makes `openat2` fail
+/// with `EPERM`, as some container sandboxes do. Returns `false` if seccomp
+/// isn't available.
+#[cfg(test)]
+#[allow(unsafe_code)]
+pub(crate) fn deny_openat2_on_this_thread() -> bool {
+    let filter = [
+        // Load the syscall number from `struct seccomp_data`.
+        libc::sock_filter {
+            code: (libc::BPF_LD | libc::BPF_W | libc::BPF_ABS) as u16,
+            jt: 0,
+            jf: 0,
+            k: 0,
+        },
+        libc::sock_filter {
+            code: (libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K) as u16,
+            jt: 0,
+            jf: 1,
+            k: libc::SYS_openat2 as u32,
+        },
+        libc::sock_filter {
+            code: (libc::BPF_RET | libc::BPF_K) as u16,
+            jt: 0,
+            jf: 0,
+            k: libc::SECCOMP_RET_ERRNO | libc::EPERM as u32,
+        },
+        libc::sock_filter {
+            code: (libc::BPF_RET | libc::BPF_K) as u16,
+            jt: 0,
+            jf: 0,
+            k: libc::SECCOMP_RET_ALLOW,
+        },
+    ];
+    let program = libc::sock_fprog {
+        len: filter.len() as u16,
+        filter: filter.as_ptr() as *mut _,
+    };
+
+    // Safety: `program` points to a valid filter for the duration of the
+    // calls, and the filter only applies to the current thread.
+    unsafe {
+        libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) == 0
+            && libc::prctl(
+                libc::PR_SET_SECCOMP,
+                libc::SECCOMP_MODE_FILTER,
+                &program as *const libc::sock_fprog,
+            ) == 0
+    }
+}
+
+/// Test that when `openat2` fails with `EPERM`, plain opens succeed with the
+/// fallback, and `O_TMPFILE` opens report the `EPERM`.
+#[test]
+#[allow(clippy::unnecessary_cast)]
+fn eperm_fallback_behavior() {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let tmp = tempfile::tempdir().unwrap();
+    fs::write(tmp.path().join("file"), b"").unwrap();
+    let start = fs::File::open(tmp.path()).unwrap();
+
+    // Seccomp filters can't be removed, so install it on a thread of its own.
+    std::thread::spawn(move || {
+        if !deny_openat2_on_this_thread() {
+            return;
+        }
+
+        let mut options = OpenOptions::new();
+        options.read(true);
+        let err = open_beneath(&start, Path::new("file"), &options).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ENOSYS));
+        open_impl(&start, Path::new("file"), &options).unwrap();
+
+        let mut options = OpenOptions::new();
+        options
+            .write(true)
+            .custom_flags(OFlags::TMPFILE.bits() as i32);
+        let err = open_impl(&start, Path::new("."), &options).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EPERM));
+
+        // The filter doesn't mark `openat2` as permanently unavailable.
+        assert!(!INVALID.load(Relaxed));
+    })
+    .join()
+    .unwrap();
+}
+
 #[cfg(racy_asserts)]
 fn check_open(start: &fs::File, path: &Path, options: &OpenOptions, file: &fs::File) {
     let check = manually::open(
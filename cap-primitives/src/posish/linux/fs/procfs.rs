@@ -168,7 +168,7 @@ fn is_mountpoint(file: &fs::File) -> io::Result<bool> {
     }
 }
 
-fn proc_self_fd() -> io::Result<&'static fs::File> {
+pub(super) fn proc_self_fd() -> io::Result<&'static fs::File> {
     #[allow(clippy::useless_conversion)]
     static PROC_SELF_FD: Lazy<io::Result<fs::File>> = Lazy::new(|| {
         // Open "/proc". Here and below, use `read(true)` even though we don't need
@@ -252,3 +252,22 @@ pub(crate) fn set_times_through_proc_self_fd(
     let dirfd = proc_self_fd()?;
     set_times_follow_unchecked(dirfd, &DecInt::from_fd(&opath), atime, mtime)
 }
+
+/// Open a new file description for `file`, which may be an `O_PATH` file
+/// descriptor, through its entry in "/proc/self/fd", for use with calls which
+/// don't accept `O_PATH`.
+#[allow(clippy::unnecessary_cast)]
+pub(crate) fn reopen_through_proc_self_fd(file: &fs::File) -> io::Result<fs::File> {
+    // Use `O_NONBLOCK` so that FIFOs don't block waiting for a writer, and
+    // `O_NOCTTY` so that terminals don't become our controlling terminal.
+    let dirfd = proc_self_fd()?;
+    open_unchecked(
+        dirfd,
+        &DecInt::from_fd(file),
+        OpenOptions::new()
+            .read(true)
+            .follow(FollowSymlinks::Yes)
+            .custom_flags((OFlags::NONBLOCK | OFlags::NOCTTY).bits() as i32),
+    )
+    .map_err(Into::into)
+}
@@ -3,6 +3,7 @@
 //! `fstat` to perform a fast sandboxed `stat`.
 
 use super::file_metadata;
+use super::open_impl::openat2_in_root;
 use crate::fs::{manually, open_beneath, FollowSymlinks, Metadata, OpenOptions};
 use posish::fs::OFlags;
 use posish::io::Errno;
@@ -14,13 +15,32 @@ pub(crate) fn stat_impl(
     start: &fs::File,
     path: &Path,
     follow: FollowSymlinks,
+) -> io::Result<Metadata> {
+    stat_with(start, path, follow, open_beneath, manually::stat)
+}
+
+/// Like `stat_impl`, but resolve `path` as `open_in_root` does.
+pub(crate) fn stat_in_root_impl(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+) -> io::Result<Metadata> {
+    stat_with(start, path, follow, openat2_in_root, manually::stat_in_root)
+}
+
+fn stat_with(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+    open: fn(&fs::File, &Path, &OpenOptions) -> io::Result<fs::File>,
+    fallback: fn(&fs::File, &Path, FollowSymlinks) -> io::Result<Metadata>,
 ) -> io::Result<Metadata> {
     use std::os::unix::fs::OpenOptionsExt;
 
     // Open the path with `O_PATH`. Use `read(true)` even though we don't need
     // `read` permissions, because Rust's libstd requires an access mode, and
     // Linux ignores `O_RDONLY` with `O_PATH`.
-    let result = open_beneath(
+    let result = open(
         start,
         path,
         OpenOptions::new()
@@ -35,7 +55,7 @@ pub(crate) fn stat_impl(
         Err(err) => match Errno::from_io_error(&err) {
             // `ENOSYS` from `open_beneath` means `openat2` is unavailable
             // and we should use a fallback.
-            Some(Errno::NOSYS) => manually::stat(start, path, follow),
+            Some(Errno::NOSYS) => fallback(start, path, follow),
             _ => Err(err),
         },
     }
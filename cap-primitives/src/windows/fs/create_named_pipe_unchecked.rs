@@ -0,0 +1,68 @@
+use super::get_path::concatenate_or_return_absolute;
+use crate::fs::PipeOptions;
+use std::{
+    fs, io,
+    os::windows::{ffi::OsStrExt, io::FromRawHandle},
+    path::Path,
+    ptr,
+};
+use winapi::um::{
+    handleapi::INVALID_HANDLE_VALUE,
+    namedpipeapi::CreateNamedPipeW,
+    winbase::{
+        PIPE_ACCESS_DUPLEX, PIPE_ACCESS_INBOUND, PIPE_ACCESS_OUTBOUND, PIPE_READMODE_BYTE,
+        PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    },
+};
+
+/// The size, in bytes, to request for each of the pipe's buffers.
+const BUFFER_SIZE: u32 = 4096;
+
+/// *Unsandboxed* function similar to `create_named_pipe`, but which does not
+/// perform sandboxing.
+#[allow(unsafe_code)]
+pub(crate) fn create_named_pipe_unchecked(
+    start: &fs::File,
+    path: &Path,
+    options: &PipeOptions,
+) -> io::Result<fs::File> {
+    let full_path = concatenate_or_return_absolute(start, path)?;
+    let wide = full_path
+        .as_os_str()
+        .encode_wide()
+        .chain(Some(0))
+        .collect::<Vec<_>>();
+
+    let access = match (options.read, options.write) {
+        (true, true) => PIPE_ACCESS_DUPLEX,
+        (true, false) => PIPE_ACCESS_INBOUND,
+        (false, _) => PIPE_ACCESS_OUTBOUND,
+    };
+    if options.max_instances == 0 || options.max_instances > PIPE_UNLIMITED_INSTANCES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the maximum number of named pipe instances must be between 1 and 255",
+        ));
+    }
+
+    // Safety: `wide` is a NUL-terminated string, and the security attributes
+    // are optional.
+    let handle = unsafe {
+        CreateNamedPipeW(
+            wide.as_ptr(),
+            access,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            options.max_instances,
+            BUFFER_SIZE,
+            BUFFER_SIZE,
+            0,
+            ptr::null_mut(),
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return Err(io::Error::last_os_error());
+    }
+
+    // Safety: `handle` is a newly created handle which nothing else owns.
+    Ok(unsafe { fs::File::from_raw_handle(handle as _) })
+}
@@ -6,7 +6,11 @@ use crate::{
         ReadDir, ReadDirInner,
     },
 };
-use std::{ffi::OsString, fmt, fs, io, os::windows::fs::OpenOptionsExt};
+use std::{
+    ffi::OsString,
+    fmt, fs, io,
+    os::windows::fs::{MetadataExt, OpenOptionsExt},
+};
 use winapi::um::winbase::{FILE_FLAG_BACKUP_SEMANTICS, FILE_FLAG_OPEN_REPARSE_POINT};
 
 pub(crate) struct DirEntryInner {
@@ -79,6 +83,25 @@ impl DirEntryInner {
         self.std.file_type().map(FileTypeExt::from_std)
     }
 
+    #[inline]
+    pub(crate) fn ino(&self) -> io::Result<u64> {
+        // The find data doesn't include the file index, so this has to open
+        // the file.
+        Ok(self.full_metadata()?.ext.file_id().1)
+    }
+
+    #[inline]
+    pub(crate) fn d_type_raw(&self) -> io::Result<u32> {
+        // `std` caches the attributes from the find data, including
+        // `FILE_ATTRIBUTE_REPARSE_POINT`, so this doesn't open the file.
+        Ok(self.std.metadata()?.file_attributes())
+    }
+
+    #[inline]
+    pub(crate) fn file_type_cached(&self) -> Option<FileType> {
+        self.file_type().ok()
+    }
+
     #[inline]
     pub(crate) fn file_name(&self) -> OsString {
         self.std.file_name()
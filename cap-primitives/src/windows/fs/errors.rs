@@ -16,7 +16,21 @@ pub(crate) fn is_not_directory() -> io::Error {
     io::Error::from_raw_os_error(winerror::ERROR_DIRECTORY as i32)
 }
 
+#[cold]
+pub(crate) fn reserved_device_name() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "path component is a reserved Windows device name",
+    )
+}
+
 #[cold]
 pub(crate) fn too_many_symlinks() -> io::Error {
     io::Error::from_raw_os_error(winerror::ERROR_TOO_MANY_LINKS as i32)
 }
+
+/// Test whether `err` indicates an attempt to link or rename across
+/// filesystems.
+pub(crate) fn is_cross_device(err: &io::Error) -> bool {
+    err.raw_os_error() == Some(winerror::ERROR_NOT_SAME_DEVICE as i32)
+}
@@ -0,0 +1,63 @@
+use std::{fs, io, mem, os::windows::io::AsRawHandle};
+use winapi::{
+    shared::{minwindef::DWORD, winerror::ERROR_LOCK_VIOLATION},
+    um::{
+        fileapi::{LockFileEx, UnlockFileEx},
+        minwinbase::{LOCKFILE_EXCLUSIVE_LOCK, LOCKFILE_FAIL_IMMEDIATELY, OVERLAPPED},
+    },
+};
+
+#[inline]
+pub(crate) fn lock_file_exclusive_impl(file: &fs::File) -> io::Result<()> {
+    lock_file_ex(file, LOCKFILE_EXCLUSIVE_LOCK)
+}
+
+pub(crate) fn try_lock_file_exclusive_impl(file: &fs::File) -> io::Result<bool> {
+    match lock_file_ex(file, LOCKFILE_EXCLUSIVE_LOCK | LOCKFILE_FAIL_IMMEDIATELY) {
+        Ok(()) => Ok(true),
+        Err(err) if err.raw_os_error() == Some(ERROR_LOCK_VIOLATION as i32) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Lock the entire file. Windows locks are byte ranges, so use the largest
+/// range possible, starting at offset 0.
+#[allow(unsafe_code)]
+fn lock_file_ex(file: &fs::File, flags: DWORD) -> io::Result<()> {
+    // Safety: `file` owns a valid handle for the duration of the call, and
+    // `overlapped` is a plain-old-data struct for which zero is valid.
+    unsafe {
+        let mut overlapped: OVERLAPPED = mem::zeroed();
+        if LockFileEx(
+            file.as_raw_handle() as _,
+            flags,
+            0,
+            DWORD::MAX,
+            DWORD::MAX,
+            &mut overlapped,
+        ) == 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[allow(unsafe_code)]
+pub(crate) fn unlock_file_impl(file: &fs::File) -> io::Result<()> {
+    // Safety: See `lock_file_ex`.
+    unsafe {
+        let mut overlapped: OVERLAPPED = mem::zeroed();
+        if UnlockFileEx(
+            file.as_raw_handle() as _,
+            0,
+            DWORD::MAX,
+            DWORD::MAX,
+            &mut overlapped,
+        ) == 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
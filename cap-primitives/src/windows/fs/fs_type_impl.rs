@@ -0,0 +1,102 @@
+use crate::fs::FsType;
+use std::{
+    ffi::OsString, fs, io, mem, os::windows::ffi::OsStringExt, os::windows::io::AsRawHandle, ptr,
+};
+use winapi::{
+    shared::{
+        minwindef::{DWORD, MAX_PATH},
+        wnnc::{WNNC_NET_MS_NFS, WNNC_NET_SMB},
+    },
+    um::{
+        fileapi::GetVolumeInformationByHandleW, minwinbase::FileRemoteProtocolInfo,
+        winbase::GetFileInformationByHandleEx, winnt::FILE_READ_ONLY_VOLUME,
+    },
+};
+
+/// `FILE_REMOTE_PROTOCOL_INFO`, which winapi doesn't define. Only `protocol`
+/// is used; the protocol-specific information is left opaque.
+#[repr(C)]
+struct FileRemoteProtocolInfoBuf {
+    structure_version: u16,
+    structure_size: u16,
+    protocol: DWORD,
+    protocol_major_version: u16,
+    protocol_minor_version: u16,
+    protocol_revision: u16,
+    reserved: u16,
+    flags: DWORD,
+    generic_reserved: [DWORD; 8],
+    protocol_specific: [DWORD; 16],
+}
+
+pub(crate) fn filesystem_type_impl(file: &fs::File) -> io::Result<FsType> {
+    if let Some(protocol) = remote_protocol(file) {
+        return Ok(match protocol {
+            WNNC_NET_SMB => FsType::Smb,
+            WNNC_NET_MS_NFS => FsType::Nfs,
+            other => FsType::Other(other.into()),
+        });
+    }
+
+    let (name, _flags) = volume_information(file)?;
+    Ok(match name.to_str() {
+        Some("NTFS") => FsType::Ntfs,
+        Some("ReFS") => FsType::Refs,
+        Some("FAT") | Some("FAT32") | Some("exFAT") => FsType::Fat,
+        _ => FsType::Other(0),
+    })
+}
+
+/// Return the network protocol `file` is accessed with, or `None` if it's
+/// local.
+#[allow(unsafe_code)]
+fn remote_protocol(file: &fs::File) -> Option<DWORD> {
+    // Safety: `file` owns a valid handle for the duration of the call, and
+    // `info` is a plain-old-data struct for which zero is valid, of the size
+    // we pass.
+    unsafe {
+        let mut info: FileRemoteProtocolInfoBuf = mem::zeroed();
+        if GetFileInformationByHandleEx(
+            file.as_raw_handle() as _,
+            FileRemoteProtocolInfo,
+            &mut info as *mut _ as *mut _,
+            mem::size_of_val(&info) as DWORD,
+        ) == 0
+        {
+            return None;
+        }
+        Some(info.protocol)
+    }
+}
+
+pub(crate) fn is_read_only_mount_impl(file: &fs::File) -> io::Result<bool> {
+    let (_name, flags) = volume_information(file)?;
+    Ok(flags & FILE_READ_ONLY_VOLUME != 0)
+}
+
+/// Return the filesystem name and flags of the volume `file` is on.
+#[allow(unsafe_code)]
+fn volume_information(file: &fs::File) -> io::Result<(OsString, DWORD)> {
+    let mut name = [0_u16; MAX_PATH + 1];
+    let mut flags: DWORD = 0;
+
+    // Safety: `file` owns a valid handle for the duration of the call,
+    // `name` is a buffer of the length we pass, and `flags` is a `DWORD`.
+    let ok = unsafe {
+        GetVolumeInformationByHandleW(
+            file.as_raw_handle() as _,
+            ptr::null_mut(),
+            0,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut flags,
+            name.as_mut_ptr(),
+            name.len() as DWORD,
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let len = name.iter().position(|c| *c == 0).unwrap_or(name.len());
+    Ok((OsString::from_wide(&name[..len]), flags))
+}
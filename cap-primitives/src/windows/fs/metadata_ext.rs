@@ -132,6 +132,26 @@ impl MetadataExt {
         self_vsn == other_vsn && self_file_index == other_file_index
     }
 
+    /// Return the volume serial number and file index, which together
+    /// identify a file. See the comments in `is_same_file`.
+    pub(crate) fn file_id(&self) -> (u64, u64) {
+        let vsn = self
+            .volume_serial_number
+            .expect("could extract volume serial number");
+        let file_index = self.file_index.expect("could extract file index");
+        (u64::from(vsn), file_index)
+    }
+
+    /// Like `file_id`, but returns `None` where the volume serial number or
+    /// file index weren't available, such as when `Self` was constructed
+    /// with `from_just_metadata`.
+    pub(crate) fn maybe_file_id(&self) -> Option<(u64, u64)> {
+        match (self.volume_serial_number, self.file_index) {
+            (Some(vsn), Some(file_index)) => Some((u64::from(vsn), file_index)),
+            _ => None,
+        }
+    }
+
     /// `MetadataExt` requires nightly to be implemented, but we sometimes
     /// just need the file attributes.
     #[inline]
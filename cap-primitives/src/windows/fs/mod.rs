@@ -1,9 +1,12 @@
 mod copy;
 mod create_dir_unchecked;
+mod create_named_pipe_unchecked;
 mod dir_entry_inner;
 mod dir_options_ext;
 mod dir_utils;
+mod file_lock_impl;
 mod file_type_ext;
+mod fs_type_impl;
 mod get_path;
 mod hard_link_unchecked;
 mod is_file_read_write_impl;
@@ -23,6 +26,7 @@ mod remove_open_dir_impl;
 mod rename_unchecked;
 mod reopen_impl;
 mod set_permissions_unchecked;
+mod set_sparse_impl;
 mod set_times_impl;
 mod stat_unchecked;
 mod symlink_unchecked;
@@ -32,6 +36,9 @@ pub(crate) mod errors;
 #[rustfmt::skip]
 pub(crate) use crate::fs::{
     manually::canonicalize as canonicalize_impl,
+    manually::open_and_resolve as open_and_resolve_impl,
+    manually::open_in_root as open_in_root_impl,
+    manually::stat_in_root as stat_in_root_impl,
     via_parent::hard_link as hard_link_impl,
     via_parent::create_dir as create_dir_impl,
     via_parent::rename as rename_impl,
@@ -45,10 +52,13 @@ pub(crate) use crate::fs::{
 
 pub(crate) use copy::*;
 pub(crate) use create_dir_unchecked::*;
+pub(crate) use create_named_pipe_unchecked::*;
 pub(crate) use dir_entry_inner::*;
 pub(crate) use dir_options_ext::*;
 pub(crate) use dir_utils::*;
+pub(crate) use file_lock_impl::*;
 pub(crate) use file_type_ext::*;
+pub(crate) use fs_type_impl::{filesystem_type_impl, is_read_only_mount_impl};
 pub(crate) use hard_link_unchecked::*;
 pub(crate) use is_file_read_write_impl::*;
 pub(crate) use is_same_file::*;
@@ -66,6 +76,7 @@ pub(crate) use remove_open_dir_impl::*;
 pub(crate) use rename_unchecked::*;
 pub(crate) use reopen_impl::reopen_impl;
 pub(crate) use set_permissions_unchecked::*;
+pub(crate) use set_sparse_impl::set_sparse_impl;
 pub(crate) use set_times_impl::*;
 pub(crate) use stat_unchecked::*;
 pub(crate) use symlink_unchecked::*;
@@ -21,7 +21,7 @@ pub(in super::super) fn open_options_to_std(opts: &OpenOptions) -> (fs::OpenOpti
             opts.ext.custom_flags | FILE_FLAG_OPEN_REPARSE_POINT
         }
     };
-    if opts.maybe_dir {
+    if opts.maybe_dir || opts.backup_semantics {
         custom_flags |= FILE_FLAG_BACKUP_SEMANTICS;
     }
     let mut std_opts = fs::OpenOptions::new();
@@ -1,26 +1,98 @@
-use crate::fs::{manually, OpenOptions};
-use std::{fs, io, path::Path};
-use winapi::shared::winerror::ERROR_FILE_NOT_FOUND;
+use crate::fs::{errors, manually, OpenOptions};
+use std::{
+    ffi::OsStr,
+    fs, io,
+    path::{Component, Path},
+};
 
 pub(crate) fn open_impl(
     start: &fs::File,
     path: &Path,
     options: &OpenOptions,
 ) -> io::Result<fs::File> {
-    // Windows reserves several special device paths. Disallow opening any
-    // of them.
-    if let Some(stem) = path.file_stem() {
-        if let Some(stemstr) = stem.to_str() {
-            match stemstr.to_uppercase().as_str() {
-                "CON" | "PRN" | "AUX" | "NUL" | "COM0" | "COM1" | "COM2" | "COM3" | "COM4"
-                | "COM5" | "COM6" | "COM7" | "COM8" | "COM9" | "LPT0" | "LPT1" | "LPT2"
-                | "LPT3" | "LPT4" | "LPT5" | "LPT6" | "LPT7" | "LPT8" | "LPT9" => {
-                    return Err(io::Error::from_raw_os_error(ERROR_FILE_NOT_FOUND as i32));
-                }
-                _ => {}
+    // Windows reserves several special device names, which refer to the
+    // device in any directory. Disallow opening any of them, in any
+    // component, so that untrusted paths can't reach devices through the
+    // sandbox.
+    for component in path.components() {
+        if let Component::Normal(name) = component {
+            if is_reserved_device_name(name) {
+                return Err(errors::reserved_device_name());
             }
         }
     }
 
     manually::open(start, path, options)
 }
+
+/// Test whether Windows would interpret `name` as a device.
+///
+/// Windows ignores everything from the first `.` or `:`, and any spaces
+/// before it, so `CON.txt`, `con .tar.gz`, and `CON:stream` all name the
+/// console. `COM` and `LPT` may be followed by any digit, including the
+/// superscripts `¹`, `²`, and `³`.
+fn is_reserved_device_name(name: &OsStr) -> bool {
+    let name = match name.to_str() {
+        Some(name) => name,
+        // Reserved names are all ASCII, so names which aren't valid Unicode
+        // aren't reserved.
+        None => return false,
+    };
+    let base = name
+        .split(['.', ':'])
+        .next()
+        .unwrap()
+        .trim_end_matches(' ');
+
+    if ["CON", "PRN", "AUX", "NUL", "CONIN$", "CONOUT$"]
+        .iter()
+        .any(|reserved| base.eq_ignore_ascii_case(reserved))
+    {
+        return true;
+    }
+
+    let mut chars = base.chars();
+    let prefix = chars.by_ref().take(3).collect::<String>();
+    let digit = chars.next();
+    (prefix.eq_ignore_ascii_case("COM") || prefix.eq_ignore_ascii_case("LPT"))
+        && matches!(digit, Some('0'..='9') | Some('¹') | Some('²') | Some('³'))
+        && chars.next().is_none()
+}
+
+#[test]
+fn reserved_device_names() {
+    for name in &[
+        "CON",
+        "con",
+        "Con.txt",
+        "CON .txt",
+        "con.tar.gz",
+        "CON:stream",
+        "NUL",
+        "aux",
+        "PRN",
+        "CONIN$",
+        "conout$",
+        "COM0",
+        "com1.log",
+        "COM9",
+        "COM¹",
+        "lpt3",
+        "LPT².txt",
+    ] {
+        assert!(is_reserved_device_name(name.as_ref()), "{}", name);
+    }
+    for name in &[
+        "conference.txt",
+        "CONSOLE",
+        "aCON",
+        "COM",
+        "COM10",
+        "LPTX",
+        "NUL_",
+        ".CON",
+        "",
+    ] {
+        assert!(!is_reserved_device_name(name.as_ref()), "{}", name);
+    }
+}
@@ -1,7 +1,9 @@
 use super::get_path::concatenate_or_return_absolute;
-use crate::fs::{open_dir, DirEntryInner};
+use crate::fs::{open_dir, DirEntryInner, FileType, FileTypeExt};
 use std::{
+    ffi::OsStr,
     fmt, fs, io,
+    ops::ControlFlow,
     path::{Component, Path},
 };
 
@@ -31,6 +33,27 @@ impl ReadDirInner {
     }
 }
 
+/// Call `f` with the name and type of each entry in `start`.
+///
+/// `FindNextFileW` reports the name and attributes together, so this uses
+/// the entries' cached types rather than opening each one. It still goes
+/// through `std::fs::read_dir`, so unlike on Unix-family platforms, each
+/// entry and its name are allocated.
+pub(crate) fn for_each_entry_impl(
+    start: &fs::File,
+    mut f: impl FnMut(&OsStr, FileType) -> ControlFlow<()>,
+) -> io::Result<()> {
+    let full_path = concatenate_or_return_absolute(start, Component::CurDir.as_os_str().as_ref())?;
+    for entry in fs::read_dir(full_path)? {
+        let entry = entry?;
+        let file_type = FileTypeExt::from_std(entry.file_type()?);
+        if let ControlFlow::Break(()) = f(&entry.file_name(), file_type) {
+            break;
+        }
+    }
+    Ok(())
+}
+
 impl Iterator for ReadDirInner {
     type Item = io::Result<DirEntryInner>;
 
@@ -0,0 +1,36 @@
+use std::{fs, io, os::windows::io::AsRawHandle, ptr};
+use winapi::um::{ioapiset::DeviceIoControl, winioctl::FSCTL_SET_SPARSE};
+
+/// `FILE_SET_SPARSE_BUFFER`, from <winioctl.h>.
+#[repr(C)]
+#[allow(non_snake_case)]
+struct FILE_SET_SPARSE_BUFFER {
+    SetSparse: u8,
+}
+
+#[allow(unsafe_code)]
+pub(crate) fn set_sparse_impl(file: &fs::File, sparse: bool) -> io::Result<()> {
+    let mut buffer = FILE_SET_SPARSE_BUFFER {
+        SetSparse: sparse.into(),
+    };
+    let mut returned = 0;
+
+    // Safety: `file` owns a valid handle for the duration of the call, and
+    // `buffer` is a `FILE_SET_SPARSE_BUFFER` of the size we pass.
+    let ok = unsafe {
+        DeviceIoControl(
+            file.as_raw_handle().cast(),
+            FSCTL_SET_SPARSE,
+            (&mut buffer as *mut FILE_SET_SPARSE_BUFFER).cast(),
+            std::mem::size_of_val(&buffer) as u32,
+            ptr::null_mut(),
+            0,
+            &mut returned,
+            ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
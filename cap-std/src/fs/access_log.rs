@@ -0,0 +1,122 @@
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// How a path recorded in an [`AccessLog`] was accessed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum AccessKind {
+    /// The file was opened without write access.
+    Read,
+
+    /// The file was opened with write or append access, possibly creating
+    /// it.
+    Write,
+
+    /// The path's metadata was queried, as with [`Dir::metadata`] or
+    /// [`Dir::exists`].
+    ///
+    /// [`Dir::metadata`]: crate::fs::Dir::metadata
+    /// [`Dir::exists`]: crate::fs::Dir::exists
+    Stat,
+}
+
+/// One access recorded in an [`AccessLog`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Access {
+    path: PathBuf,
+    kind: AccessKind,
+}
+
+impl Access {
+    /// Returns the path which was accessed, relative to the `Dir` which
+    /// [`Dir::with_access_recorder`] was called on.
+    ///
+    /// [`Dir::with_access_recorder`]: crate::fs::Dir::with_access_recorder
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns how the path was accessed.
+    #[inline]
+    pub fn kind(&self) -> AccessKind {
+        self.kind
+    }
+}
+
+/// A shared log of the paths accessed through a `Dir` and the handles
+/// derived from it, returned by [`Dir::with_access_recorder`].
+///
+/// Clones of an `AccessLog` share the same log.
+///
+/// [`Dir::with_access_recorder`]: crate::fs::Dir::with_access_recorder
+#[derive(Clone, Debug, Default)]
+pub struct AccessLog {
+    accesses: Arc<Mutex<Vec<Access>>>,
+}
+
+impl AccessLog {
+    /// Returns the accesses recorded so far, in the order they happened.
+    pub fn accesses(&self) -> Vec<Access> {
+        self.accesses.lock().unwrap().clone()
+    }
+
+    /// Returns the accesses recorded so far, in the order they happened,
+    /// and clears the log.
+    pub fn take(&self) -> Vec<Access> {
+        std::mem::take(&mut *self.accesses.lock().unwrap())
+    }
+}
+
+/// The recording state of a `Dir`, `ReadDir`, or `DirEntry`: the log, and
+/// the path of the handle relative to where recording started.
+#[derive(Clone, Debug)]
+pub(crate) struct Recorder {
+    log: AccessLog,
+    prefix: PathBuf,
+}
+
+impl Recorder {
+    pub(crate) fn new(log: AccessLog) -> Self {
+        Self {
+            log,
+            prefix: PathBuf::new(),
+        }
+    }
+
+    /// Record an access to `path`, relative to this handle.
+    pub(crate) fn record(&self, path: &Path, kind: AccessKind) {
+        let mut path = self.join(path);
+        if path.as_os_str().is_empty() {
+            path.push(Component::CurDir);
+        }
+        self.log
+            .accesses
+            .lock()
+            .unwrap()
+            .push(Access { path, kind });
+    }
+
+    /// Returns a `Recorder` for a handle opened at `path`, relative to this
+    /// handle.
+    pub(crate) fn descend(&self, path: &Path) -> Self {
+        Self {
+            log: self.log.clone(),
+            prefix: self.join(path),
+        }
+    }
+
+    fn join(&self, path: &Path) -> PathBuf {
+        // A lone `/` refers to the `Dir` itself, so don't let it replace the
+        // prefix, and leave out `.`s, which don't say anything.
+        let mut joined = self.prefix.clone();
+        for component in path.components() {
+            match component {
+                Component::Prefix(_) | Component::RootDir | Component::CurDir => (),
+                component => joined.push(component),
+            }
+        }
+        joined
+    }
+}
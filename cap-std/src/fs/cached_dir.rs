@@ -0,0 +1,285 @@
+use crate::fs::{Dir, File, Metadata, OpenOptions};
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The number of entries past which expired entries are pruned when a new
+/// one is added.
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// A [`Dir`] which briefly remembers paths that were found not to exist.
+///
+/// This is intended for code which repeatedly probes for optional files
+/// that usually don't exist. When [`open`], [`metadata`], or one of the
+/// functions built on them fails with [`io::ErrorKind::NotFound`], the path
+/// is remembered for `ttl`, and further lookups of the same path within that
+/// window fail immediately, without asking the OS.
+///
+/// This means that if the path is created by anything other than this
+/// `CachedDir`, including another process or the [`Dir`] it wraps, lookups
+/// can keep reporting it as missing for up to `ttl`. Operations through the
+/// `CachedDir` which may create something, such as [`write`], [`create_dir`],
+/// and [`open_with`], clear the whole cache, since the same file can be named
+/// by many different paths. Only use this for lookups where that staleness
+/// is acceptable, and use [`dir`] for everything else.
+///
+/// Paths are remembered as they're given, so `a` and `./a` are cached
+/// separately, and only `NotFound` errors carrying an OS error code are
+/// cached.
+///
+/// [`open`]: CachedDir::open
+/// [`metadata`]: CachedDir::metadata
+/// [`write`]: CachedDir::write
+/// [`create_dir`]: CachedDir::create_dir
+/// [`open_with`]: CachedDir::open_with
+/// [`dir`]: CachedDir::dir
+#[derive(Debug)]
+pub struct CachedDir {
+    dir: Dir,
+    ttl: Duration,
+    missing: Mutex<Missing>,
+}
+
+/// Paths remembered as missing, with when they were found missing and the
+/// OS error code they failed with.
+#[derive(Debug, Default)]
+struct Missing {
+    entries: HashMap<PathBuf, (Instant, i32)>,
+
+    /// Incremented each time the cache is cleared, so that a lookup which
+    /// races with a create doesn't remember a path that may now exist.
+    generation: u64,
+}
+
+impl CachedDir {
+    /// Constructs a new instance of `Self` which remembers missing paths in
+    /// `dir` for `ttl`.
+    #[inline]
+    pub fn from_dir(dir: Dir, ttl: Duration) -> Self {
+        Self {
+            dir,
+            ttl,
+            missing: Mutex::new(Missing::default()),
+        }
+    }
+
+    /// Returns the underlying directory.
+    ///
+    /// Operations on the returned `Dir` don't consult or update the cache.
+    #[inline]
+    pub fn dir(&self) -> &Dir {
+        &self.dir
+    }
+
+    /// Consumes `self` and returns the underlying directory.
+    #[inline]
+    pub fn into_dir(self) -> Dir {
+        self.dir
+    }
+
+    /// Forgets all remembered missing paths.
+    #[inline]
+    pub fn invalidate(&self) {
+        let mut missing = self.missing.lock().unwrap();
+        missing.entries.clear();
+        missing.generation += 1;
+    }
+
+    /// Attempts to open a file in read-only mode.
+    ///
+    /// This corresponds to [`Dir::open`], but fails immediately if `path`
+    /// was recently found not to exist.
+    #[inline]
+    pub fn open<P: AsRef<Path>>(&self, path: P) -> io::Result<File> {
+        self.cached(path.as_ref(), |path| self.dir.open(path))
+    }
+
+    /// Opens a file at `path` with the options specified by `options`.
+    ///
+    /// This corresponds to [`Dir::open_with`]. Since `options` may create
+    /// the file, this always asks the OS, and clears the cache if it
+    /// succeeds.
+    #[inline]
+    pub fn open_with<P: AsRef<Path>>(&self, path: P, options: &OpenOptions) -> io::Result<File> {
+        let file = self.dir.open_with(path, options)?;
+        self.invalidate();
+        Ok(file)
+    }
+
+    /// Given a path, query the file system to get information about a file,
+    /// directory, etc.
+    ///
+    /// This corresponds to [`Dir::metadata`], but fails immediately if `path`
+    /// was recently found not to exist.
+    #[inline]
+    pub fn metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        self.cached(path.as_ref(), |path| self.dir.metadata(path))
+    }
+
+    /// Read the entire contents of a file into a bytes vector.
+    ///
+    /// This corresponds to [`Dir::read`], but fails immediately if `path`
+    /// was recently found not to exist.
+    #[inline]
+    pub fn read<P: AsRef<Path>>(&self, path: P) -> io::Result<Vec<u8>> {
+        let mut file = self.open(path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Returns `true` if the path points at an existing entity.
+    ///
+    /// This corresponds to [`Dir::exists`], but returns `false` immediately
+    /// if `path` was recently found not to exist.
+    #[inline]
+    pub fn exists<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.metadata(path).is_ok()
+    }
+
+    /// Returns `true` if the path exists on disk and is pointing at a regular
+    /// file.
+    ///
+    /// This corresponds to [`Dir::is_file`], but returns `false` immediately
+    /// if `path` was recently found not to exist.
+    #[inline]
+    pub fn is_file<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.metadata(path).map(|m| m.is_file()).unwrap_or(false)
+    }
+
+    /// Checks if `path` is a directory.
+    ///
+    /// This corresponds to [`Dir::is_dir`], but returns `false` immediately
+    /// if `path` was recently found not to exist.
+    #[inline]
+    pub fn is_dir<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.metadata(path).map(|m| m.is_dir()).unwrap_or(false)
+    }
+
+    /// Write a slice as the entire contents of a file.
+    ///
+    /// This corresponds to [`Dir::write`], and clears the cache.
+    #[inline]
+    pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(&self, path: P, contents: C) -> io::Result<()> {
+        // Clear the cache even on failure, since the file may have been
+        // created before the write failed.
+        let result = self.dir.write(path, contents);
+        self.invalidate();
+        result
+    }
+
+    /// Creates a new, empty directory at the provided path.
+    ///
+    /// This corresponds to [`Dir::create_dir`], and clears the cache.
+    #[inline]
+    pub fn create_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.dir.create_dir(path)?;
+        self.invalidate();
+        Ok(())
+    }
+
+    /// Recursively create a directory and all of its parent components if
+    /// they are missing.
+    ///
+    /// This corresponds to [`Dir::create_dir_all`], and clears the cache.
+    #[inline]
+    pub fn create_dir_all<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        // Clear the cache even on failure, since some of the parents may
+        // have been created.
+        let result = self.dir.create_dir_all(path);
+        self.invalidate();
+        result
+    }
+
+    /// Look `path` up with `lookup`, unless it's remembered as missing, and
+    /// remember it if `lookup` finds it missing.
+    fn cached<T>(&self, path: &Path, lookup: impl FnOnce(&Path) -> io::Result<T>) -> io::Result<T> {
+        let generation = {
+            let mut missing = self.missing.lock().unwrap();
+            if let Some((when, code)) = missing.entries.get(path) {
+                if when.elapsed() < self.ttl {
+                    return Err(io::Error::from_raw_os_error(*code));
+                }
+                missing.entries.remove(path);
+            }
+            missing.generation
+        };
+
+        let result = lookup(path);
+        if let Err(err) = &result {
+            if err.kind() == io::ErrorKind::NotFound {
+                if let Some(code) = err.raw_os_error() {
+                    self.remember(path, code, generation);
+                }
+            }
+        }
+        result
+    }
+
+    /// Remember `path` as missing, unless the cache has been cleared since
+    /// `generation`.
+    fn remember(&self, path: &Path, code: i32, generation: u64) {
+        let mut missing = self.missing.lock().unwrap();
+        if missing.generation != generation {
+            return;
+        }
+        if missing.entries.len() >= PRUNE_THRESHOLD {
+            let ttl = self.ttl;
+            missing.entries.retain(|_, (when, _)| when.elapsed() < ttl);
+        }
+        missing
+            .entries
+            .insert(path.to_path_buf(), (Instant::now(), code));
+    }
+}
+
+/// Test that a remembered path is answered without calling the lookup, and
+/// that it's looked up again once `ttl` has passed.
+#[test]
+fn cached_lookups() {
+    use std::{cell::Cell, thread};
+
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = Dir::open_ambient_dir(tmp.path(), crate::ambient_authority()).unwrap();
+    let cached = CachedDir::from_dir(dir, Duration::from_millis(50));
+    let lookups = Cell::new(0);
+    let lookup = |path: &Path| {
+        lookups.set(lookups.get() + 1);
+        cached.dir().metadata(path)
+    };
+
+    let path = Path::new("cached-dir-probe");
+    cached.cached(path, lookup).unwrap_err();
+    assert_eq!(lookups.get(), 1);
+    for _ in 0..3 {
+        let err = cached.cached(path, lookup).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+    assert_eq!(lookups.get(), 1);
+
+    // Other paths are looked up.
+    cached
+        .cached(Path::new("cached-dir-other"), lookup)
+        .unwrap_err();
+    assert_eq!(lookups.get(), 2);
+
+    // Once `ttl` has passed, `path` is looked up again.
+    thread::sleep(Duration::from_millis(100));
+    cached.cached(path, lookup).unwrap_err();
+    assert_eq!(lookups.get(), 3);
+    cached.cached(path, lookup).unwrap_err();
+    assert_eq!(lookups.get(), 3);
+
+    // Errors other than `NotFound` aren't remembered.
+    let denied = |_: &Path| -> io::Result<Metadata> {
+        lookups.set(lookups.get() + 1);
+        Err(io::ErrorKind::PermissionDenied.into())
+    };
+    cached.cached(Path::new("denied"), denied).unwrap_err();
+    cached.cached(Path::new("denied"), denied).unwrap_err();
+    assert_eq!(lookups.get(), 5);
+}
@@ -0,0 +1,281 @@
+#[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+use crate::fs::AccessKind;
+use crate::fs::{Dir, File, Metadata, OpenOptions, ReadDir};
+use cap_primitives::fs::{open_dir_in_root, open_in_root, stat_in_root, FollowSymlinks};
+use std::{
+    ffi::OsStr,
+    io::{self, Read, Write},
+    path::{Component, Path, PathBuf},
+};
+use unsafe_io::AsUnsafeFile;
+
+/// A [`Dir`] which is treated as the root of a filesystem, such as a
+/// container's root filesystem.
+///
+/// Paths passed to `Container`'s methods are resolved as they would be
+/// inside a `chroot` of the directory: absolute paths, such as those found in
+/// container manifests, and absolute symlink targets are resolved relative to
+/// the root, and `..` at the root stays at the root, rather than failing as
+/// an escape attempt.
+///
+/// Every method resolves paths the same way, using `openat2` with
+/// `RESOLVE_IN_ROOT` where available, and otherwise resolving one component
+/// at a time with the same rules. Methods which operate on a directory entry
+/// itself, such as [`remove_file`], resolve the entry's parent this way and
+/// then operate on the final component within it.
+///
+/// [`remove_file`]: Container::remove_file
+#[derive(Debug)]
+pub struct Container {
+    root: Dir,
+}
+
+impl Container {
+    /// Constructs a new instance of `Self` with `root` as its root directory.
+    #[inline]
+    pub fn from_dir(root: Dir) -> Self {
+        Self { root }
+    }
+
+    /// Returns the root directory.
+    #[inline]
+    pub fn root(&self) -> &Dir {
+        &self.root
+    }
+
+    /// Consumes `self` and returns the root directory.
+    #[inline]
+    pub fn into_dir(self) -> Dir {
+        self.root
+    }
+
+    /// Attempts to open a file in read-only mode.
+    ///
+    /// This corresponds to [`Dir::open`], with `path` resolved within the
+    /// container.
+    #[inline]
+    pub fn open<P: AsRef<Path>>(&self, path: P) -> io::Result<File> {
+        self.open_with(path, OpenOptions::new().read(true))
+    }
+
+    /// Opens a file at `path` with the options specified by `options`.
+    ///
+    /// This corresponds to [`Dir::open_with`], with `path` resolved within
+    /// the container.
+    #[inline]
+    pub fn open_with<P: AsRef<Path>>(&self, path: P, options: &OpenOptions) -> io::Result<File> {
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.root.record_open(path.as_ref(), options);
+
+        let file = open_in_root(
+            &self.root.as_file_view(),
+            path.as_ref(),
+            &*self.root.file_options(options)?,
+        )?;
+        self.root.file_from_opened(file, options)
+    }
+
+    /// Attempts to open a directory.
+    ///
+    /// This corresponds to [`Dir::open_dir`], with `path` resolved within
+    /// the container.
+    #[inline]
+    pub fn open_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<Dir> {
+        let dir = open_dir_in_root(&self.root.as_file_view(), path.as_ref())?;
+        Ok(self.root.subdir(path.as_ref(), dir))
+    }
+
+    /// Opens a file in write-only mode.
+    ///
+    /// This corresponds to [`Dir::create`], with `path` resolved within the
+    /// container.
+    #[inline]
+    pub fn create<P: AsRef<Path>>(&self, path: P) -> io::Result<File> {
+        self.open_with(
+            path,
+            OpenOptions::new().write(true).create(true).truncate(true),
+        )
+    }
+
+    /// Creates a new, empty directory at the provided path.
+    ///
+    /// This corresponds to [`Dir::create_dir`], with `path` resolved within
+    /// the container.
+    #[inline]
+    pub fn create_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let (parent, name) = self.parent_and_name(path.as_ref())?;
+        parent.create_dir(name)
+    }
+
+    /// Recursively create a directory and all of its parent components if
+    /// they are missing.
+    ///
+    /// This corresponds to [`Dir::create_dir_all`], with `path` resolved
+    /// within the container.
+    #[inline]
+    pub fn create_dir_all<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        // Create each prefix of `path` ending in a normal component, so that
+        // `..` and symlinks are resolved as they are everywhere else.
+        let mut prefix = PathBuf::new();
+        for component in path.as_ref().components() {
+            prefix.push(component);
+            if let Component::Normal(_) = component {
+                match self.create_dir(&prefix) {
+                    Ok(()) => (),
+                    Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                        if !self.metadata(&prefix)?.is_dir() {
+                            return Err(err);
+                        }
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Given a path, query the file system to get information about a file,
+    /// directory, etc.
+    ///
+    /// This corresponds to [`Dir::metadata`], with `path` resolved within
+    /// the container.
+    #[inline]
+    pub fn metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.root.record(path.as_ref(), AccessKind::Stat);
+
+        stat_in_root(
+            &self.root.as_file_view(),
+            path.as_ref(),
+            FollowSymlinks::Yes,
+        )
+    }
+
+    /// Query the metadata about a file without following symlinks.
+    ///
+    /// This corresponds to [`Dir::symlink_metadata`], with `path` resolved
+    /// within the container.
+    #[inline]
+    pub fn symlink_metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.root.record(path.as_ref(), AccessKind::Stat);
+
+        stat_in_root(&self.root.as_file_view(), path.as_ref(), FollowSymlinks::No)
+    }
+
+    /// Returns an iterator over the entries within a directory.
+    ///
+    /// This corresponds to [`Dir::read_dir`], with `path` resolved within
+    /// the container.
+    #[inline]
+    pub fn read_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<ReadDir> {
+        self.open_dir(path)?.entries()
+    }
+
+    /// Read the entire contents of a file into a bytes vector.
+    ///
+    /// This corresponds to [`Dir::read`], with `path` resolved within the
+    /// container.
+    #[inline]
+    pub fn read<P: AsRef<Path>>(&self, path: P) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.open(path)?.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Read the entire contents of a file into a string.
+    ///
+    /// This corresponds to [`Dir::read_to_string`], with `path` resolved
+    /// within the container.
+    #[inline]
+    pub fn read_to_string<P: AsRef<Path>>(&self, path: P) -> io::Result<String> {
+        let mut s = String::new();
+        self.open(path)?.read_to_string(&mut s)?;
+        Ok(s)
+    }
+
+    /// Write a slice as the entire contents of a file.
+    ///
+    /// This corresponds to [`Dir::write`], with `path` resolved within the
+    /// container.
+    #[inline]
+    pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(&self, path: P, contents: C) -> io::Result<()> {
+        self.create(path)?.write_all(contents.as_ref())
+    }
+
+    /// Removes a file from a filesystem.
+    ///
+    /// This corresponds to [`Dir::remove_file`], with `path` resolved within
+    /// the container.
+    #[inline]
+    pub fn remove_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let (parent, name) = self.parent_and_name(path.as_ref())?;
+        parent.remove_file(name)
+    }
+
+    /// Removes an empty directory.
+    ///
+    /// This corresponds to [`Dir::remove_dir`], with `path` resolved within
+    /// the container.
+    #[inline]
+    pub fn remove_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let (parent, name) = self.parent_and_name(path.as_ref())?;
+        parent.remove_dir(name)
+    }
+
+    /// Removes a directory at this path, after removing all its contents.
+    /// Use carefully!
+    ///
+    /// This corresponds to [`Dir::remove_dir_all`], with `path` resolved
+    /// within the container.
+    #[inline]
+    pub fn remove_dir_all<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let (parent, name) = self.parent_and_name(path.as_ref())?;
+        parent.remove_dir_all(name)
+    }
+
+    /// Rename a file or directory to a new name, replacing the original file
+    /// if `to` already exists.
+    ///
+    /// This corresponds to [`Dir::rename`], with both paths resolved within
+    /// the container.
+    #[inline]
+    pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(&self, from: P, to: Q) -> io::Result<()> {
+        let (from_parent, from_name) = self.parent_and_name(from.as_ref())?;
+        let (to_parent, to_name) = self.parent_and_name(to.as_ref())?;
+        from_parent.rename(from_name, &to_parent, to_name)
+    }
+
+    /// Returns `true` if the path points at an existing entity.
+    ///
+    /// This corresponds to [`Dir::exists`], with `path` resolved within the
+    /// container.
+    #[inline]
+    pub fn exists<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.metadata(path).is_ok()
+    }
+
+    /// Opens the parent of `path` within the container, and returns it with
+    /// the final component of `path`, for operations on the entry itself
+    /// rather than on what it refers to.
+    fn parent_and_name<'p>(&self, path: &'p Path) -> io::Result<(Dir, &'p OsStr)> {
+        let name = match path.components().next_back() {
+            Some(Component::Normal(name)) => name,
+            _ => return Err(no_entry_name()),
+        };
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Component::CurDir.as_ref(),
+        };
+        let dir = open_dir_in_root(&self.root.as_file_view(), parent)?;
+        Ok((self.root.subdir(parent, dir), name))
+    }
+}
+
+#[cold]
+fn no_entry_name() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "path does not end in the name of a directory entry",
+    )
+}
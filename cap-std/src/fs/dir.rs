@@ -1,13 +1,27 @@
-use crate::fs::{DirBuilder, File, Metadata, OpenOptions, ReadDir};
+#[cfg(feature = "normalize_names")]
+use crate::fs::NormalizedDirEntry;
+#[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+use crate::fs::{access_log::Recorder, AccessKind, AccessLog};
+use crate::fs::{
+    DirBuilder, DirId, File, FileType, FsType, LockGuard, Metadata, OpenOptions, OverwritePolicy,
+    ReadDir, SymlinkTargetType,
+};
 #[cfg(unix)]
 use crate::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+#[cfg(not(target_os = "wasi"))]
+use crate::{fs::ReadDirModifiedSince, time::SystemTime};
+#[cfg(all(feature = "trace_resolution", not(target_os = "wasi")))]
+use cap_primitives::fs::{open_traced, ResolutionStep};
 use cap_primitives::{
     ambient_authority,
     fs::{
-        canonicalize, copy, create_dir, hard_link, open, open_ambient_dir, open_dir, read_base_dir,
-        read_dir, read_link, remove_dir, remove_dir_all, remove_file, remove_open_dir,
-        remove_open_dir_all, rename, set_permissions, stat, DirOptions, FollowSymlinks,
-        Permissions,
+        canonicalize, canonicalize_with_chain, copy, copy_throttled, copy_verified,
+        copy_with_policy, create_dir, create_named_pipe, for_each_entry, hard_link,
+        lock_file_exclusive, move_file, open, open_ambient_dir, open_and_resolve, open_child,
+        open_dir, open_if, read_base_dir, read_dir, read_link, remove_dir, remove_dir_all,
+        remove_file, remove_open_dir, remove_open_dir_all, rename, set_acl, set_permissions, stat,
+        symlink_with_type, try_lock_file_exclusive, Acl, DirOptions, FollowSymlinks, Permissions,
+        PipeOptions,
     },
     AmbientAuthority,
 };
@@ -18,9 +32,15 @@ use io_lifetimes::{AsHandle, BorrowedHandle, FromHandle, IntoHandle, OwnedHandle
 #[cfg(target_os = "wasi")]
 use posish::fs::OpenOptionsExt;
 use std::{
+    borrow::Cow,
+    cmp,
+    ffi::OsStr,
     fmt, fs,
     io::{self, Read, Write},
-    path::{Path, PathBuf},
+    ops::ControlFlow,
+    path::{Component, Path, PathBuf},
+    thread,
+    time::Duration,
 };
 use unsafe_io::OwnsRaw;
 #[cfg(not(windows))]
@@ -47,6 +67,128 @@ use {
 /// [functions in `std::fs`]: https://doc.rust-lang.org/std/fs/index.html#functions
 pub struct Dir {
     std_file: fs::File,
+    settings: DirSettings,
+}
+
+/// The settings of a `Dir` which carry over to the directories, iterators,
+/// and entries derived from it.
+#[derive(Clone, Default)]
+pub(crate) struct DirSettings {
+    #[cfg(not(target_os = "wasi"))]
+    max_file_size: Option<u64>,
+    #[cfg(not(target_os = "wasi"))]
+    default_file_mode: Option<u32>,
+    #[cfg(not(target_os = "wasi"))]
+    default_dir_mode: Option<u32>,
+    #[cfg(not(target_os = "wasi"))]
+    no_create: bool,
+    #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+    recorder: Option<Recorder>,
+}
+
+impl DirSettings {
+    /// Returns the settings for whatever is at `path` within a `Dir` with
+    /// these settings.
+    #[inline]
+    pub(crate) fn descend(&self, path: &Path) -> Self {
+        #[cfg(not(all(feature = "record_access", not(target_os = "wasi"))))]
+        let _ = path;
+        Self {
+            #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+            recorder: self.recorder.as_ref().map(|r| r.descend(path)),
+            ..self.clone()
+        }
+    }
+
+    /// Constructs a `Dir` for `std_file`, which was opened at `path` within a
+    /// `Dir` with these settings.
+    #[inline]
+    pub(crate) fn subdir(&self, path: &Path, std_file: fs::File) -> Dir {
+        Dir {
+            std_file,
+            settings: self.descend(path),
+        }
+    }
+
+    /// Record an access to `path` if these settings are recording accesses.
+    #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+    #[inline]
+    pub(crate) fn record(&self, path: &Path, kind: AccessKind) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(path, kind);
+        }
+    }
+
+    /// Record opening `path` with `options` if these settings are recording
+    /// accesses.
+    #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+    #[inline]
+    pub(crate) fn record_open(&self, path: &Path, options: &OpenOptions) {
+        self.record(
+            path,
+            if options._cap_std_writes() {
+                AccessKind::Write
+            } else {
+                AccessKind::Read
+            },
+        );
+    }
+
+    /// Returns `options`, with the default file mode applied if there is
+    /// one, or an error if they'd create a file and that's disallowed.
+    #[cfg(not(target_os = "wasi"))]
+    pub(crate) fn file_options<'a>(
+        &self,
+        options: &'a OpenOptions,
+    ) -> io::Result<Cow<'a, OpenOptions>> {
+        if self.no_create && options._cap_std_creates() {
+            return Err(creation_disabled());
+        }
+        Ok(match self.default_file_mode {
+            Some(mode) => {
+                let mut options = options.clone();
+                options._cap_std_default_mode(mode);
+                Cow::Owned(options)
+            }
+            None => Cow::Borrowed(options),
+        })
+    }
+
+    /// Returns `dir_options`, with the default directory mode applied if
+    /// there is one.
+    fn dir_options<'a>(&self, dir_options: &'a DirOptions) -> Cow<'a, DirOptions> {
+        #[cfg(not(target_os = "wasi"))]
+        if let Some(mode) = self.default_dir_mode {
+            let mut dir_options = dir_options.clone();
+            dir_options._cap_std_default_mode(mode);
+            return Cow::Owned(dir_options);
+        }
+        Cow::Borrowed(dir_options)
+    }
+
+    /// Returns pipe `options`, with the default file mode applied if there
+    /// is one.
+    fn pipe_options<'a>(&self, options: &'a PipeOptions) -> Cow<'a, PipeOptions> {
+        #[cfg(not(target_os = "wasi"))]
+        if let Some(mode) = self.default_file_mode {
+            let mut options = options.clone();
+            options._cap_std_default_mode(mode);
+            return Cow::Owned(options);
+        }
+        Cow::Borrowed(options)
+    }
+
+    /// Constructs a `File` for `std`, which was opened with `options`,
+    /// applying the size limit.
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub(crate) fn file_from_opened(
+        &self,
+        std: fs::File,
+        options: &OpenOptions,
+    ) -> io::Result<File> {
+        File::from_opened(std, options, self.max_file_size)
+    }
 }
 
 impl Dir {
@@ -61,7 +203,160 @@ impl Dir {
     /// process has access to.
     #[inline]
     pub fn from_std_file(std_file: fs::File, _: AmbientAuthority) -> Self {
-        Self { std_file }
+        Self {
+            std_file,
+            settings: DirSettings::default(),
+        }
+    }
+
+    /// Constructs a `Dir` for `std_file`, which was opened at `path` through
+    /// `self`, carrying over `self`'s size limit, default modes, creation
+    /// restriction, and access recorder.
+    #[inline]
+    pub(crate) fn subdir(&self, path: &Path, std_file: fs::File) -> Self {
+        self.settings.subdir(path, std_file)
+    }
+
+    /// Limits the size of files opened through `self` to `max_file_size`
+    /// bytes.
+    ///
+    /// Writes through the returned [`File`]s which would extend a file past
+    /// the limit fail with the OS's "file too large" error (`EFBIG`, or
+    /// `ERROR_FILE_TOO_LARGE` on Windows); `write` writes as much as fits,
+    /// and `write_all` fails without writing anything. The limit carries
+    /// over to directories opened with [`Dir::open_dir`] and to entries
+    /// from [`Dir::entries`] and [`Dir::read_dir`].
+    ///
+    /// This is an application-level guard, which complements, rather than
+    /// replaces, OS resource limits: it only covers writes through handles
+    /// opened through `self`, and not, for example, [`Dir::copy`] or writes
+    /// by other handles or processes to the same files.
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.settings.max_file_size = Some(max_file_size);
+        self
+    }
+
+    /// Sets the mode used for files created through `self`, when the
+    /// [`OpenOptions`] don't specify one with `OpenOptionsExt::mode`, and
+    /// for FIFOs created with [`Dir::create_named_pipe`].
+    ///
+    /// As with an explicit mode, the process umask still applies. The
+    /// default carries over to directories opened with [`Dir::open_dir`],
+    /// so it can serve as the permission policy for a whole subtree.
+    ///
+    /// On Windows, which has no mode bits, this is ignored.
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn with_default_file_mode(mut self, mode: u32) -> Self {
+        self.settings.default_file_mode = Some(mode);
+        self
+    }
+
+    /// Sets the mode used for directories created through `self`, when the
+    /// [`DirBuilder`] doesn't specify one with `DirBuilderExt::mode`.
+    ///
+    /// As with an explicit mode, the process umask still applies. The
+    /// default carries over to directories opened with [`Dir::open_dir`],
+    /// so it can serve as the permission policy for a whole subtree.
+    ///
+    /// On Windows, which has no mode bits, this is ignored.
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn with_default_dir_mode(mut self, mode: u32) -> Self {
+        self.settings.default_dir_mode = Some(mode);
+        self
+    }
+
+    /// Disallows creating files through `self`.
+    ///
+    /// Opens through `self` whose [`OpenOptions`] request `create` or
+    /// `create_new` fail with [`io::ErrorKind::PermissionDenied`], whether
+    /// or not the file exists, and so do functions which open that way,
+    /// such as [`Dir::create`] and [`Dir::write`]. Existing files can still
+    /// be opened for writing and modified, so this is narrower than a
+    /// read-only restriction. The restriction carries over to directories
+    /// opened with [`Dir::open_dir`].
+    ///
+    /// This only covers opening files; it doesn't prevent, for example,
+    /// [`Dir::create_dir`], [`Dir::copy`], or [`Dir::symlink`].
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn with_no_create(mut self) -> Self {
+        self.settings.no_create = true;
+        self
+    }
+
+    /// Returns a `Dir` which records the paths accessed through it, and an
+    /// [`AccessLog`] to read them from.
+    ///
+    /// This is meant for build systems and similar tools which want to
+    /// discover a task's inputs and outputs. Files opened through the `Dir`
+    /// are recorded as [`AccessKind::Read`] or [`AccessKind::Write`],
+    /// depending on whether they're opened for writing, and metadata
+    /// queries, including [`Dir::exists`], as [`AccessKind::Stat`]. This
+    /// covers every way of opening a file, such as [`Dir::open_child`] and
+    /// [`Dir::open_if`], and functions built on them, such as [`Dir::read`]
+    /// and [`Dir::write`]. Copies record the source as read and the
+    /// destination as written, in whichever of the two `Dir`s record
+    /// accesses. Recording carries over to directories opened with
+    /// [`Dir::open_dir`] and [`Dir::try_clone`], to entries from
+    /// [`Dir::entries`] and [`Dir::read_dir`], and to a [`Container`] made
+    /// from the `Dir`.
+    ///
+    /// [`Container`]: crate::fs::Container
+    ///
+    /// Paths are recorded relative to `self`, as they were passed in,
+    /// without resolving symlinks or `..`, and are recorded whether or not
+    /// the access succeeds, since a missing file can be a dependency too.
+    /// Directory listings, removals, renames, and links aren't recorded.
+    #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+    #[inline]
+    pub fn with_access_recorder(mut self) -> (Self, AccessLog) {
+        let log = AccessLog::default();
+        self.settings.recorder = Some(Recorder::new(log.clone()));
+        (self, log)
+    }
+
+    /// Record an access to `path` if `self` is recording accesses.
+    #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+    #[inline]
+    pub(crate) fn record(&self, path: &Path, kind: AccessKind) {
+        self.settings.record(path, kind)
+    }
+
+    /// Record opening `path` with `options` if `self` is recording accesses.
+    #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+    #[inline]
+    pub(crate) fn record_open(&self, path: &Path, options: &OpenOptions) {
+        self.settings.record_open(path, options)
+    }
+
+    /// Record copying `from` in `self` to `to` in `to_dir`, for whichever of
+    /// them are recording accesses.
+    #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+    #[inline]
+    fn record_copy(&self, from: &Path, to_dir: &Self, to: &Path) {
+        self.record(from, AccessKind::Read);
+        to_dir.record(to, AccessKind::Write);
+    }
+
+    /// Returns `options`, with `self`'s default file mode applied if it has
+    /// one, or an error if they'd create a file and `self` disallows that.
+    #[cfg(not(target_os = "wasi"))]
+    pub(crate) fn file_options<'a>(
+        &self,
+        options: &'a OpenOptions,
+    ) -> io::Result<Cow<'a, OpenOptions>> {
+        self.settings.file_options(options)
+    }
+
+    /// Returns `dir_options`, with `self`'s default directory mode applied if
+    /// it has one.
+    #[inline]
+    fn dir_options<'a>(&self, dir_options: &'a DirOptions) -> Cow<'a, DirOptions> {
+        self.settings.dir_options(dir_options)
     }
 
     /// Consumes `self` and returns a [`std::fs::File`].
@@ -79,22 +374,206 @@ impl Dir {
         self.open_with(path, OpenOptions::new().read(true))
     }
 
+    /// Attempts to open a file in read-only mode, for reading sequentially.
+    ///
+    /// This is like [`Dir::open`], but on Linux it also advises the kernel
+    /// with `POSIX_FADV_SEQUENTIAL` that the whole file will be read in
+    /// order, so that it can read ahead more aggressively. Elsewhere, this is
+    /// just [`Dir::open`].
+    #[inline]
+    pub fn open_sequential<P: AsRef<Path>>(&self, path: P) -> io::Result<File> {
+        let file = self.open(path)?;
+
+        // The advice is just a hint, so ignore errors, such as `ESPIPE` for
+        // FIFOs.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        posish::fs::fadvise(&file.std, 0, 0, posish::fs::Advice::Sequential).ok();
+
+        Ok(file)
+    }
+
     /// Opens a file at `path` with the options specified by `self`.
     ///
     /// This corresponds to [`std::fs::OpenOptions::open`].
     ///
     /// Instead of being a method on `OpenOptions`, this is a method on `Dir`,
-    /// and it only accesses paths relative to `self`.
+    /// and it only accesses paths relative to `self`. An empty path fails
+    /// with [`io::ErrorKind::NotFound`], and a lone `/` refers to `self`
+    /// itself rather than the host root; any other absolute path fails.
     #[inline]
     pub fn open_with<P: AsRef<Path>>(&self, path: P, options: &OpenOptions) -> io::Result<File> {
-        self._open_with(path.as_ref(), options)
+        let path = path.as_ref();
+        traced!("open", path = path => self._open_with(path, options))
+    }
+
+    /// Opens a file at `path` with the options specified by `options`,
+    /// retrying up to `retries` times if it fails because the file is an
+    /// executable which is currently running.
+    ///
+    /// Opening a running executable for writing fails with `ETXTBSY`, which
+    /// is usually brief when replacing a binary whose old process is
+    /// exiting. Retries wait 10 milliseconds at first, doubling each time up
+    /// to one second. If the file is still busy after the last retry, this
+    /// fails with the last `ETXTBSY` error.
+    ///
+    /// On Windows, running executables are protected by sharing modes
+    /// rather than `ETXTBSY`, so this is equivalent to [`Dir::open_with`].
+    pub fn open_with_busy_retry<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &OpenOptions,
+        retries: u32,
+    ) -> io::Result<File> {
+        let path = path.as_ref();
+        let mut delay = Duration::from_millis(10);
+        for _ in 0..retries {
+            match self.open_with(path, options) {
+                Err(err) if is_text_file_busy(&err) => (),
+                result => return result,
+            }
+            thread::sleep(delay);
+            delay = cmp::min(delay * 2, Duration::from_secs(1));
+        }
+        self.open_with(path, options)
+    }
+
+    /// Opens a file at `path` with the options specified by `options`, and
+    /// also returns the canonical path of the opened file, relative to
+    /// `self`.
+    ///
+    /// This is equivalent to [`Dir::open_with`] followed by
+    /// [`Dir::canonicalize`], but where possible the path is computed from
+    /// the opened handle, or recorded while resolving `path`, rather than by
+    /// resolving `path` a second time.
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn open_and_resolve<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &OpenOptions,
+    ) -> io::Result<(File, PathBuf)> {
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.record_open(path.as_ref(), options);
+
+        let (file, canonical_path) =
+            open_and_resolve(&self.std_file, path.as_ref(), &*self.file_options(options)?)?;
+        Ok((self.file_from_opened(file, options)?, canonical_path))
+    }
+
+    /// Opens the entry named `name` in `self` with the options specified by
+    /// `options`, where `name` is a single path component.
+    ///
+    /// This is a fast path for code which resolves paths itself: `name` must
+    /// not be empty, `.`, or `..`, and must not contain path separators, or
+    /// this fails with [`io::ErrorKind::InvalidInput`]. The entry is opened
+    /// directly, without the multi-component resolution of
+    /// [`Dir::open_with`], falling back to it only if `name` is a symlink or
+    /// the direct open fails, so sandboxing still applies.
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn open_child<N: AsRef<OsStr>>(&self, name: N, options: &OpenOptions) -> io::Result<File> {
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.record_open(Path::new(name.as_ref()), options);
+
+        let options = self.file_options(options)?;
+        let file = open_child(&self.std_file, name.as_ref(), &options)?;
+        self.file_from_opened(file, &options)
+    }
+
+    /// Opens a file at `path` with the options specified by `options`, but
+    /// only if `predicate` returns `true` for its metadata.
+    ///
+    /// This replaces checking [`Dir::metadata`] and then opening, which
+    /// resolves `path` twice and can race with `path` being replaced in
+    /// between. Here, the metadata is read from the opened file, and if the
+    /// predicate returns `false`, the file is closed and this returns
+    /// `Ok(None)`. On Linux, the file is opened with `O_PATH` for the check,
+    /// and only opened with `options` once the predicate passes; elsewhere,
+    /// truncation is only done once the predicate passes. `options` may not
+    /// request `create` or `create_new`.
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn open_if<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &OpenOptions,
+        predicate: impl FnOnce(&Metadata) -> bool,
+    ) -> io::Result<Option<File>> {
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.record_open(path.as_ref(), options);
+
+        let options = self.file_options(options)?;
+        match open_if(&self.std_file, path.as_ref(), &options, predicate)? {
+            Some(file) => self.file_from_opened(file, &options).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Opens a file at `path` with the options specified by `options`, and
+    /// also returns the steps taken to resolve `path`.
+    ///
+    /// This is meant for diagnosing why a path was rejected, such as with an
+    /// [`EscapeError`]. The steps are returned whether or not the open
+    /// succeeds. Each step records a path component, the target if it was a
+    /// symlink, and whether resolution descended into it, ascended out of
+    /// it, followed it, or rejected it. Where the kernel resolves the path
+    /// itself, as with `openat2` on Linux, a single step with
+    /// [`ResolutionDecision::Kernel`] is returned instead.
+    ///
+    /// [`EscapeError`]: crate::fs::EscapeError
+    /// [`ResolutionDecision::Kernel`]: crate::fs::ResolutionDecision::Kernel
+    #[cfg(all(feature = "trace_resolution", not(target_os = "wasi")))]
+    pub fn open_traced<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &OpenOptions,
+    ) -> (io::Result<File>, Vec<ResolutionStep>) {
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.record_open(path.as_ref(), options);
+
+        let open_options = match self.file_options(options) {
+            Ok(open_options) => open_options,
+            Err(err) => return (Err(err), Vec::new()),
+        };
+        let (result, trace) = open_traced(&self.std_file, path.as_ref(), &open_options);
+        (
+            result.and_then(|file| self.file_from_opened(file, options)),
+            trace,
+        )
     }
 
     #[cfg(not(target_os = "wasi"))]
     #[inline]
     fn _open_with(&self, path: &Path, options: &OpenOptions) -> io::Result<File> {
-        let dir = open(&self.std_file, path, options)?;
-        Ok(File::from_std(dir, ambient_authority()))
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.record_open(path, options);
+
+        let create_options = self.file_options(options)?;
+        let dir = match open(&self.std_file, path, &create_options) {
+            Err(err)
+                if err.kind() == io::ErrorKind::NotFound && options._cap_std_creates_parents() =>
+            {
+                // Create the missing parents and try again.
+                if let Some(parent) = path.parent() {
+                    self.create_dir_all(parent)?;
+                }
+                open(&self.std_file, path, &create_options)?
+            }
+            other => other?,
+        };
+        self.file_from_opened(dir, options)
+    }
+
+    /// Constructs a `File` for `std`, which was opened through `self` with
+    /// `options`, applying `self`'s size limit.
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub(crate) fn file_from_opened(
+        &self,
+        std: fs::File,
+        options: &OpenOptions,
+    ) -> io::Result<File> {
+        self.settings.file_from_opened(std, options)
     }
 
     #[cfg(target_os = "wasi")]
@@ -104,11 +583,133 @@ impl Dir {
         Ok(File::from_std(dir, ambient_authority()))
     }
 
+    /// Opens a file at `path` with the options specified by `options`, and
+    /// with the WASI rights of the resulting handle narrowed to `base_rights`
+    /// and `inheriting_rights`.
+    ///
+    /// The rights masks are passed to `path_open`, which makes it possible to
+    /// hand out deliberately restricted handles, such as read-only handles
+    /// which can't seek. Rights can only be narrowed from those held by
+    /// `self`.
+    ///
+    /// On platforms other than WASI, this always fails.
+    #[cfg(target_os = "wasi")]
+    #[inline]
+    pub fn open_with_rights<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &OpenOptions,
+        base_rights: u64,
+        inheriting_rights: u64,
+    ) -> io::Result<File> {
+        let file = options
+            .clone()
+            .fs_rights_base(base_rights)
+            .fs_rights_inheriting(inheriting_rights)
+            .open_at(&self.std_file, path.as_ref())?;
+        Ok(File::from_std(file, ambient_authority()))
+    }
+
+    /// Opens a file at `path` with the options specified by `options`, and
+    /// with the WASI rights of the resulting handle narrowed to `base_rights`
+    /// and `inheriting_rights`.
+    ///
+    /// On platforms other than WASI, this always fails.
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn open_with_rights<P: AsRef<Path>>(
+        &self,
+        _path: P,
+        _options: &OpenOptions,
+        _base_rights: u64,
+        _inheriting_rights: u64,
+    ) -> io::Result<File> {
+        Err(rights_unsupported())
+    }
+
     /// Attempts to open a directory.
+    ///
+    /// Every operation on a path resolves all of the path's components, so
+    /// when performing many operations within the same subdirectory, it's
+    /// cheaper to open it once with this function, or [`Dir::scope`], and
+    /// use paths relative to the returned `Dir`. On Linux, the directory is
+    /// opened with `O_PATH`, which is all that resolving paths relative to
+    /// it needs.
     #[inline]
     pub fn open_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<Self> {
-        let dir = open_dir(&self.std_file, path.as_ref())?;
-        Ok(Self::from_std_file(dir, ambient_authority()))
+        let path = path.as_ref();
+        traced!("open_dir", path = path => {
+            open_dir(&self.std_file, path).map(|dir| self.subdir(path, dir))
+        })
+    }
+
+    /// Resolves the subdirectory at `path` once, returning a `Dir` for
+    /// performing a batch of operations within it.
+    ///
+    /// This is [`Dir::open_dir`], under a name which says what it's for.
+    /// Operations on the returned `Dir` only resolve the components of their
+    /// own paths, rather than resolving `path` again each time. The `Dir`
+    /// holds a single file descriptor, and [`Dir::try_clone`] just
+    /// duplicates it, so it's cheap to share between threads.
+    #[inline]
+    pub fn scope<P: AsRef<Path>>(&self, path: P) -> io::Result<Self> {
+        self.open_dir(path)
+    }
+
+    /// Calls `f` with a `Dir` for the subdirectory at `path`, which is
+    /// resolved once, as [`Dir::scope`] does, and closed when `f` returns.
+    #[inline]
+    pub fn batch<P: AsRef<Path>, R, F: FnOnce(&Self) -> io::Result<R>>(
+        &self,
+        path: P,
+        f: F,
+    ) -> io::Result<R> {
+        f(&self.scope(path)?)
+    }
+
+    /// Returns an identifier for the directory `self` refers to.
+    ///
+    /// Handles to the same directory, such as those returned by
+    /// [`Dir::try_clone`], have equal identifiers. See [`DirId`] for
+    /// caveats about reuse.
+    #[inline]
+    pub fn id(&self) -> io::Result<DirId> {
+        DirId::from_file(&self.std_file)
+    }
+
+    /// Returns the kind of filesystem the directory `self` refers to is on.
+    ///
+    /// This is best-effort; in particular, it's intended for deciding whether
+    /// to expect network-filesystem semantics, such as weaker locking and
+    /// rename guarantees, via [`FsType::is_network`]. Filesystems which
+    /// aren't recognized are reported as [`FsType::Other`].
+    #[inline]
+    pub fn filesystem_type(&self) -> io::Result<FsType> {
+        cap_primitives::fs::filesystem_type(&self.std_file)
+    }
+
+    /// Tests whether the filesystem the directory `self` refers to is on is
+    /// mounted read-only.
+    ///
+    /// This allows setup code to fail early with a clear message, rather
+    /// than with `EROFS` partway through an operation. A `true` result means
+    /// writes will fail, but a `false` result doesn't mean they'll succeed,
+    /// since permissions still apply and the mount can change.
+    #[inline]
+    pub fn is_read_only_mount(&self) -> io::Result<bool> {
+        cap_primitives::fs::is_read_only_mount(&self.std_file)
+    }
+
+    /// Sets the POSIX access control list of the file or directory at
+    /// `path`, following symlinks.
+    ///
+    /// This is how archive tools can restore ACLs extracted with
+    /// [`File::get_acl`]. The OS also updates the permission bits to match.
+    /// This is only supported on Linux; on other platforms it fails with
+    /// [`io::ErrorKind::Unsupported`].
+    #[inline]
+    pub fn set_acl<P: AsRef<Path>>(&self, path: P, acl: &Acl) -> io::Result<()> {
+        set_acl(&self.std_file, path.as_ref(), acl)
     }
 
     /// Creates a new, empty directory at the provided path.
@@ -117,7 +718,8 @@ impl Dir {
     /// relative to `self`.
     #[inline]
     pub fn create_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
-        self._create_dir_one(path.as_ref(), &DirOptions::new())
+        let path = path.as_ref();
+        traced!("create_dir", path = path => self._create_dir_one(path, &DirOptions::new()))
     }
 
     /// Recursively create a directory and all of its parent components if they are missing.
@@ -126,7 +728,53 @@ impl Dir {
     /// relative to `self`.
     #[inline]
     pub fn create_dir_all<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
-        self._create_dir_all(path.as_ref(), &DirOptions::new())
+        let path = path.as_ref();
+        traced!("create_dir_all", path = path => self._create_dir_all(path, &DirOptions::new()))
+    }
+
+    /// Recursively create a directory and all of its parent components if
+    /// they are missing, from a path which has already been split into
+    /// `components`.
+    ///
+    /// This is like [`Dir::create_dir_all`], but it doesn't parse a path,
+    /// and it resolves each component only once, relative to a handle for
+    /// its parent which is held while descending, rather than resolving the
+    /// whole path again for each level. Each component must be a single
+    /// normal path component; `..`, `.`, absolute paths, and components
+    /// containing separators are rejected.
+    pub fn create_dir_all_components(&self, components: &[&OsStr]) -> io::Result<()> {
+        for component in components {
+            let mut parsed = Path::new(component).components();
+            match (parsed.next(), parsed.next()) {
+                (Some(Component::Normal(name)), None) if name == *component => (),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "create_dir_all_components requires normal path components",
+                    ))
+                }
+            }
+        }
+
+        let dir_options = self.dir_options(&DirOptions::new()).into_owned();
+        let mut parent = None;
+        for (i, component) in components.iter().enumerate() {
+            let start = parent.as_ref().unwrap_or(&self.std_file);
+            let path = Path::new(component);
+            let created = match create_dir(start, path, &dir_options) {
+                Ok(()) => true,
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => false,
+                Err(e) => return Err(e),
+            };
+
+            // Open the directory to descend into it, or to check that an
+            // existing last component is a directory.
+            if i + 1 < components.len() || !created {
+                let dir = open_dir(start, path)?;
+                parent = Some(dir);
+            }
+        }
+        Ok(())
     }
 
     /// Creates the specified directory with the options configured in this builder.
@@ -138,16 +786,45 @@ impl Dir {
         path: P,
         dir_builder: &DirBuilder,
     ) -> io::Result<()> {
-        let options = dir_builder.options();
+        let (path, options) = (path.as_ref(), dir_builder.options());
         if dir_builder.is_recursive() {
-            self._create_dir_all(path.as_ref(), options)
+            traced!("create_dir_all", path = path => self._create_dir_all(path, options))
         } else {
-            self._create_dir_one(path.as_ref(), options)
+            traced!("create_dir", path = path => self._create_dir_one(path, options))
         }
     }
 
+    /// Creates a named pipe at `path`, returning a [`File`] for its server
+    /// end.
+    ///
+    /// On Unix-family platforms this creates a FIFO, as with `mkfifo`, and
+    /// opens it with the access requested in `options` without waiting for
+    /// a peer. Clients connect by opening the FIFO with [`Dir::open_with`].
+    /// FIFOs don't have server instances, so [`PipeOptions::max_instances`]
+    /// must be 1. The FIFO's mode is `self`'s default file mode, if it has
+    /// one, or 0o666, before the umask.
+    ///
+    /// On Windows, named pipes aren't created in ordinary directories, so
+    /// `self` must refer to the named pipe namespace, such as a `Dir` for
+    /// `\\.\pipe\` opened with ambient authority. This creates a server
+    /// instance, which clients connect to by opening `path` in that `Dir`
+    /// with [`Dir::open_with`].
+    #[inline]
+    pub fn create_named_pipe<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &PipeOptions,
+    ) -> io::Result<File> {
+        let file = create_named_pipe(
+            &self.std_file,
+            path.as_ref(),
+            &self.settings.pipe_options(options),
+        )?;
+        Ok(File::from_std(file, ambient_authority()))
+    }
+
     fn _create_dir_one(&self, path: &Path, dir_options: &DirOptions) -> io::Result<()> {
-        create_dir(&self.std_file, path, dir_options)
+        create_dir(&self.std_file, path, &self.dir_options(dir_options))
     }
 
     fn _create_dir_all(&self, path: &Path, dir_options: &DirOptions) -> io::Result<()> {
@@ -177,6 +854,36 @@ impl Dir {
         }
     }
 
+    /// Opens or creates a lockfile at `path`, and takes an exclusive lock on
+    /// it, blocking until the lock is available.
+    ///
+    /// The file is created if it doesn't exist, and is not truncated. The
+    /// lock is advisory, using `flock` on Posix-ish platforms and
+    /// `LockFileEx` on Windows, and is released when the returned guard is
+    /// dropped.
+    pub fn lock_file<P: AsRef<Path>>(&self, path: P) -> io::Result<LockGuard<'_>> {
+        let path = path.as_ref();
+        let file = self.open_lockfile(path)?;
+        lock_file_exclusive(&file.std)?;
+        Ok(LockGuard::new(file, self, path.to_path_buf()))
+    }
+
+    /// Like [`Dir::lock_file`], but returns `Ok(None)` rather than blocking
+    /// if the lock is held elsewhere.
+    pub fn try_lock_file<P: AsRef<Path>>(&self, path: P) -> io::Result<Option<LockGuard<'_>>> {
+        let path = path.as_ref();
+        let file = self.open_lockfile(path)?;
+        if !try_lock_file_exclusive(&file.std)? {
+            return Ok(None);
+        }
+        Ok(Some(LockGuard::new(file, self, path.to_path_buf())))
+    }
+
+    #[inline]
+    fn open_lockfile(&self, path: &Path) -> io::Result<File> {
+        self.open_with(path, OpenOptions::new().write(true).create(true))
+    }
+
     /// Opens a file in write-only mode.
     ///
     /// This corresponds to [`std::fs::File::create`], but only accesses paths
@@ -199,6 +906,22 @@ impl Dir {
         canonicalize(&self.std_file, path.as_ref())
     }
 
+    /// Like [`Dir::canonicalize`], but also returns each symbolic link
+    /// followed during resolution, in order, paired with its target.
+    ///
+    /// Both paths in each pair are relative to `self`; the target is the
+    /// link's contents joined onto the directory containing the link, so it
+    /// may contain `..` components. This always resolves the path one
+    /// component at a time, rather than using faster mechanisms such as
+    /// `openat2` on Linux, which don't report the links they follow.
+    #[inline]
+    pub fn canonicalize_with_chain<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> io::Result<(PathBuf, Vec<(PathBuf, PathBuf)>)> {
+        canonicalize_with_chain(&self.std_file, path.as_ref())
+    }
+
     /// Copies the contents of one file to another. This function will also copy the permission
     /// bits of the original file to the destination file.
     ///
@@ -211,9 +934,85 @@ impl Dir {
         to_dir: &Self,
         to: Q,
     ) -> io::Result<u64> {
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.record_copy(from.as_ref(), to_dir, to.as_ref());
+
         copy(&self.std_file, from.as_ref(), &to_dir.std_file, to.as_ref())
     }
 
+    /// Copies the contents of one file to another, with `policy` determining
+    /// what happens if `to` already exists.
+    ///
+    /// This is like [`Dir::copy`], but returns `Ok(0)` without copying
+    /// anything when `policy` says to leave an existing destination alone.
+    #[inline]
+    pub fn copy_with_policy<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        policy: OverwritePolicy,
+    ) -> io::Result<u64> {
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.record_copy(from.as_ref(), to_dir, to.as_ref());
+
+        copy_with_policy(
+            &self.std_file,
+            from.as_ref(),
+            &to_dir.std_file,
+            to.as_ref(),
+            policy,
+        )
+    }
+
+    /// Copies the contents of one file to another, pacing the copy to
+    /// approximately `bytes_per_sec` bytes per second.
+    ///
+    /// This is like [`Dir::copy`], but always copies through a buffer so
+    /// that it can sleep between chunks. Chunks are at most 64 KiB, and at
+    /// most a tenth of a second's worth of data, so the rate is held on
+    /// average rather than exactly. A `bytes_per_sec` of zero fails with
+    /// [`io::ErrorKind::InvalidInput`].
+    #[inline]
+    pub fn copy_throttled<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        bytes_per_sec: u64,
+    ) -> io::Result<u64> {
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.record_copy(from.as_ref(), to_dir, to.as_ref());
+
+        copy_throttled(
+            &self.std_file,
+            from.as_ref(),
+            &to_dir.std_file,
+            to.as_ref(),
+            bytes_per_sec,
+        )
+    }
+
+    /// Copies the contents of one file to another, and verifies the copy.
+    ///
+    /// This is like [`Dir::copy`], but after copying, it reads the
+    /// destination back and compares it, byte for byte, to the source. If
+    /// they differ, the destination is removed and this fails with
+    /// [`io::ErrorKind::InvalidData`]. This is slower than `copy`, and is
+    /// intended for copies where silent corruption must be detected.
+    #[inline]
+    pub fn copy_verified<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<u64> {
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.record_copy(from.as_ref(), to_dir, to.as_ref());
+
+        copy_verified(&self.std_file, from.as_ref(), &to_dir.std_file, to.as_ref())
+    }
+
     /// Creates a new hard link on a filesystem.
     ///
     /// This corresponds to [`std::fs::hard_link`], but only accesses paths
@@ -239,7 +1038,10 @@ impl Dir {
     /// relative to `self`.
     #[inline]
     pub fn metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
-        stat(&self.std_file, path.as_ref(), FollowSymlinks::Yes)
+        let path = path.as_ref();
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.record(path, AccessKind::Stat);
+        traced!("metadata", path = path => stat(&self.std_file, path, FollowSymlinks::Yes))
     }
 
     /// Queries metadata about the underlying directory.
@@ -254,7 +1056,54 @@ impl Dir {
     /// Returns an iterator over the entries within `self`.
     #[inline]
     pub fn entries(&self) -> io::Result<ReadDir> {
-        read_base_dir(&self.std_file).map(|inner| ReadDir { inner })
+        read_base_dir(&self.std_file).map(|inner| self.read_dir_from(Path::new(""), inner))
+    }
+
+    /// Calls `f` with the name and type of each entry within `self`,
+    /// stopping early if it returns [`ControlFlow::Break`].
+    ///
+    /// Unlike [`entries`], this doesn't construct a [`DirEntry`] for each
+    /// entry, so it's cheaper for scans which only need names and types. On
+    /// Windows, the savings are smaller, since each entry's name is still
+    /// allocated. The type is that of the entry itself, so symlinks aren't
+    /// followed.
+    ///
+    /// [`entries`]: Dir::entries
+    /// [`DirEntry`]: crate::fs::DirEntry
+    #[inline]
+    pub fn for_each_entry(
+        &self,
+        f: impl FnMut(&OsStr, FileType) -> ControlFlow<()>,
+    ) -> io::Result<()> {
+        for_each_entry(&self.std_file, f)
+    }
+
+    #[inline]
+    fn read_dir_from(&self, path: &Path, inner: cap_primitives::fs::ReadDir) -> ReadDir {
+        ReadDir {
+            inner,
+            settings: self.settings.descend(path),
+        }
+    }
+
+    /// Returns the entries within `self`, with their names normalized to
+    /// Unicode Normalization Form C, sorted by normalized name.
+    ///
+    /// This gives the same order for the same names on all platforms, even
+    /// where filesystems store names decomposed, as HFS+ does, which is
+    /// useful for things like hashing directory contents reproducibly.
+    /// Names which normalize identically are ordered by their raw names.
+    ///
+    /// The normalized name may differ from the name on disk; see
+    /// [`NormalizedDirEntry::name`].
+    #[cfg(feature = "normalize_names")]
+    pub fn read_dir_normalized(&self) -> io::Result<Vec<NormalizedDirEntry>> {
+        let mut entries = self
+            .entries()?
+            .map(|entry| entry.map(NormalizedDirEntry::new))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort_by_cached_key(|entry| (entry.name().to_os_string(), entry.raw_name()));
+        Ok(entries)
     }
 
     /// Returns an iterator over the entries within a directory.
@@ -263,7 +1112,30 @@ impl Dir {
     /// relative to `self`.
     #[inline]
     pub fn read_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<ReadDir> {
-        read_dir(&self.std_file, path.as_ref()).map(|inner| ReadDir { inner })
+        let path = path.as_ref();
+        traced!("read_dir", path = path => {
+            read_dir(&self.std_file, path).map(|inner| self.read_dir_from(path, inner))
+        })
+    }
+
+    /// Returns an iterator over the entries within `self` whose modification
+    /// times are after `since`.
+    ///
+    /// This is meant for incremental scans, such as backups. On Unix-family
+    /// platforms, directory listings don't include modification times, so
+    /// this costs one `fstatat` per entry, relative to the directory's own
+    /// handle so that no paths need to be resolved. On Windows, the listing
+    /// includes them, so no further calls are needed. Use
+    /// [`ReadDirModifiedSince::skip_dirs`] to skip directories, which avoids
+    /// the `fstatat` wherever the listing records entry types. Entries
+    /// removed while iterating are skipped.
+    ///
+    /// [`ReadDirModifiedSince::skip_dirs`]: crate::fs::ReadDirModifiedSince::skip_dirs
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn read_dir_modified_since(&self, since: SystemTime) -> io::Result<ReadDirModifiedSince> {
+        self.entries()
+            .map(|entries| ReadDirModifiedSince::new(entries, since))
     }
 
     /// Read the entire contents of a file into a bytes vector.
@@ -278,6 +1150,35 @@ impl Dir {
         Ok(bytes)
     }
 
+    /// Reads bytes from the file at `path`, starting at `offset`, returning
+    /// the number of bytes read.
+    ///
+    /// This opens the file, performs a single positional read, like
+    /// [`std::os::unix::fs::FileExt::read_at`], and closes it again. Code
+    /// which accesses the same file repeatedly should open it once and use
+    /// the `File`'s positional reads instead.
+    ///
+    /// [`std::os::unix::fs::FileExt::read_at`]: https://doc.rust-lang.org/std/os/unix/fs/trait.FileExt.html#tymethod.read_at
+    #[inline]
+    pub fn read_at<P: AsRef<Path>>(
+        &self,
+        path: P,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> io::Result<usize> {
+        let file = self.open(path)?;
+
+        #[cfg(not(windows))]
+        {
+            posish::io::pread(&file.std, buf, offset)
+        }
+
+        #[cfg(windows)]
+        {
+            std::os::windows::fs::FileExt::seek_read(&file.std, buf, offset)
+        }
+    }
+
     /// Reads a symbolic link, returning the file that the link points to.
     ///
     /// This corresponds to [`std::fs::read_link`], but only accesses paths
@@ -304,7 +1205,8 @@ impl Dir {
     /// relative to `self`.
     #[inline]
     pub fn remove_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
-        remove_dir(&self.std_file, path.as_ref())
+        let path = path.as_ref();
+        traced!("remove_dir", path = path => remove_dir(&self.std_file, path))
     }
 
     /// Removes a directory at this path, after removing all its contents. Use carefully!
@@ -313,7 +1215,8 @@ impl Dir {
     /// relative to `self`.
     #[inline]
     pub fn remove_dir_all<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
-        remove_dir_all(&self.std_file, path.as_ref())
+        let path = path.as_ref();
+        traced!("remove_dir_all", path = path => remove_dir_all(&self.std_file, path))
     }
 
     /// Remove the directory referenced by `self` and consume `self`.
@@ -343,13 +1246,17 @@ impl Dir {
     /// relative to `self`.
     #[inline]
     pub fn remove_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
-        remove_file(&self.std_file, path.as_ref())
+        let path = path.as_ref();
+        traced!("remove_file", path = path => remove_file(&self.std_file, path))
     }
 
     /// Rename a file or directory to a new name, replacing the original file if to already exists.
     ///
     /// This corresponds to [`std::fs::rename`], but only accesses paths
-    /// relative to `self`.
+    /// relative to `self`. `from` is resolved within `self` and `to` within
+    /// `to_dir`, and if they are on different filesystems this fails with
+    /// the OS's cross-device error rather than copying; see
+    /// [`Dir::rename_or_move`].
     #[inline]
     pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(
         &self,
@@ -357,7 +1264,51 @@ impl Dir {
         to_dir: &Self,
         to: Q,
     ) -> io::Result<()> {
-        rename(&self.std_file, from.as_ref(), &to_dir.std_file, to.as_ref())
+        let (from, to) = (from.as_ref(), to.as_ref());
+        traced!("rename", from = from, to = to => {
+            rename(&self.std_file, from, &to_dir.std_file, to)
+        })
+    }
+
+    /// Moves a file to a new name, which may be on a different filesystem,
+    /// replacing the original file if `to` already exists.
+    ///
+    /// This is similar to [`Dir::rename`], except that if `from` and `to` are
+    /// on different filesystems, the file is copied to a temporary name next
+    /// to `to`, synced, renamed into place, and then `from` is removed. `to`
+    /// never appears partially written, however the move as a whole is not
+    /// atomic.
+    ///
+    /// As with `rename`, `from` is resolved within `self` and `to` within
+    /// `to_dir`, and neither may escape its own `Dir`. When a plain rename is
+    /// possible, directories and other kinds of files are moved too, however
+    /// only regular files can be moved by copying, so for anything else on a
+    /// different filesystem, the OS's cross-device error is returned.
+    #[inline]
+    pub fn move_file<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<()> {
+        move_file(&self.std_file, from.as_ref(), &to_dir.std_file, to.as_ref())
+    }
+
+    /// Renames a file or directory from `self` into `to_dir`, falling back
+    /// to moving it if the two are on different filesystems.
+    ///
+    /// This is [`Dir::move_file`], under a name which says what it's for.
+    /// `from` must resolve within `self` and `to` within `to_dir`; holding
+    /// both `Dir`s is what authorizes the transfer, and neither path may
+    /// escape its own sandbox.
+    #[inline]
+    pub fn rename_or_move<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<()> {
+        self.move_file(from, to_dir, to)
     }
 
     /// Changes the permissions found on a file or a directory.
@@ -376,7 +1327,10 @@ impl Dir {
     /// relative to `self`.
     #[inline]
     pub fn symlink_metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
-        stat(&self.std_file, path.as_ref(), FollowSymlinks::No)
+        let path = path.as_ref();
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.record(path, AccessKind::Stat);
+        traced!("symlink_metadata", path = path => stat(&self.std_file, path, FollowSymlinks::No))
     }
 
     /// Write a slice as the entire contents of a file.
@@ -389,6 +1343,31 @@ impl Dir {
         file.write_all(contents.as_ref())
     }
 
+    /// Writes bytes to the existing file at `path`, starting at `offset`,
+    /// returning the number of bytes written.
+    ///
+    /// This opens the file, without creating or truncating it, performs a
+    /// single positional write, like
+    /// [`std::os::unix::fs::FileExt::write_at`], and closes it again. Code
+    /// which accesses the same file repeatedly should open it once and use
+    /// the `File`'s positional writes instead.
+    ///
+    /// [`std::os::unix::fs::FileExt::write_at`]: https://doc.rust-lang.org/std/os/unix/fs/trait.FileExt.html#tymethod.write_at
+    #[inline]
+    pub fn write_at<P: AsRef<Path>>(&self, path: P, buf: &[u8], offset: u64) -> io::Result<usize> {
+        let file = self.open_with(path, OpenOptions::new().write(true))?;
+
+        #[cfg(not(windows))]
+        {
+            posish::io::pwrite(&file.std, buf, offset)
+        }
+
+        #[cfg(windows)]
+        {
+            std::os::windows::fs::FileExt::seek_write(&file.std, buf, offset)
+        }
+    }
+
     /// Creates a new symbolic link on a filesystem.
     ///
     /// This corresponds to [`std::os::unix::fs::symlink`], but only accesses paths
@@ -425,6 +1404,25 @@ impl Dir {
         symlink_dir(src.as_ref(), &self.std_file, dst.as_ref())
     }
 
+    /// Creates a new symbolic link on a filesystem, with a hint about what
+    /// kind of object `src` is.
+    ///
+    /// On Windows, a symlink must be created as either a file symlink or a
+    /// directory symlink. [`SymlinkTargetType::File`] and
+    /// [`SymlinkTargetType::Dir`] force the choice, which allows creating
+    /// symlinks to targets which don't exist yet, and
+    /// [`SymlinkTargetType::Auto`] stats `src` to decide. On other platforms,
+    /// this is equivalent to `symlink` and the hint is ignored.
+    #[inline]
+    pub fn symlink_with_type<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        src: P,
+        dst: Q,
+        target_type: SymlinkTargetType,
+    ) -> io::Result<()> {
+        symlink_with_type(src.as_ref(), &self.std_file, dst.as_ref(), target_type)
+    }
+
     /// Creates a new `UnixListener` bound to the specified socket.
     ///
     /// This corresponds to [`std::os::unix::net::UnixListener::bind`], but only
@@ -530,7 +1528,7 @@ impl Dir {
     #[inline]
     pub fn try_clone(&self) -> io::Result<Self> {
         let dir = self.std_file.try_clone()?;
-        Ok(Self::from_std_file(dir, ambient_authority()))
+        Ok(self.subdir(Path::new(""), dir))
     }
 
     /// Returns `true` if the path points at an existing entity.
@@ -695,6 +1693,24 @@ impl IntoRawHandleOrSocket for Dir {
 // Safety: `Dir` wraps a `fs::File` which owns its handle.
 unsafe impl OwnsRaw for Dir {}
 
+#[cfg(not(target_os = "wasi"))]
+#[cold]
+fn rights_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "WASI rights are not supported on this platform",
+    )
+}
+
+#[cfg(not(target_os = "wasi"))]
+#[cold]
+fn creation_disabled() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        "creating files is disabled for this `Dir`",
+    )
+}
+
 /// Indicates how large a buffer to pre-allocate before reading the entire file.
 ///
 /// Derived from the function of the same name in Rust's library/std/src/fs.rs
@@ -706,6 +1722,18 @@ fn initial_buffer_size(file: &File) -> usize {
     file.metadata().map(|m| m.len() as usize + 1).unwrap_or(0)
 }
 
+/// Test whether `err` is `ETXTBSY`, which means a running executable can't
+/// be opened for writing.
+#[cfg(not(windows))]
+fn is_text_file_busy(err: &io::Error) -> bool {
+    posish::io::Errno::from_io_error(err) == Some(posish::io::Errno::TXTBSY)
+}
+
+#[cfg(windows)]
+fn is_text_file_busy(_err: &io::Error) -> bool {
+    false
+}
+
 impl fmt::Debug for Dir {
     // Like libstd's version, but doesn't print the path.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
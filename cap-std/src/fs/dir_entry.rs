@@ -1,4 +1,6 @@
-use crate::fs::{Dir, File, FileType, Metadata, OpenOptions};
+#[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+use crate::fs::AccessKind;
+use crate::fs::{Dir, DirSettings, File, FileType, Metadata, OpenOptions};
 use cap_primitives::ambient_authority;
 #[cfg(not(windows))]
 use posish::fs::DirEntryExt;
@@ -21,28 +23,47 @@ use std::{ffi::OsString, fmt, io};
 /// ambient paths.
 pub struct DirEntry {
     pub(crate) inner: cap_primitives::fs::DirEntry,
+    pub(crate) settings: DirSettings,
 }
 
 impl DirEntry {
     /// Open the file for reading.
     #[inline]
     pub fn open(&self) -> io::Result<File> {
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.record(AccessKind::Read);
         let file = self.inner.open()?;
         Ok(File::from_std(file, ambient_authority()))
     }
 
     /// Open the file with the given options.
+    ///
+    /// As with [`Dir::open_with`], this fails if `options` would create the
+    /// file and the `Dir` this entry came from was made with
+    /// [`Dir::with_no_create`].
     #[inline]
     pub fn open_with(&self, options: &OpenOptions) -> io::Result<File> {
+        #[cfg(not(target_os = "wasi"))]
+        let options = &*self.settings.file_options(options)?;
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.settings
+            .record_open(self.file_name().as_ref(), options);
         let file = self.inner.open_with(options)?;
-        Ok(File::from_std(file, ambient_authority()))
+        #[cfg(not(target_os = "wasi"))]
+        {
+            self.settings.file_from_opened(file, options)
+        }
+        #[cfg(target_os = "wasi")]
+        {
+            Ok(File::from_std(file, ambient_authority()))
+        }
     }
 
     /// Open the entry as a directory.
     #[inline]
     pub fn open_dir(&self) -> io::Result<Dir> {
         let dir = self.inner.open_dir()?;
-        Ok(Dir::from_std_file(dir, ambient_authority()))
+        Ok(self.settings.subdir(self.file_name().as_ref(), dir))
     }
 
     /// Removes the file from its filesystem.
@@ -62,6 +83,8 @@ impl DirEntry {
     /// This corresponds to [`std::fs::DirEntry::metadata`].
     #[inline]
     pub fn metadata(&self) -> io::Result<Metadata> {
+        #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+        self.record(AccessKind::Stat);
         self.inner.metadata()
     }
 
@@ -81,6 +104,13 @@ impl DirEntry {
     pub fn file_name(&self) -> OsString {
         self.inner.file_name()
     }
+
+    /// Record an access to this entry if its `Dir` is recording accesses.
+    #[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+    #[inline]
+    fn record(&self, kind: AccessKind) {
+        self.settings.record(self.file_name().as_ref(), kind)
+    }
 }
 
 #[cfg(not(windows))]
@@ -100,6 +130,24 @@ impl cap_primitives::fs::_WindowsDirEntryExt for DirEntry {
     }
 }
 
+#[doc(hidden)]
+impl cap_primitives::fs::_DirEntryRawExt for DirEntry {
+    #[inline]
+    fn ino(&self) -> io::Result<u64> {
+        cap_primitives::fs::_DirEntryRawExt::ino(&self.inner)
+    }
+
+    #[inline]
+    fn d_type_raw(&self) -> io::Result<u32> {
+        cap_primitives::fs::_DirEntryRawExt::d_type_raw(&self.inner)
+    }
+
+    #[inline]
+    fn file_type_cached(&self) -> Option<FileType> {
+        cap_primitives::fs::_DirEntryRawExt::file_type_cached(&self.inner)
+    }
+}
+
 impl fmt::Debug for DirEntry {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.inner.fmt(f)
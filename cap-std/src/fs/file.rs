@@ -1,7 +1,11 @@
-#[cfg(with_options)]
+#[cfg(any(with_options, not(target_os = "wasi")))]
 use crate::fs::OpenOptions;
 use crate::fs::{Metadata, Permissions};
-use cap_primitives::{ambient_authority, fs::is_file_read_write, AmbientAuthority};
+use cap_primitives::{
+    ambient_authority,
+    fs::{dedupe_range, get_acl, is_file_read_write, set_sparse, Acl},
+    AmbientAuthority,
+};
 #[cfg(not(windows))]
 use io_lifetimes::{AsFd, BorrowedFd, FromFd, IntoFd, OwnedFd};
 #[cfg(windows)]
@@ -9,9 +13,10 @@ use io_lifetimes::{AsHandle, BorrowedHandle, FromHandle, IntoHandle, OwnedHandle
 #[cfg(target_os = "wasi")]
 use std::path::Path;
 use std::{
-    fmt, fs,
+    cmp, fmt, fs,
     io::{self, IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write},
     process,
+    sync::atomic::{AtomicU64, Ordering},
 };
 #[cfg(not(windows))]
 use unsafe_io::os::posish::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
@@ -35,6 +40,119 @@ use {
 /// [`Dir::create`]: crate::fs::Dir::create
 pub struct File {
     pub(crate) std: fs::File,
+    // Fields drop in order, so this truncates before dropping the cache, as
+    // `finish` does.
+    truncate: TruncateOnClose,
+    drop_cache: DropCacheOnClose,
+    max_size: Option<MaxSize>,
+}
+
+/// A limit on the size which writes through a `File` may extend it to. See
+/// `Dir::with_max_file_size`.
+#[derive(Clone, Copy)]
+struct MaxSize {
+    limit: u64,
+    append: bool,
+}
+
+/// When armed, drops a file's pages from the page cache when it's dropped.
+///
+/// This holds its own handle to the file, so that `File` itself doesn't need
+/// to implement `Drop`, which would prevent moving `std` out of it.
+#[derive(Default)]
+struct DropCacheOnClose(Option<fs::File>);
+
+impl DropCacheOnClose {
+    fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for DropCacheOnClose {
+    fn drop(&mut self) {
+        if let Some(file) = &self.0 {
+            drop_cache(file).ok();
+        }
+    }
+}
+
+/// When armed, truncates a file to the end of the furthest write through its
+/// `File` when it's dropped. See `OpenOptionsTruncateToWrittenExt`.
+///
+/// Like `DropCacheOnClose`, this holds its own handle to the file. The
+/// length to truncate to is stored plus one, so that zero means nothing has
+/// been written and the file should be left alone.
+#[derive(Default)]
+struct TruncateOnClose(Option<(fs::File, AtomicU64)>);
+
+impl TruncateOnClose {
+    fn disarm(mut self) {
+        self.0 = None;
+    }
+
+    /// Record that a write ended at `end`.
+    fn written_to(&self, end: u64) {
+        if let Some((_, written)) = &self.0 {
+            written.fetch_max(end.saturating_add(1), Ordering::Relaxed);
+        }
+    }
+
+    /// Record that the file's length was set to `len`, replacing what was
+    /// written before.
+    fn set_to(&self, len: u64) {
+        if let Some((_, written)) = &self.0 {
+            written.store(len.saturating_add(1), Ordering::Relaxed);
+        }
+    }
+
+    /// Truncate the file, if anything was written and it's longer than that,
+    /// and disarm.
+    fn truncate(&mut self) -> io::Result<()> {
+        if let Some((file, written)) = self.0.take() {
+            let written = match written.into_inner() {
+                0 => return Ok(()),
+                written => written - 1,
+            };
+            if written < file.metadata()?.len() {
+                file.set_len(written)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for TruncateOnClose {
+    fn drop(&mut self) {
+        self.truncate().ok();
+    }
+}
+
+fn drop_cache(file: &fs::File) -> io::Result<()> {
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        posish::fs::fadvise(file, 0, 0, posish::fs::Advice::DontNeed)
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    {
+        let _ = file;
+        Ok(())
+    }
+}
+
+/// The error for a write which would exceed a `File`'s size limit, matching
+/// what the OS reports when a file size rlimit is exceeded.
+#[cold]
+fn file_too_large() -> io::Error {
+    #[cfg(not(windows))]
+    {
+        posish::io::Errno::FBIG.io_error()
+    }
+
+    #[cfg(windows)]
+    {
+        io::Error::from_raw_os_error(winapi::shared::winerror::ERROR_FILE_TOO_LARGE as i32)
+    }
 }
 
 impl File {
@@ -46,13 +164,74 @@ impl File {
     /// host process has access to.
     #[inline]
     pub fn from_std(std: fs::File, _: AmbientAuthority) -> Self {
-        Self { std }
+        Self {
+            std,
+            truncate: TruncateOnClose::default(),
+            drop_cache: DropCacheOnClose::default(),
+            max_size: None,
+        }
+    }
+
+    /// Constructs a new instance of `Self` from a [`std::fs::File`] which was
+    /// just opened with `options`, applying any options which `File` itself
+    /// implements, and the size limit of the `Dir` it was opened through.
+    #[cfg(not(target_os = "wasi"))]
+    pub(crate) fn from_opened(
+        std: fs::File,
+        options: &OpenOptions,
+        max_file_size: Option<u64>,
+    ) -> io::Result<Self> {
+        let mut file = Self::from_std(std, ambient_authority());
+        file.max_size = max_file_size.map(|limit| MaxSize {
+            limit,
+            append: options._cap_std_appends(),
+        });
+
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        if options._cap_std_drops_cache_on_close() {
+            file.drop_cache = DropCacheOnClose(Some(file.std.try_clone()?));
+        }
+
+        if options._cap_std_truncates_to_written() && options._cap_std_writes() {
+            file.truncate = TruncateOnClose(Some((file.std.try_clone()?, AtomicU64::new(0))));
+        }
+
+        Ok(file)
     }
 
     /// Consumes `self` and returns a [`std::fs::File`].
+    ///
+    /// If `self` was opened with `drop_cache_on_close` or
+    /// `truncate_to_written`, the returned file doesn't drop its pages from
+    /// the page cache or get truncated when it's closed.
     #[inline]
     pub fn into_std(self) -> fs::File {
-        self.std
+        let Self {
+            std,
+            truncate,
+            drop_cache,
+            ..
+        } = self;
+        truncate.disarm();
+        drop_cache.disarm();
+        std
+    }
+
+    /// Closes the file, first truncating it to the end of the furthest write
+    /// through `self` if it was opened with `truncate_to_written` and is
+    /// longer than that.
+    ///
+    /// Dropping such a `File` truncates it too, but ignores any errors in
+    /// doing so; use this to find out about them. Only writes through `self`
+    /// are counted, so writes through other handles, including those from
+    /// [`File::try_clone`], may be cut off, and a file which hasn't been
+    /// written to through `self` is left as it is. [`File::set_len`] sets
+    /// the length to truncate to. Without `truncate_to_written`, this just
+    /// closes the file.
+    #[inline]
+    pub fn finish(self) -> io::Result<()> {
+        let Self { mut truncate, .. } = self;
+        truncate.truncate()
     }
 
     /// Returns a new [`OpenOptions`] object.
@@ -81,13 +260,31 @@ impl File {
         self.std.sync_data()
     }
 
+    /// Drops the file's pages from the page cache.
+    ///
+    /// This uses `posix_fadvise` with `POSIX_FADV_DONTNEED` over the whole
+    /// file, which is only effective on Linux; on other platforms this does
+    /// nothing. Dirty pages can't be dropped until they're written back, so
+    /// call [`File::sync_data`] first to drop pages which have been written.
+    #[inline]
+    pub fn drop_cache(&self) -> io::Result<()> {
+        drop_cache(&self.std)
+    }
+
     /// Truncates or extends the underlying file, updating the size of this file
     /// to become size.
     ///
     /// This corresponds to [`std::fs::File::set_len`].
     #[inline]
     pub fn set_len(&self, size: u64) -> io::Result<()> {
-        self.std.set_len(size)
+        if let Some(max_size) = self.max_size {
+            if size > max_size.limit {
+                return Err(file_too_large());
+            }
+        }
+        self.std.set_len(size)?;
+        self.truncate.set_to(size);
+        Ok(())
     }
 
     /// Queries metadata about the underlying file.
@@ -95,7 +292,7 @@ impl File {
     /// This corresponds to [`std::fs::File::metadata`].
     #[inline]
     pub fn metadata(&self) -> io::Result<Metadata> {
-        metadata_from(&self.std)
+        traced!("file_metadata" => metadata_from(&self.std))
     }
 
     /// Creates a new `File` instance that shares the same underlying file handle as the existing
@@ -105,7 +302,9 @@ impl File {
     #[inline]
     pub fn try_clone(&self) -> io::Result<Self> {
         let file = self.std.try_clone()?;
-        Ok(Self::from_std(file, ambient_authority()))
+        let mut file = Self::from_std(file, ambient_authority());
+        file.max_size = self.max_size;
+        Ok(file)
     }
 
     /// Changes the permissions on the underlying file.
@@ -116,6 +315,86 @@ impl File {
         self.std
             .set_permissions(permissions_into_std(&self.std, perm)?)
     }
+
+    /// Reads the file's POSIX access control list.
+    ///
+    /// Files without an extended ACL report the ACL equivalent to their
+    /// permission bits. This is only supported on Linux; on other platforms
+    /// it fails with [`io::ErrorKind::Unsupported`].
+    #[inline]
+    pub fn get_acl(&self) -> io::Result<Acl> {
+        get_acl(&self.std)
+    }
+
+    /// Sets whether the file is sparse, so that ranges of zeros don't need
+    /// to be allocated.
+    ///
+    /// On Windows, files must be marked sparse before punching holes in them
+    /// with `FSCTL_SET_ZERO_DATA` actually deallocates anything, and this
+    /// uses `FSCTL_SET_SPARSE`, which needs the file to be open for writing.
+    /// On Unix-family platforms, sparseness is implicit, so this does
+    /// nothing.
+    #[inline]
+    pub fn set_sparse(&self, sparse: bool) -> io::Result<()> {
+        set_sparse(&self.std, sparse)
+    }
+
+    /// Deduplicates `len` bytes of `self` at `dst_offset` with the same
+    /// bytes of `src` at `src_offset`, if they're identical.
+    ///
+    /// Returns `true` if the ranges were identical and now share storage,
+    /// and `false` if they differ, in which case nothing is changed. `self`
+    /// must be open for writing. The offsets must be multiples of the
+    /// filesystem's block size, and so must `len` unless the range ends at
+    /// the end of `src`.
+    ///
+    /// This uses the `FIDEDUPERANGE` ioctl, which only some filesystems,
+    /// such as Btrfs and XFS, support. It's only supported on Linux; on
+    /// other platforms it fails with [`io::ErrorKind::Unsupported`].
+    #[inline]
+    pub fn dedupe_range(
+        &self,
+        src: &File,
+        src_offset: u64,
+        len: u64,
+        dst_offset: u64,
+    ) -> io::Result<bool> {
+        dedupe_range(&self.std, &src.std, src_offset, len, dst_offset)
+    }
+
+    /// Reads all bytes from the current position until EOF, appending them
+    /// to `buf`, failing if there are more than `max` of them.
+    ///
+    /// `buf` is reserved up front using the file's size, clamped to `max`.
+    /// The limit is enforced on the bytes actually read, so a file which
+    /// grows while it's being read is still caught. On error, `buf` is left
+    /// as it was, though the file position may have advanced.
+    pub fn read_to_end_capped(&self, buf: &mut Vec<u8>, max: usize) -> io::Result<usize> {
+        let mut file = &self.std;
+        let start = buf.len();
+
+        // The size is only a hint, so don't fail if it's unavailable.
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let pos = file.stream_position().unwrap_or(0);
+        buf.reserve(cmp::min(len.saturating_sub(pos), max as u64) as usize);
+
+        // Ask for one byte more than `max` so that we can tell if the file is
+        // too large.
+        match file.take((max as u64).saturating_add(1)).read_to_end(buf) {
+            Ok(n) if n <= max => Ok(n),
+            Ok(_) => {
+                buf.truncate(start);
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "file is larger than the size limit",
+                ))
+            }
+            Err(err) => {
+                buf.truncate(start);
+                Err(err)
+            }
+        }
+    }
 }
 
 #[cfg(not(target_os = "wasi"))]
@@ -142,6 +421,59 @@ fn permissions_into_std(_file: &fs::File, permissions: Permissions) -> io::Resul
     permissions
 }
 
+impl File {
+    /// Returns how many of `len` bytes may be written at `offset`, or at the
+    /// current position if `offset` is `None`, without exceeding the size
+    /// limit, failing if none may be.
+    fn writable_len(&self, offset: Option<u64>, len: usize) -> io::Result<usize> {
+        let max_size = match self.max_size {
+            Some(max_size) => max_size,
+            None => return Ok(len),
+        };
+        let offset = match offset {
+            Some(offset) => offset,
+            None if max_size.append => self.std.metadata()?.len(),
+            None => (&self.std).stream_position()?,
+        };
+        let room = max_size.limit.saturating_sub(offset);
+        if len != 0 && room == 0 {
+            return Err(file_too_large());
+        }
+        Ok(cmp::min(len as u64, room) as usize)
+    }
+
+    /// Like `writable_len`, but for writes which must be completed in full.
+    fn check_writable(&self, offset: Option<u64>, len: usize) -> io::Result<()> {
+        if self.writable_len(offset, len)? < len {
+            return Err(file_too_large());
+        }
+        Ok(())
+    }
+
+    /// If `self` truncates to what was written, record that a write through
+    /// the file position has finished. If the position can't be found, give
+    /// up on truncating rather than risk cutting off what was written.
+    fn written_to_position(&self) {
+        if self.truncate.0.is_some() {
+            let end = (&self.std).stream_position().unwrap_or(u64::MAX);
+            self.truncate.written_to(end);
+        }
+    }
+
+    /// Like `written_to_position`, for a write of `len` bytes at `offset`.
+    fn written_at(&self, offset: u64, len: usize) {
+        self.truncate.written_to(offset.saturating_add(len as u64));
+    }
+
+    /// With a size limit, vectored writes just write the first non-empty
+    /// buffer, so that only one length needs to be checked.
+    fn first_nonempty<'a>(bufs: &'a [IoSlice]) -> &'a [u8] {
+        bufs.iter()
+            .find(|buf| !buf.is_empty())
+            .map_or(&[], |buf| &**buf)
+    }
+}
+
 #[cfg(not(windows))]
 impl FromRawFd for File {
     #[inline]
@@ -218,7 +550,7 @@ impl AsRawHandleOrSocket for File {
 impl IntoRawFd for File {
     #[inline]
     fn into_raw_fd(self) -> RawFd {
-        self.std.into_raw_fd()
+        self.into_std().into_raw_fd()
     }
 }
 
@@ -226,7 +558,7 @@ impl IntoRawFd for File {
 impl IntoFd for File {
     #[inline]
     fn into_fd(self) -> OwnedFd {
-        self.std.into_fd()
+        self.into_std().into_fd()
     }
 }
 
@@ -234,7 +566,7 @@ impl IntoFd for File {
 impl IntoRawHandle for File {
     #[inline]
     fn into_raw_handle(self) -> RawHandle {
-        self.std.into_raw_handle()
+        self.into_std().into_raw_handle()
     }
 }
 
@@ -242,7 +574,7 @@ impl IntoRawHandle for File {
 impl IntoHandle for File {
     #[inline]
     fn into_handle(self) -> OwnedHandle {
-        self.std.into_handle()
+        self.into_std().into_handle()
     }
 }
 
@@ -250,7 +582,7 @@ impl IntoHandle for File {
 impl IntoRawHandleOrSocket for File {
     #[inline]
     fn into_raw_handle_or_socket(self) -> RawHandleOrSocket {
-        self.std.into_raw_handle_or_socket()
+        self.into_std().into_raw_handle_or_socket()
     }
 }
 
@@ -326,7 +658,7 @@ impl Read for &File {
 impl Write for File {
     #[inline]
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.std.write(buf)
+        (&*self).write(buf)
     }
 
     #[inline]
@@ -336,12 +668,12 @@ impl Write for File {
 
     #[inline]
     fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
-        self.std.write_vectored(bufs)
+        (&*self).write_vectored(bufs)
     }
 
     #[inline]
     fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
-        self.std.write_all(buf)
+        (&*self).write_all(buf)
     }
 
     #[cfg(can_vector)]
@@ -353,14 +685,17 @@ impl Write for File {
     #[cfg(write_all_vectored)]
     #[inline]
     fn write_all_vectored(&mut self, bufs: &mut [IoSlice]) -> io::Result<()> {
-        self.std.write_all_vectored(bufs)
+        (&*self).write_all_vectored(bufs)
     }
 }
 
 impl Write for &File {
     #[inline]
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        (&mut &self.std).write(buf)
+        let len = self.writable_len(None, buf.len())?;
+        let result = (&mut &self.std).write(&buf[..len]);
+        self.written_to_position();
+        result
     }
 
     #[inline]
@@ -370,12 +705,20 @@ impl Write for &File {
 
     #[inline]
     fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
-        (&mut &self.std).write_vectored(bufs)
+        if self.max_size.is_some() {
+            return self.write(File::first_nonempty(bufs));
+        }
+        let result = (&mut &self.std).write_vectored(bufs);
+        self.written_to_position();
+        result
     }
 
     #[inline]
     fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
-        (&mut &self.std).write_all(buf)
+        self.check_writable(None, buf.len())?;
+        let result = (&mut &self.std).write_all(buf);
+        self.written_to_position();
+        result
     }
 
     #[cfg(can_vector)]
@@ -387,7 +730,10 @@ impl Write for &File {
     #[cfg(write_all_vectored)]
     #[inline]
     fn write_all_vectored(&mut self, bufs: &mut [IoSlice]) -> io::Result<()> {
-        (&mut &self.std).write_all_vectored(bufs)
+        self.check_writable(None, bufs.iter().map(|buf| buf.len()).sum())?;
+        let result = (&mut &self.std).write_all_vectored(bufs);
+        self.written_to_position();
+        result
     }
 }
 
@@ -420,7 +766,7 @@ impl Seek for &File {
 impl From<File> for process::Stdio {
     #[inline]
     fn from(file: File) -> Self {
-        From::<fs::File>::from(file.std)
+        From::<fs::File>::from(file.into_std())
     }
 }
 
@@ -433,7 +779,10 @@ impl std::os::unix::fs::FileExt for File {
 
     #[inline]
     fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
-        self.std.write_at(buf, offset)
+        let len = self.writable_len(Some(offset), buf.len())?;
+        let written = self.std.write_at(&buf[..len], offset)?;
+        self.written_at(offset, written);
+        Ok(written)
     }
 
     #[inline]
@@ -443,6 +792,9 @@ impl std::os::unix::fs::FileExt for File {
 
     #[inline]
     fn write_all_at(&self, buf: &[u8], offset: u64) -> io::Result<()> {
+        self.check_writable(Some(offset), buf.len())?;
+        // Even if this fails, some of `buf` may have been written.
+        self.written_at(offset, buf.len());
         self.std.write_all_at(buf, offset)
     }
 }
@@ -456,7 +808,9 @@ impl std::os::wasi::fs::FileExt for File {
 
     #[inline]
     fn write_at(&self, bufs: &[IoSlice], offset: u64) -> io::Result<usize> {
-        self.std.write_at(bufs, offset)
+        let written = self.std.write_at(bufs, offset)?;
+        self.written_at(offset, written);
+        Ok(written)
     }
 
     #[inline]
@@ -530,7 +884,10 @@ impl std::os::windows::fs::FileExt for File {
 
     #[inline]
     fn seek_write(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
-        self.std.seek_write(buf, offset)
+        let len = self.writable_len(Some(offset), buf.len())?;
+        let written = self.std.seek_write(&buf[..len], offset)?;
+        self.written_at(offset, written);
+        Ok(written)
     }
 }
 
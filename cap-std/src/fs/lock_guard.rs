@@ -0,0 +1,64 @@
+use crate::fs::{Dir, File};
+use cap_primitives::fs::unlock_file;
+use std::{fmt, path::PathBuf};
+
+/// An exclusive lock on a lockfile, returned by [`Dir::lock_file`] and
+/// [`Dir::try_lock_file`].
+///
+/// The lock is released when this is dropped. If requested with
+/// [`LockGuard::remove_on_drop`], the lockfile is also removed first.
+pub struct LockGuard<'dir> {
+    file: File,
+    dir: &'dir Dir,
+    path: PathBuf,
+    remove_on_drop: bool,
+}
+
+impl<'dir> LockGuard<'dir> {
+    #[inline]
+    pub(crate) fn new(file: File, dir: &'dir Dir, path: PathBuf) -> Self {
+        Self {
+            file,
+            dir,
+            path,
+            remove_on_drop: false,
+        }
+    }
+
+    /// Returns the locked file.
+    #[inline]
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    /// Sets whether the lockfile is removed when `self` is dropped.
+    ///
+    /// The removal happens while the lock is still held. Note that another
+    /// process which opened the lockfile before it was removed may still
+    /// acquire a lock on the removed file, so this is only suitable when
+    /// such processes can detect that, for example by checking that the
+    /// file still exists after locking it.
+    #[inline]
+    pub fn remove_on_drop(&mut self, remove: bool) {
+        self.remove_on_drop = remove;
+    }
+}
+
+impl<'dir> Drop for LockGuard<'dir> {
+    fn drop(&mut self) {
+        if self.remove_on_drop {
+            self.dir.remove_file(&self.path).ok();
+        }
+        unlock_file(&self.file.std).ok();
+    }
+}
+
+impl<'dir> fmt::Debug for LockGuard<'dir> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LockGuard")
+            .field("file", &self.file)
+            .field("path", &self.path)
+            .field("remove_on_drop", &self.remove_on_drop)
+            .finish()
+    }
+}
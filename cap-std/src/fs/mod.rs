@@ -22,19 +22,50 @@
 //!
 //! [`std::fs`'s free functions]: https://doc.rust-lang.org/std/fs/#functions
 
+#[macro_use]
+mod traced;
+
+#[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+mod access_log;
+mod cached_dir;
+#[cfg(not(target_os = "wasi"))]
+mod container;
 mod dir;
 mod dir_entry;
 mod file;
+mod lock_guard;
+#[cfg(feature = "normalize_names")]
+mod normalized_dir_entry;
 mod read_dir;
+#[cfg(not(target_os = "wasi"))]
+mod read_dir_modified_since;
 
+#[cfg(all(feature = "record_access", not(target_os = "wasi")))]
+pub use access_log::{Access, AccessKind, AccessLog};
+pub use cached_dir::CachedDir;
+#[cfg(not(target_os = "wasi"))]
+pub use container::Container;
 pub use dir::Dir;
+pub(crate) use dir::DirSettings;
 pub use dir_entry::DirEntry;
 pub use file::File;
+pub use lock_guard::LockGuard;
+#[cfg(feature = "normalize_names")]
+pub use normalized_dir_entry::NormalizedDirEntry;
 pub use read_dir::ReadDir;
+#[cfg(not(target_os = "wasi"))]
+pub use read_dir_modified_since::ReadDirModifiedSince;
 
 // Re-export things from `cap_primitives` that we can use as-is.
+pub use cap_primitives::fs::{
+    Acl, AclEntry, AclTag, DanglingSymlinkError, DirId, EscapeError, FsType, MissingComponentError,
+    OverwritePolicy, PipeOptions, StorageErrorKind, SymlinkTargetType, ACL_EXECUTE, ACL_READ,
+    ACL_WRITE,
+};
 #[cfg(not(target_os = "wasi"))]
 pub use cap_primitives::fs::{DirBuilder, FileType, Metadata, OpenOptions, Permissions};
+#[cfg(all(feature = "trace_resolution", not(target_os = "wasi")))]
+pub use cap_primitives::fs::{ResolutionDecision, ResolutionStep};
 
 // Re-export things from `std` that we can use as-is.
 #[cfg(target_os = "wasi")]
@@ -0,0 +1,67 @@
+use crate::fs::DirEntry;
+use std::{
+    ffi::{OsStr, OsString},
+    fmt,
+};
+use unicode_normalization::UnicodeNormalization;
+
+/// An entry returned by [`Dir::read_dir_normalized`], pairing a [`DirEntry`]
+/// with its name normalized to Unicode Normalization Form C.
+///
+/// [`Dir::read_dir_normalized`]: crate::fs::Dir::read_dir_normalized
+pub struct NormalizedDirEntry {
+    name: OsString,
+    entry: DirEntry,
+}
+
+impl NormalizedDirEntry {
+    pub(crate) fn new(entry: DirEntry) -> Self {
+        let raw_name = entry.file_name();
+
+        // Names which aren't valid Unicode can't be normalized, so leave
+        // them as they are.
+        let name = match raw_name.to_str() {
+            Some(s) => s.nfc().collect::<String>().into(),
+            None => raw_name,
+        };
+
+        Self { name, entry }
+    }
+
+    /// Returns the NFC-normalized name of this entry.
+    ///
+    /// This may differ from the name on disk, so it shouldn't be used to
+    /// access the entry; use [`NormalizedDirEntry::raw_name`] or the methods
+    /// of [`NormalizedDirEntry::entry`] instead.
+    #[inline]
+    pub fn name(&self) -> &OsStr {
+        &self.name
+    }
+
+    /// Returns the name of this entry as it is on disk.
+    #[inline]
+    pub fn raw_name(&self) -> OsString {
+        self.entry.file_name()
+    }
+
+    /// Returns the underlying `DirEntry`.
+    #[inline]
+    pub fn entry(&self) -> &DirEntry {
+        &self.entry
+    }
+
+    /// Consumes `self` and returns the underlying `DirEntry`.
+    #[inline]
+    pub fn into_entry(self) -> DirEntry {
+        self.entry
+    }
+}
+
+impl fmt::Debug for NormalizedDirEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NormalizedDirEntry")
+            .field("name", &self.name)
+            .field("entry", &self.entry)
+            .finish()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::fs::DirEntry;
+use crate::fs::{DirEntry, DirSettings};
 use std::{fmt, io};
 
 /// Iterator over the entries in a directory.
@@ -10,6 +10,7 @@ use std::{fmt, io};
 /// ambient paths.
 pub struct ReadDir {
     pub(crate) inner: cap_primitives::fs::ReadDir,
+    pub(crate) settings: DirSettings,
 }
 
 impl Iterator for ReadDir {
@@ -17,9 +18,12 @@ impl Iterator for ReadDir {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner
-            .next()
-            .map(|inner| inner.map(|inner| DirEntry { inner }))
+        self.inner.next().map(|inner| {
+            inner.map(|inner| DirEntry {
+                inner,
+                settings: self.settings.clone(),
+            })
+        })
     }
 }
 
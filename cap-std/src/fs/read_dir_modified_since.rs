@@ -0,0 +1,79 @@
+use crate::{
+    fs::{DirEntry, ReadDir},
+    time::SystemTime,
+};
+use cap_primitives::fs::_DirEntryRawExt;
+use std::{fmt, io};
+
+/// Iterator over the entries in a directory which were modified after a
+/// given time, returned by [`Dir::read_dir_modified_since`].
+///
+/// [`Dir::read_dir_modified_since`]: crate::fs::Dir::read_dir_modified_since
+pub struct ReadDirModifiedSince {
+    inner: ReadDir,
+    since: SystemTime,
+    skip_dirs: bool,
+}
+
+impl ReadDirModifiedSince {
+    pub(crate) fn new(inner: ReadDir, since: SystemTime) -> Self {
+        Self {
+            inner,
+            since,
+            skip_dirs: false,
+        }
+    }
+
+    /// Skip directories, without `stat`ing them where the directory entry
+    /// itself records that they're directories.
+    #[inline]
+    pub fn skip_dirs(mut self) -> Self {
+        self.skip_dirs = true;
+        self
+    }
+
+    /// Test whether `entry` should be yielded, or `None` if it has been
+    /// removed since it was read.
+    fn wanted(&self, entry: &DirEntry) -> Option<io::Result<bool>> {
+        if self.skip_dirs && matches!(entry.file_type_cached(), Some(ty) if ty.is_dir()) {
+            return Some(Ok(false));
+        }
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+            Err(err) => return Some(Err(err)),
+        };
+        if self.skip_dirs && metadata.is_dir() {
+            return Some(Ok(false));
+        }
+        Some(metadata.modified().map(|modified| modified > self.since))
+    }
+}
+
+impl Iterator for ReadDirModifiedSince {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let entry = match self.inner.next()? {
+                Ok(entry) => entry,
+                Err(err) => return Some(Err(err)),
+            };
+            match self.wanted(&entry) {
+                Some(Ok(true)) => return Some(Ok(entry)),
+                Some(Ok(false)) | None => continue,
+                Some(Err(err)) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+impl fmt::Debug for ReadDirModifiedSince {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadDirModifiedSince")
+            .field("inner", &self.inner)
+            .field("since", &self.since)
+            .field("skip_dirs", &self.skip_dirs)
+            .finish()
+    }
+}
@@ -0,0 +1,33 @@
+/// Evaluates `$body`, an `io::Result`, in a `tracing` span named `$name`
+/// with a field for each of the given paths, if any, and records whether it
+/// succeeded, and the error if it didn't, in the span's `ok` and `error`
+/// fields.
+///
+/// Without the `tracing` feature, this is just `$body`.
+macro_rules! traced {
+    ($name:literal $(, $field:ident = $path:expr)* => $body:expr) => {{
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            $name,
+            $($field = %$path.display(),)*
+            ok = tracing::field::Empty,
+            error = tracing::field::Empty,
+        )
+        .entered();
+
+        let result = $body;
+
+        #[cfg(feature = "tracing")]
+        match &result {
+            Ok(_) => {
+                span.record("ok", &true);
+            }
+            Err(err) => {
+                span.record("ok", &false);
+                span.record("error", &tracing::field::display(err));
+            }
+        }
+
+        result
+    }};
+}
@@ -107,6 +107,24 @@ impl cap_primitives::fs::_WindowsDirEntryExt for DirEntry {
     }
 }
 
+#[doc(hidden)]
+impl cap_primitives::fs::_DirEntryRawExt for DirEntry {
+    #[inline]
+    fn ino(&self) -> io::Result<u64> {
+        cap_primitives::fs::_DirEntryRawExt::ino(&self.cap_std)
+    }
+
+    #[inline]
+    fn d_type_raw(&self) -> io::Result<u32> {
+        cap_primitives::fs::_DirEntryRawExt::d_type_raw(&self.cap_std)
+    }
+
+    #[inline]
+    fn file_type_cached(&self) -> Option<FileType> {
+        cap_primitives::fs::_DirEntryRawExt::file_type_cached(&self.cap_std)
+    }
+}
+
 impl fmt::Debug for DirEntry {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.cap_std.fmt(f)
@@ -0,0 +1,150 @@
+#![cfg(feature = "record_access")]
+
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{AccessKind, OpenOptions};
+use std::path::{Path, PathBuf};
+use sys_common::io::tmpdir;
+
+fn accesses(log: &cap_std::fs::AccessLog) -> Vec<(PathBuf, AccessKind)> {
+    log.take()
+        .into_iter()
+        .map(|access| (access.path().to_owned(), access.kind()))
+        .collect()
+}
+
+#[test]
+fn access_log_records_opens() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("src/sub"));
+    check!(tmpdir.write("src/a.rs", b"a"));
+    check!(tmpdir.write("src/sub/b.rs", b"b"));
+
+    let (dir, log) = check!(tmpdir.try_clone()).with_access_recorder();
+
+    check!(dir.read("src/a.rs"));
+    check!(dir.open_with("out.o", OpenOptions::new().write(true).create(true)));
+    assert!(!dir.exists("src/missing.rs"));
+    let sub = check!(dir.open_dir("src/sub"));
+    check!(sub.read_to_string("b.rs"));
+    check!(sub.metadata("."));
+
+    assert_eq!(
+        accesses(&log),
+        vec![
+            (PathBuf::from("src/a.rs"), AccessKind::Read),
+            (PathBuf::from("out.o"), AccessKind::Write),
+            (PathBuf::from("src/missing.rs"), AccessKind::Stat),
+            (PathBuf::from("src/sub/b.rs"), AccessKind::Read),
+            (PathBuf::from("src/sub"), AccessKind::Stat),
+        ]
+    );
+    assert!(log.accesses().is_empty());
+}
+
+#[test]
+fn access_log_entries() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("d"));
+    check!(tmpdir.write("d/f", b"f"));
+
+    let (dir, log) = check!(tmpdir.try_clone()).with_access_recorder();
+    for entry in check!(dir.read_dir("d")) {
+        let entry = check!(entry);
+        check!(entry.open());
+        check!(entry.metadata());
+    }
+
+    assert_eq!(
+        accesses(&log),
+        vec![
+            (PathBuf::from("d/f"), AccessKind::Read),
+            (PathBuf::from("d/f"), AccessKind::Stat),
+        ]
+    );
+}
+
+#[test]
+fn access_log_not_recording() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("f", b"f"));
+
+    let (dir, log) = check!(tmpdir.try_clone()).with_access_recorder();
+
+    // Accesses through the original `Dir` aren't recorded.
+    check!(tmpdir.read("f"));
+    assert!(log.accesses().is_empty());
+
+    // A clone of the recording `Dir` records into the same log.
+    check!(check!(dir.try_clone()).metadata(Path::new("f")));
+    assert_eq!(accesses(&log), vec![(PathBuf::from("f"), AccessKind::Stat)]);
+}
+
+#[test]
+fn access_log_other_opens() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("d"));
+    check!(tmpdir.write("d/f", b"f"));
+
+    let (dir, log) = check!(tmpdir.try_clone()).with_access_recorder();
+    let sub = check!(dir.open_dir("d"));
+    check!(sub.open_child("f", OpenOptions::new().read(true)));
+    check!(sub.open_child("g", OpenOptions::new().write(true).create(true)));
+    check!(dir.open_if("d/f", OpenOptions::new().read(true), |_| false));
+    check!(dir.open_and_resolve("d/f", OpenOptions::new().write(true)));
+
+    assert_eq!(
+        accesses(&log),
+        vec![
+            (PathBuf::from("d/f"), AccessKind::Read),
+            (PathBuf::from("d/g"), AccessKind::Write),
+            (PathBuf::from("d/f"), AccessKind::Read),
+            (PathBuf::from("d/f"), AccessKind::Write),
+        ]
+    );
+}
+
+#[test]
+fn access_log_copy() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("out"));
+    check!(tmpdir.write("f", b"f"));
+    let out = check!(tmpdir.open_dir("out"));
+
+    // Only the recording side of a copy is recorded.
+    let (dir, log) = check!(tmpdir.try_clone()).with_access_recorder();
+    check!(dir.copy("f", &out, "g"));
+    assert_eq!(accesses(&log), vec![(PathBuf::from("f"), AccessKind::Read)]);
+
+    check!(dir.copy_verified("f", &dir, "h"));
+    assert_eq!(
+        accesses(&log),
+        vec![
+            (PathBuf::from("f"), AccessKind::Read),
+            (PathBuf::from("h"), AccessKind::Write),
+        ]
+    );
+}
+
+#[test]
+fn access_log_container() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("etc"));
+    check!(tmpdir.write("etc/hosts", b"localhost"));
+
+    let (dir, log) = check!(tmpdir.try_clone()).with_access_recorder();
+    let container = cap_std::fs::Container::from_dir(dir);
+    check!(container.read("/etc/hosts"));
+    check!(container.write("/etc/motd", b"hi"));
+    check!(container.metadata("/etc"));
+
+    assert_eq!(
+        accesses(&log),
+        vec![
+            (PathBuf::from("etc/hosts"), AccessKind::Read),
+            (PathBuf::from("etc/motd"), AccessKind::Write),
+            (PathBuf::from("etc"), AccessKind::Stat),
+        ]
+    );
+}
@@ -0,0 +1,108 @@
+#![cfg(any(target_os = "android", target_os = "linux"))]
+
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{Acl, AclEntry, AclTag, OpenOptions, Permissions, ACL_READ, ACL_WRITE};
+use std::{
+    io,
+    os::unix::fs::{OpenOptionsExt, PermissionsExt},
+};
+use sys_common::io::tmpdir;
+
+/// A gid which the test doesn't need to be a member of.
+const GID: u32 = 54321;
+
+#[test]
+fn acl_grant_group_read() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+    check!(tmpdir.set_permissions(
+        "file",
+        Permissions::from_std(std::fs::Permissions::from_mode(0o600))
+    ));
+
+    // Without an extended ACL, the ACL reflects the mode.
+    let file = check!(tmpdir.open("file"));
+    assert_eq!(check!(file.get_acl()), Acl::from_mode(0o600));
+
+    let mut acl = Acl::from_mode(0o600);
+    acl.push(AclEntry::new(AclTag::Group(GID), ACL_READ));
+    acl.push(AclEntry::new(AclTag::Mask, ACL_READ));
+    match tmpdir.set_acl("file", &acl) {
+        Ok(()) => (),
+        // The filesystem may not support ACLs.
+        Err(err) if err.kind() == io::ErrorKind::Unsupported => return,
+        Err(err) => panic!("{}", err),
+    }
+
+    let read = check!(check!(tmpdir.open("file")).get_acl());
+    assert_eq!(read, acl);
+    assert_eq!(
+        read.get(AclTag::Group(GID)),
+        Some(&AclEntry::new(AclTag::Group(GID), ACL_READ))
+    );
+    assert_eq!(
+        read.get(AclTag::UserObj).unwrap().perms(),
+        ACL_READ | ACL_WRITE
+    );
+
+    // The group permission bits now reflect the mask.
+    let mode = check!(tmpdir.metadata("file")).permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+}
+
+/// `O_PATH` file descriptors can't be passed to `fgetxattr`, so reading their
+/// ACL goes through "/proc/self/fd".
+#[test]
+#[allow(clippy::unnecessary_cast)]
+fn acl_get_o_path() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+    check!(tmpdir.set_permissions(
+        "file",
+        Permissions::from_std(std::fs::Permissions::from_mode(0o640))
+    ));
+
+    let file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new()
+            .read(true)
+            .custom_flags(posish::fs::OFlags::PATH.bits() as i32)
+    ));
+    assert_eq!(check!(file.get_acl()), Acl::from_mode(0o640));
+}
+
+/// Setting an ACL reopens the file through "/proc/self/fd", because
+/// `fsetxattr` doesn't accept `O_PATH` file descriptors, so it fails cleanly
+/// when the file can't be read, unless we can override that.
+#[test]
+fn acl_no_access() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+    check!(tmpdir.set_permissions(
+        "file",
+        Permissions::from_std(std::fs::Permissions::from_mode(0o000))
+    ));
+
+    match tmpdir.set_acl("file", &Acl::from_mode(0o640)) {
+        Ok(()) => (),
+        // The filesystem may not support ACLs.
+        Err(err) if err.kind() == io::ErrorKind::Unsupported => return,
+        Err(err) if err.kind() == io::ErrorKind::PermissionDenied => {
+            let mode = check!(tmpdir.metadata("file")).permissions().mode();
+            assert_eq!(mode & 0o777, 0o000);
+            return;
+        }
+        Err(err) => panic!("{}", err),
+    }
+
+    let mode = check!(tmpdir.metadata("file")).permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+}
+
+#[test]
+fn acl_escape() {
+    let tmpdir = tmpdir();
+    assert!(tmpdir.set_acl("../file", &Acl::from_mode(0o600)).is_err());
+}
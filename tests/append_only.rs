@@ -0,0 +1,68 @@
+//! Setting the append-only attribute requires `CAP_LINUX_IMMUTABLE`, so these
+//! tests are skipped if `chattr` fails.
+
+#![cfg(target_os = "linux")]
+
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::OpenOptionsAppendOnlyExt;
+use cap_std::{
+    ambient_authority,
+    fs::{Dir, OpenOptions},
+};
+use std::{io::Write, path::Path, process::Command};
+
+fn chattr(flag: &str, path: &Path) -> bool {
+    matches!(Command::new("chattr").arg(flag).arg(path).status(), Ok(status) if status.success())
+}
+
+#[test]
+fn open_append_only_for_writing() {
+    // `chattr` needs an ambient path, so use `tempfile` here.
+    let dir = tempfile::tempdir().unwrap();
+    let tmpdir = check!(Dir::open_ambient_dir(dir.path(), ambient_authority()));
+    check!(tmpdir.write("log", b"first\n"));
+
+    let path = dir.path().join("log");
+    if !chattr("+a", &path) {
+        eprintln!("skipping open_append_only_for_writing: unable to set +a");
+        return;
+    }
+
+    let err = tmpdir
+        .open_with("log", OpenOptions::new().read(true).write(true))
+        .unwrap_err();
+    let msg = err.to_string();
+
+    // Appending still works, and `append_if_append_only` opts into it.
+    let appended = tmpdir.open_with("log", OpenOptions::new().append(true));
+    let fallback = tmpdir.open_with(
+        "log",
+        OpenOptions::new()
+            .read(true)
+            .write(true)
+            .append_if_append_only(true),
+    );
+    let truncated = tmpdir.open_with(
+        "log",
+        OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .append_if_append_only(true),
+    );
+
+    assert!(chattr("-a", &path));
+
+    assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+    assert!(msg.contains("append-only"), "unexpected error: {}", msg);
+
+    check!(check!(appended).write_all(b"second\n"));
+    check!(check!(fallback).write_all(b"third\n"));
+    assert!(truncated.unwrap_err().to_string().contains("append-only"));
+
+    assert_eq!(
+        check!(tmpdir.read_to_string("log")),
+        "first\nsecond\nthird\n"
+    );
+}
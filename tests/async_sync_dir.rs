@@ -0,0 +1,39 @@
+#![cfg(feature = "async_std_sync")]
+
+#[macro_use]
+mod sys_common;
+
+use async_std::io::{ReadExt, WriteExt};
+use sys_common::io::tmpdir;
+
+#[async_std::test]
+async fn sync_to_async_and_back() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    check!(tmpdir.write("sub/file", b"hello"));
+
+    let dir = check!(tmpdir.open_dir("sub"));
+    let id = check!(dir.id());
+
+    // Convert to an async `Dir` and use it.
+    let async_dir = cap_async_std::fs::Dir::from_sync(dir);
+    let mut contents = String::new();
+    let mut file = check!(async_dir.open("file"));
+    check!(file.read_to_string(&mut contents).await);
+    assert_eq!(contents, "hello");
+    drop(file);
+
+    let mut file = check!(async_dir.create("other"));
+    check!(file.write_all(b"world").await);
+    check!(file.flush().await);
+    drop(file);
+
+    // The sandbox is preserved.
+    assert!(async_dir.open("../sub/file").is_err());
+
+    // Convert back, and check that it's the same directory.
+    let dir = async_dir.into_sync();
+    assert_eq!(check!(dir.id()), id);
+    assert_eq!(check!(dir.read_to_string("other")), "world");
+    assert!(dir.open("../sub/file").is_err());
+}
@@ -0,0 +1,110 @@
+// `FILE_FLAG_BACKUP_SEMANTICS` is specific to Windows.
+#![cfg(windows)]
+
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::OpenOptionsBackupSemanticsExt;
+use cap_std::fs::OpenOptions;
+use std::{os::windows::fs::OpenOptionsExt, os::windows::io::AsRawHandle, ptr};
+use sys_common::io::tmpdir;
+use winapi::um::{
+    accctrl::SE_FILE_OBJECT,
+    aclapi::GetSecurityInfo,
+    handleapi::CloseHandle,
+    processthreadsapi::{GetCurrentProcess, OpenProcessToken},
+    securitybaseapi::AdjustTokenPrivileges,
+    winbase::{LocalFree, LookupPrivilegeValueW},
+    winnt::{
+        OWNER_SECURITY_INFORMATION, READ_CONTROL, SE_PRIVILEGE_ENABLED, TOKEN_ADJUST_PRIVILEGES,
+        TOKEN_PRIVILEGES,
+    },
+};
+
+#[test]
+fn backup_semantics_open_dir_handle() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+
+    // Without backup semantics, directories can't be opened as files.
+    assert!(tmpdir.open("dir").is_err());
+
+    let dir = check!(tmpdir.open_with("dir", OpenOptions::new().read(true).backup_semantics(true)));
+    assert!(check!(dir.metadata()).is_dir());
+
+    // Path resolution is still sandboxed.
+    assert!(tmpdir
+        .open_with("..", OpenOptions::new().read(true).backup_semantics(true))
+        .is_err());
+}
+
+/// Enable `SeBackupPrivilege` for the process, returning `false` if the
+/// process doesn't hold it.
+#[allow(unsafe_code)]
+fn enable_backup_privilege() -> bool {
+    let name = "SeBackupPrivilege\0".encode_utf16().collect::<Vec<_>>();
+    unsafe {
+        let mut token = ptr::null_mut();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_ADJUST_PRIVILEGES, &mut token) == 0 {
+            return false;
+        }
+        let mut privileges: TOKEN_PRIVILEGES = std::mem::zeroed();
+        privileges.PrivilegeCount = 1;
+        privileges.Privileges[0].Attributes = SE_PRIVILEGE_ENABLED;
+        let ok = LookupPrivilegeValueW(
+            ptr::null(),
+            name.as_ptr(),
+            &mut privileges.Privileges[0].Luid,
+        ) != 0
+            && AdjustTokenPrivileges(
+                token,
+                0,
+                &mut privileges,
+                0,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            ) != 0
+            // `AdjustTokenPrivileges` succeeds without enabling privileges
+            // the token doesn't hold, reporting that via the last error.
+            && std::io::Error::last_os_error().raw_os_error() == Some(0);
+        CloseHandle(token);
+        ok
+    }
+}
+
+#[test]
+#[allow(unsafe_code)]
+fn backup_semantics_security_info() {
+    if !enable_backup_privilege() {
+        // Only elevated processes hold the backup privilege.
+        return;
+    }
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+
+    let file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new()
+            .access_mode(READ_CONTROL)
+            .backup_semantics(true)
+    ));
+
+    let mut owner = ptr::null_mut();
+    let mut descriptor = ptr::null_mut();
+    let result = unsafe {
+        GetSecurityInfo(
+            file.as_raw_handle() as _,
+            SE_FILE_OBJECT,
+            OWNER_SECURITY_INFORMATION,
+            &mut owner,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut descriptor,
+        )
+    };
+    assert_eq!(result, 0);
+    assert!(!owner.is_null());
+    unsafe { LocalFree(descriptor) };
+}
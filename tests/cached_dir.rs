@@ -0,0 +1,66 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{CachedDir, OpenOptions};
+use std::{io, thread, time::Duration};
+use sys_common::io::tmpdir;
+
+#[test]
+fn cached_dir_remembers_missing() {
+    let tmp = tmpdir();
+    let cached = CachedDir::from_dir(check!(tmp.try_clone()), Duration::from_secs(3600));
+
+    assert_eq!(
+        cached.open("probe").unwrap_err().kind(),
+        io::ErrorKind::NotFound
+    );
+
+    // Create the file behind the cache's back. If the cache asked the OS
+    // again, it would find it.
+    check!(tmp.write("probe", b"hello"));
+    let err = cached.metadata("probe").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.raw_os_error().is_some());
+    assert!(!cached.exists("probe"));
+    assert!(!cached.is_file("probe"));
+
+    // Other paths aren't affected.
+    check!(tmp.write("other", b"other"));
+    assert!(cached.is_file("other"));
+
+    cached.invalidate();
+    assert_eq!(check!(cached.read("probe")), b"hello");
+}
+
+#[test]
+fn cached_dir_ttl() {
+    let tmp = tmpdir();
+    let cached = CachedDir::from_dir(check!(tmp.try_clone()), Duration::from_millis(50));
+
+    assert!(!cached.exists("probe"));
+    check!(tmp.write("probe", b""));
+    thread::sleep(Duration::from_millis(100));
+    assert!(cached.exists("probe"));
+}
+
+#[test]
+fn cached_dir_invalidated_by_create() {
+    let tmp = tmpdir();
+    let cached = CachedDir::from_dir(check!(tmp.try_clone()), Duration::from_secs(3600));
+
+    assert!(!cached.exists("file"));
+    check!(cached.write("file", b"hello"));
+    assert!(cached.is_file("file"));
+
+    assert!(!cached.exists("dir/sub"));
+    check!(cached.create_dir_all("dir/sub"));
+    assert!(cached.is_dir("dir/sub"));
+
+    assert!(!cached.exists("new"));
+    check!(cached.open_with("new", OpenOptions::new().write(true).create_new(true)));
+    assert!(cached.is_file("new"));
+
+    assert!(!cached.exists("one"));
+    check!(cached.create_dir("one"));
+    assert!(cached.is_dir("one"));
+}
@@ -1,8 +1,9 @@
 #[macro_use]
 mod sys_common;
 
-use std::path::Path;
-use sys_common::io::tmpdir;
+use cap_std::fs::SymlinkTargetType;
+use std::path::{Path, PathBuf};
+use sys_common::{io::tmpdir, symlink_supported};
 
 #[test]
 fn canonicalize_dot() {
@@ -141,3 +142,33 @@ fn canonicalize_edge_cases() {
     #[cfg(windows)]
     error!(tmpdir.canonicalize("foo/bar/qux"), 2);
 }
+
+#[test]
+fn canonicalize_with_chain_two_hops() {
+    if !symlink_supported() {
+        return;
+    }
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a"));
+    check!(tmpdir.create_dir_all("b"));
+    check!(tmpdir.write("b/file", b"hello"));
+    check!(tmpdir.symlink_with_type("link2", "a/link1", SymlinkTargetType::Dir));
+    check!(tmpdir.symlink_with_type("../b", "a/link2", SymlinkTargetType::Dir));
+
+    let (canonical, chain) = check!(tmpdir.canonicalize_with_chain("a/link1/file"));
+    assert_eq!(canonical, Path::new("b/file"));
+    assert_eq!(canonical, check!(tmpdir.canonicalize("a/link1/file")));
+    assert_eq!(
+        chain,
+        vec![
+            (PathBuf::from("a/link1"), PathBuf::from("a/link2")),
+            (PathBuf::from("a/link2"), PathBuf::from("a/../b")),
+        ]
+    );
+
+    // Paths without symlinks have an empty chain.
+    let (canonical, chain) = check!(tmpdir.canonicalize_with_chain("b/./file"));
+    assert_eq!(canonical, Path::new("b/file"));
+    assert!(chain.is_empty());
+}
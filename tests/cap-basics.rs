@@ -89,7 +89,8 @@ fn cap_smoke_test() {
     #[cfg(windows)]
     error!(inner.open_dir(""), 2);
 
-    error_contains!(inner.open_dir("/"), "a path led outside of the filesystem");
+    // A lone `/` refers to the root of the sandbox, which here is `inner`.
+    check!(inner.open_dir("/"));
     error_contains!(
         inner.open_dir("/etc/services"),
         "a path led outside of the filesystem"
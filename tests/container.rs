@@ -0,0 +1,132 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::Container;
+use sys_common::io::tmpdir;
+
+#[test]
+fn container_absolute_paths() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("rootfs"));
+    let container = Container::from_dir(check!(tmpdir.open_dir("rootfs")));
+
+    check!(container.create_dir_all("/etc"));
+    check!(container.write("/etc/hostname", b"sandbox"));
+    assert_eq!(
+        check!(tmpdir.read_to_string("rootfs/etc/hostname")),
+        "sandbox"
+    );
+
+    assert_eq!(check!(container.read_to_string("/etc/hostname")), "sandbox");
+    assert_eq!(check!(container.read_to_string("etc/hostname")), "sandbox");
+    assert!(check!(container.metadata("/etc")).is_dir());
+    assert!(container.exists("/etc/hostname"));
+
+    let etc = check!(container.open_dir("/etc"));
+    assert_eq!(check!(etc.read_to_string("hostname")), "sandbox");
+}
+
+#[test]
+fn container_dotdot_is_clamped() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("rootfs/etc"));
+    check!(tmpdir.write("rootfs/etc/hostname", b"inside"));
+    check!(tmpdir.write("hostname", b"outside"));
+    let container = Container::from_dir(check!(tmpdir.open_dir("rootfs")));
+
+    assert_eq!(
+        check!(container.read_to_string("../../etc/hostname")),
+        "inside"
+    );
+    assert_eq!(
+        check!(container.read_to_string("/etc/../../etc/./hostname")),
+        "inside"
+    );
+
+    // `/../hostname` names `/hostname` within the container, not the
+    // `hostname` beside the root.
+    assert!(container.open("/../hostname").is_err());
+    assert!(container.open("../hostname").is_err());
+}
+
+#[test]
+fn container_create_and_remove() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("rootfs"));
+    let container = Container::from_dir(check!(tmpdir.open_dir("rootfs")));
+
+    check!(container.create_dir("/var"));
+    check!(container.create_dir_all("/var/lib/app"));
+    check!(container.write("/var/lib/app/state", b"1"));
+    check!(container.rename("/var/lib/app/state", "/var/state"));
+    assert!(tmpdir.exists("rootfs/var/state"));
+
+    let names = check!(container.read_dir("/var"))
+        .map(|entry| check!(entry).file_name())
+        .collect::<Vec<_>>();
+    assert_eq!(names.len(), 2);
+
+    check!(container.remove_file("/var/state"));
+    check!(container.remove_dir("/var/lib/app"));
+    check!(container.remove_dir_all("/var"));
+    assert!(!tmpdir.exists("rootfs/var"));
+}
+
+/// Absolute symlink targets resolve within the container too.
+#[cfg(not(windows))]
+#[test]
+fn container_absolute_symlink() {
+    use cap_std::{ambient_authority, fs::Dir};
+
+    // `Dir::symlink` refuses absolute targets, so create the link with `std`.
+    let rootfs = tempfile::tempdir().unwrap();
+    check!(std::fs::create_dir_all(
+        rootfs.path().join("usr/share/zoneinfo")
+    ));
+    check!(std::fs::create_dir(rootfs.path().join("etc")));
+    check!(std::fs::write(
+        rootfs.path().join("usr/share/zoneinfo/UTC"),
+        b"utc"
+    ));
+    check!(std::os::unix::fs::symlink(
+        "/usr/share/zoneinfo/UTC",
+        rootfs.path().join("etc/localtime")
+    ));
+    let container = Container::from_dir(check!(Dir::open_ambient_dir(
+        rootfs.path(),
+        ambient_authority()
+    )));
+
+    assert_eq!(check!(container.read_to_string("/etc/localtime")), "utc");
+
+    // Sandboxed `Dir` resolution still rejects the absolute target.
+    assert!(container.root().open("etc/localtime").is_err());
+}
+
+/// `..` after a symlink steps out of the symlink's target, not out of the
+/// directory containing the symlink, in every method.
+#[cfg(not(windows))]
+#[test]
+fn container_symlink_dotdot() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("rootfs/a/b"));
+    check!(tmpdir.write("rootfs/x", b"root"));
+    check!(tmpdir.write("rootfs/a/x", b"a"));
+    let container = Container::from_dir(check!(tmpdir.open_dir("rootfs")));
+    check!(container.root().symlink("a/b", "link"));
+
+    assert_eq!(check!(container.read_to_string("link/../x")), "a");
+    assert_eq!(check!(container.metadata("/link/../x")).len(), 1);
+    assert!(check!(container.symlink_metadata("link/../x")).is_file());
+    assert!(container.exists("link/../x"));
+    assert_eq!(check!(container.read_dir("link/..")).count(), 2);
+
+    check!(container.create_dir("link/../c"));
+    assert!(tmpdir.exists("rootfs/a/c"));
+    check!(container.remove_dir("link/../c"));
+    check!(container.rename("link/../x", "link/../y"));
+    assert!(tmpdir.exists("rootfs/a/y"));
+    check!(container.remove_file("link/../y"));
+    assert!(!tmpdir.exists("rootfs/a/y"));
+    assert_eq!(check!(tmpdir.read_to_string("rootfs/x")), "root");
+}
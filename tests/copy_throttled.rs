@@ -0,0 +1,58 @@
+#[macro_use]
+mod sys_common;
+
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+use sys_common::io::tmpdir;
+
+#[test]
+fn copy_throttled_rate() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dst"));
+    let dst = check!(tmpdir.open_dir("dst"));
+
+    let contents = (0..200_000_u32).map(|i| i as u8).collect::<Vec<u8>>();
+    check!(tmpdir.write("src", &contents));
+
+    // At 400 KB/s, this should take about half a second.
+    let start = Instant::now();
+    assert_eq!(
+        check!(tmpdir.copy_throttled("src", &dst, "copy", 400_000)),
+        contents.len() as u64
+    );
+    let elapsed = start.elapsed();
+    assert_eq!(check!(dst.read("copy")), contents);
+
+    // The pacing never finishes early, but leave generous slack for a
+    // loaded machine on the upper end.
+    assert!(elapsed >= Duration::from_millis(450), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_millis(2000), "{:?}", elapsed);
+}
+
+#[test]
+fn copy_throttled_empty() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("src", b""));
+
+    assert_eq!(check!(tmpdir.copy_throttled("src", &tmpdir, "copy", 1)), 0);
+    assert_eq!(check!(tmpdir.read("copy")), b"");
+}
+
+#[test]
+fn copy_throttled_invalid() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("src", b"hello"));
+    check!(tmpdir.create_dir("dir"));
+
+    let err = tmpdir
+        .copy_throttled("src", &tmpdir, "copy", 0)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let err = tmpdir
+        .copy_throttled("dir", &tmpdir, "copy", 1000)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(!tmpdir.exists("copy"));
+}
@@ -0,0 +1,54 @@
+#[macro_use]
+mod sys_common;
+
+use std::io;
+use sys_common::io::tmpdir;
+
+#[test]
+fn copy_verified_basic() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dst"));
+    let dst = check!(tmpdir.open_dir("dst"));
+
+    // Use enough data to span several buffers.
+    let contents = (0..300_000_u32).map(|i| i as u8).collect::<Vec<u8>>();
+    check!(tmpdir.write("src", &contents));
+
+    assert_eq!(
+        check!(tmpdir.copy_verified("src", &dst, "copy")),
+        contents.len() as u64
+    );
+    assert_eq!(check!(dst.read("copy")), contents);
+
+    // An existing destination is replaced.
+    check!(tmpdir.write("small", b"small"));
+    assert_eq!(check!(tmpdir.copy_verified("small", &dst, "copy")), 5);
+    assert_eq!(check!(dst.read("copy")), b"small");
+}
+
+#[test]
+fn copy_verified_not_file() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+
+    let err = tmpdir.copy_verified("dir", &tmpdir, "copy").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(!tmpdir.exists("copy"));
+}
+
+#[cfg(unix)]
+#[test]
+fn copy_verified_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("src", b"hello"));
+    check!(tmpdir.set_permissions(
+        "src",
+        cap_std::fs::Permissions::from_std(std::fs::Permissions::from_mode(0o640))
+    ));
+
+    check!(tmpdir.copy_verified("src", &tmpdir, "copy"));
+    let mode = check!(tmpdir.metadata("copy")).permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+}
@@ -0,0 +1,105 @@
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::{DirExt, SystemTimeSpec};
+use cap_std::{
+    fs::{Dir, OverwritePolicy},
+    time::{Duration, SystemTime},
+};
+use std::io;
+use sys_common::io::tmpdir;
+
+/// Create "src" and, unless `dst` is `None`, a "dst" which is older or newer
+/// than "src".
+fn setup(dst: Option<bool>) -> sys_common::io::TempDir {
+    let tmpdir = tmpdir();
+    let now = SystemTime::from_std(std::time::SystemTime::now());
+    check!(tmpdir.write("src", b"source"));
+    check!(tmpdir.set_mtime("src", SystemTimeSpec::Absolute(now)));
+    if let Some(newer) = dst {
+        let mtime = if newer {
+            now + Duration::from_secs(60)
+        } else {
+            now - Duration::from_secs(60)
+        };
+        check!(tmpdir.write("dst", b"destination"));
+        check!(tmpdir.set_mtime("dst", SystemTimeSpec::Absolute(mtime)));
+    }
+    tmpdir
+}
+
+fn copy(tmpdir: &Dir, policy: OverwritePolicy) -> io::Result<u64> {
+    tmpdir.copy_with_policy("src", tmpdir, "dst", policy)
+}
+
+#[test]
+fn overwrite() {
+    for &dst in &[None, Some(false), Some(true)] {
+        let tmpdir = setup(dst);
+        assert_eq!(check!(copy(&tmpdir, OverwritePolicy::Overwrite)), 6);
+        assert_eq!(check!(tmpdir.read("dst")), b"source");
+    }
+}
+
+#[test]
+fn skip() {
+    let tmpdir = setup(None);
+    assert_eq!(check!(copy(&tmpdir, OverwritePolicy::Skip)), 6);
+    assert_eq!(check!(tmpdir.read("dst")), b"source");
+
+    for &dst in &[Some(false), Some(true)] {
+        let tmpdir = setup(dst);
+        assert_eq!(check!(copy(&tmpdir, OverwritePolicy::Skip)), 0);
+        assert_eq!(check!(tmpdir.read("dst")), b"destination");
+    }
+}
+
+#[test]
+fn error() {
+    let tmpdir = setup(None);
+    assert_eq!(check!(copy(&tmpdir, OverwritePolicy::Error)), 6);
+    assert_eq!(check!(tmpdir.read("dst")), b"source");
+
+    for &dst in &[Some(false), Some(true)] {
+        let tmpdir = setup(dst);
+        assert_eq!(
+            copy(&tmpdir, OverwritePolicy::Error).unwrap_err().kind(),
+            io::ErrorKind::AlreadyExists
+        );
+        assert_eq!(check!(tmpdir.read("dst")), b"destination");
+    }
+}
+
+#[test]
+fn overwrite_if_newer() {
+    let tmpdir = setup(None);
+    assert_eq!(check!(copy(&tmpdir, OverwritePolicy::OverwriteIfNewer)), 6);
+    assert_eq!(check!(tmpdir.read("dst")), b"source");
+
+    // The destination is older than the source.
+    let tmpdir = setup(Some(false));
+    assert_eq!(check!(copy(&tmpdir, OverwritePolicy::OverwriteIfNewer)), 6);
+    assert_eq!(check!(tmpdir.read("dst")), b"source");
+
+    // The destination is newer than the source.
+    let tmpdir = setup(Some(true));
+    assert_eq!(check!(copy(&tmpdir, OverwritePolicy::OverwriteIfNewer)), 0);
+    assert_eq!(check!(tmpdir.read("dst")), b"destination");
+}
+
+#[test]
+fn missing_source() {
+    let tmpdir = tmpdir();
+    for &policy in &[
+        OverwritePolicy::Overwrite,
+        OverwritePolicy::Skip,
+        OverwritePolicy::Error,
+        OverwritePolicy::OverwriteIfNewer,
+    ] {
+        assert_eq!(
+            copy(&tmpdir, policy).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+        assert!(!tmpdir.exists("dst"));
+    }
+}
@@ -0,0 +1,80 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::Dir;
+use std::{ffi::OsStr, io, path::PathBuf};
+use sys_common::io::tmpdir;
+
+/// List the paths of all directories under `dir`, sorted.
+fn tree(dir: &Dir) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let mut stack = vec![PathBuf::new()];
+    while let Some(path) = stack.pop() {
+        let entries = if path.as_os_str().is_empty() {
+            check!(dir.entries())
+        } else {
+            check!(dir.read_dir(&path))
+        };
+        for entry in entries {
+            let entry = check!(entry);
+            let child = path.join(entry.file_name());
+            if check!(entry.file_type()).is_dir() {
+                stack.push(child.clone());
+            }
+            paths.push(child);
+        }
+    }
+    paths.sort();
+    paths
+}
+
+#[test]
+fn matches_create_dir_all() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("by_path"));
+    check!(tmpdir.create_dir("by_components"));
+    let by_path = check!(tmpdir.open_dir("by_path"));
+    let by_components = check!(tmpdir.open_dir("by_components"));
+
+    for path in &["a/b/c", "a/b/d", "a/e", "f"] {
+        check!(by_path.create_dir_all(path));
+        let components = path.split('/').map(OsStr::new).collect::<Vec<_>>();
+        check!(by_components.create_dir_all_components(&components));
+    }
+
+    assert_eq!(tree(&by_path), tree(&by_components));
+    assert!(by_components.is_dir("a/b/c"));
+
+    // Creating what already exists succeeds, as with `create_dir_all`.
+    check!(by_components.create_dir_all_components(&[OsStr::new("a"), OsStr::new("b")]));
+    check!(by_components.create_dir_all_components(&[]));
+}
+
+#[test]
+fn rejects_non_normal_components() {
+    let tmpdir = tmpdir();
+
+    for component in &["..", ".", "", "/", "/a", "a/b"] {
+        let err = tmpdir
+            .create_dir_all_components(&[OsStr::new("x"), OsStr::new(component)])
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{:?}", component);
+    }
+
+    // Nothing was created before the check.
+    assert!(!tmpdir.exists("x"));
+}
+
+#[test]
+fn existing_file_fails() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("a"));
+    check!(tmpdir.write("a/file", b""));
+
+    assert!(tmpdir
+        .create_dir_all_components(&[OsStr::new("a"), OsStr::new("file")])
+        .is_err());
+    assert!(tmpdir
+        .create_dir_all_components(&[OsStr::new("a"), OsStr::new("file"), OsStr::new("b")])
+        .is_err());
+}
@@ -0,0 +1,89 @@
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::OpenOptionsCreateParentsExt;
+use cap_std::fs::OpenOptions;
+use std::io::Write;
+use sys_common::io::tmpdir;
+
+#[test]
+fn create_parents() {
+    let tmpdir = tmpdir();
+
+    let mut file = check!(tmpdir.open_with(
+        "a/b/c/file.txt",
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .create_parents(true)
+    ));
+    check!(file.write_all(b"hello, world"));
+    drop(file);
+
+    assert!(check!(tmpdir.metadata("a/b/c")).is_dir());
+    assert_eq!(check!(tmpdir.read("a/b/c/file.txt")), b"hello, world");
+
+    // Existing parents are fine too.
+    check!(tmpdir.open_with(
+        "a/b/other.txt",
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .create_parents(true)
+    ));
+    assert!(tmpdir.exists("a/b/other.txt"));
+}
+
+#[test]
+fn create_parents_not_set() {
+    let tmpdir = tmpdir();
+
+    assert_eq!(
+        tmpdir
+            .open_with(
+                "a/b/c/file.txt",
+                OpenOptions::new().write(true).create(true)
+            )
+            .unwrap_err()
+            .kind(),
+        std::io::ErrorKind::NotFound
+    );
+    assert!(!tmpdir.exists("a"));
+}
+
+#[test]
+fn create_parents_without_create() {
+    let tmpdir = tmpdir();
+
+    // Without `create`, the file wouldn't be created, so neither are its
+    // parents.
+    assert_eq!(
+        tmpdir
+            .open_with(
+                "a/b/c/file.txt",
+                OpenOptions::new().write(true).create_parents(true)
+            )
+            .unwrap_err()
+            .kind(),
+        std::io::ErrorKind::NotFound
+    );
+    assert!(!tmpdir.exists("a"));
+}
+
+#[test]
+fn create_parents_sandboxed() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("inner"));
+    let inner = check!(tmpdir.open_dir("inner"));
+
+    assert!(inner
+        .open_with(
+            "../escaped/file.txt",
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .create_parents(true)
+        )
+        .is_err());
+    assert!(!tmpdir.exists("escaped"));
+}
@@ -0,0 +1,54 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{DanglingSymlinkError, SymlinkTargetType};
+use std::io;
+use sys_common::{io::tmpdir, symlink_supported};
+
+#[test]
+fn missing_file_not_dangling() {
+    let tmpdir = tmpdir();
+
+    let err = tmpdir.open("missing").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(DanglingSymlinkError::from_io_error(&err).is_none());
+    assert!(err.raw_os_error().is_some());
+
+    let err = tmpdir.open("missing/file").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(DanglingSymlinkError::from_io_error(&err).is_none());
+}
+
+#[test]
+fn dangling_symlink() {
+    if !symlink_supported() {
+        return;
+    }
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.symlink_with_type("missing", "link", SymlinkTargetType::File));
+    check!(tmpdir.symlink_with_type("link", "link_to_link", SymlinkTargetType::File));
+
+    for path in &["link", "link_to_link"] {
+        let err = tmpdir.open(path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        let dangling = DanglingSymlinkError::from_io_error(&err).unwrap();
+        assert!(err.to_string().contains("symlink"));
+        assert!(dangling.raw_os_error().is_some());
+    }
+
+    // Without following symlinks, the symlink itself is what's opened.
+    #[cfg(not(windows))]
+    {
+        use cap_fs_ext::{FollowSymlinks, OpenOptionsFollowExt};
+        use cap_std::fs::OpenOptions;
+
+        let err = tmpdir
+            .open_with(
+                "link",
+                OpenOptions::new().read(true).follow(FollowSymlinks::No),
+            )
+            .unwrap_err();
+        assert!(DanglingSymlinkError::from_io_error(&err).is_none());
+    }
+}
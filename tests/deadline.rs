@@ -0,0 +1,61 @@
+#[macro_use]
+mod sys_common;
+
+use cap_primitives::fs::with_deadline;
+use std::{
+    io,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use sys_common::io::tmpdir;
+
+fn deep_path(depth: usize) -> PathBuf {
+    (0..depth).map(|_| "a").collect()
+}
+
+#[test]
+fn deadline_exceeded() {
+    let tmpdir = tmpdir();
+    let path = deep_path(64);
+    check!(tmpdir.create_dir_all(&path));
+
+    let result = with_deadline(Instant::now(), || tmpdir.open_dir(&path));
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::TimedOut);
+
+    let result = with_deadline(Instant::now(), || tmpdir.metadata(&path));
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::TimedOut);
+
+    // Outside of `with_deadline`, there's no deadline.
+    check!(tmpdir.open_dir(&path));
+}
+
+#[test]
+fn deadline_not_exceeded() {
+    let tmpdir = tmpdir();
+    let path = deep_path(8);
+    check!(tmpdir.create_dir_all(&path));
+
+    let deadline = Instant::now() + Duration::from_secs(3600);
+    check!(with_deadline(deadline, || tmpdir.open_dir(&path)));
+}
+
+#[test]
+fn deadline_nested() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("a"));
+
+    // The inner, later deadline doesn't extend the outer, earlier one.
+    let later = Instant::now() + Duration::from_secs(3600);
+    let result = with_deadline(Instant::now(), || {
+        with_deadline(later, || tmpdir.open_dir("a"))
+    });
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::TimedOut);
+
+    // The outer deadline is restored after the inner one ends.
+    let earlier = Instant::now();
+    let result = with_deadline(later, || {
+        with_deadline(earlier, || ());
+        tmpdir.open_dir("a")
+    });
+    check!(result);
+}
@@ -0,0 +1,81 @@
+#![cfg(target_os = "linux")]
+
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{Dir, File, FsType, OpenOptions};
+use std::os::unix::fs::MetadataExt;
+use sys_common::io::tmpdir;
+
+const LEN: usize = 16 * 4096;
+
+/// Returns whether `dir` is on a filesystem which supports deduplication.
+fn supports_dedupe(dir: &Dir) -> bool {
+    match check!(dir.filesystem_type()) {
+        FsType::Btrfs | FsType::Xfs => true,
+        _ => {
+            eprintln!("skipping: the test directory isn't on Btrfs or XFS");
+            false
+        }
+    }
+}
+
+fn open_pair(dir: &Dir, src: &[u8], dst: &[u8]) -> (File, File) {
+    check!(dir.write("src", src));
+    check!(dir.write("dst", dst));
+    let src = check!(dir.open("src"));
+    let dst = check!(dir.open_with("dst", OpenOptions::new().read(true).write(true)));
+    (src, dst)
+}
+
+#[test]
+fn dedupe_identical() {
+    let tmpdir = tmpdir();
+    if !supports_dedupe(&tmpdir) {
+        return;
+    }
+
+    let contents = (0..LEN).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+    let (src, dst) = open_pair(&tmpdir, &contents, &contents);
+    check!(dst.sync_all());
+    let blocks_before = check!(dst.metadata()).blocks();
+
+    assert!(check!(dst.dedupe_range(&src, 0, LEN as u64, 0)));
+    assert_eq!(check!(tmpdir.read("dst")), contents);
+
+    // Sharing isn't directly visible in the metadata, but deduplication
+    // must not have allocated anything new.
+    check!(dst.sync_all());
+    assert!(check!(dst.metadata()).blocks() <= blocks_before);
+}
+
+#[test]
+fn dedupe_differing() {
+    let tmpdir = tmpdir();
+    if !supports_dedupe(&tmpdir) {
+        return;
+    }
+
+    let contents = vec![0xa5_u8; LEN];
+    let mut other = contents.clone();
+    other[LEN / 2] ^= 1;
+    let (src, dst) = open_pair(&tmpdir, &contents, &other);
+
+    assert!(!check!(dst.dedupe_range(&src, 0, LEN as u64, 0)));
+    assert_eq!(check!(tmpdir.read("dst")), other);
+}
+
+#[test]
+fn dedupe_unsupported_fs() {
+    let tmpdir = tmpdir();
+    if matches!(
+        check!(tmpdir.filesystem_type()),
+        FsType::Btrfs | FsType::Xfs | FsType::Other(_)
+    ) {
+        return;
+    }
+
+    let contents = vec![0_u8; LEN];
+    let (src, dst) = open_pair(&tmpdir, &contents, &contents);
+    assert!(dst.dedupe_range(&src, 0, LEN as u64, 0).is_err());
+}
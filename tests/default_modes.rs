@@ -0,0 +1,83 @@
+#![cfg(unix)]
+
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{Dir, DirBuilder, OpenOptions};
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
+use sys_common::io::tmpdir;
+
+fn mode(dir: &Dir, path: &str) -> u32 {
+    check!(dir.metadata(path)).permissions().mode() & 0o777
+}
+
+#[test]
+fn default_file_mode() {
+    let tmp = tmpdir();
+    let dir = check!(tmp.try_clone()).with_default_file_mode(0o600);
+
+    check!(dir.write("file", b"hello"));
+    assert_eq!(mode(&dir, "file"), 0o600);
+
+    check!(dir.open_with("new", OpenOptions::new().write(true).create_new(true)));
+    assert_eq!(mode(&dir, "new"), 0o600);
+
+    // An explicit mode takes precedence.
+    check!(dir.open_with(
+        "explicit",
+        OpenOptions::new().write(true).create(true).mode(0o640)
+    ));
+    assert_eq!(mode(&dir, "explicit"), 0o640);
+
+    // Directories aren't affected.
+    check!(dir.create_dir("subdir"));
+    assert_eq!(mode(&dir, "subdir"), mode(&tmp, "subdir"));
+}
+
+#[test]
+fn default_dir_mode() {
+    let tmp = tmpdir();
+    let dir = check!(tmp.try_clone()).with_default_dir_mode(0o700);
+
+    check!(dir.create_dir("one"));
+    assert_eq!(mode(&dir, "one"), 0o700);
+
+    check!(dir.create_dir_all("a/b"));
+    assert_eq!(mode(&dir, "a"), 0o700);
+    assert_eq!(mode(&dir, "a/b"), 0o700);
+
+    // An explicit mode takes precedence.
+    check!(dir.create_dir_with("explicit", DirBuilder::new().mode(0o750)));
+    assert_eq!(mode(&dir, "explicit"), 0o750);
+}
+
+#[test]
+fn default_modes_carry_over() {
+    let tmp = tmpdir();
+    check!(tmp.create_dir("sub"));
+    let dir = check!(tmp.try_clone())
+        .with_default_file_mode(0o600)
+        .with_default_dir_mode(0o700);
+
+    let sub = check!(dir.open_dir("sub"));
+    check!(sub.write("file", b"hello"));
+    check!(sub.create_dir("dir"));
+    assert_eq!(mode(&sub, "file"), 0o600);
+    assert_eq!(mode(&sub, "dir"), 0o700);
+}
+
+#[test]
+fn default_modes_carry_over_read_dir() {
+    let tmp = tmpdir();
+    check!(tmp.create_dir("sub"));
+    let dir = check!(tmp.try_clone())
+        .with_default_file_mode(0o600)
+        .with_default_dir_mode(0o700);
+
+    let entry = check!(check!(dir.entries()).next().unwrap());
+    let sub = check!(entry.open_dir());
+    check!(sub.write("file", b"hello"));
+    check!(sub.create_dir("dir"));
+    assert_eq!(mode(&sub, "file"), 0o600);
+    assert_eq!(mode(&sub, "dir"), 0o700);
+}
@@ -31,3 +31,44 @@ fn test_dir_entry_ext() {
         check!(entry.full_metadata()).ino();
     }
 }
+
+#[test]
+fn dir_entry_ext_raw() {
+    use cap_fs_ext::DirEntryRawExt;
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.create("a"));
+    check!(tmpdir.create_dir("b"));
+
+    for entry in check!(tmpdir.entries()) {
+        let entry = check!(entry);
+        assert_ne!(check!(entry.ino()), 0);
+        check!(entry.d_type_raw());
+
+        // Linux filesystems we run tests on all record the type in the
+        // directory entry.
+        #[cfg(any(target_os = "linux", windows))]
+        {
+            let file_type = entry.file_type_cached().unwrap();
+            assert_eq!(file_type, check!(entry.file_type()));
+            match entry.file_name().to_str().unwrap() {
+                "a" => assert!(file_type.is_file()),
+                "b" => assert!(file_type.is_dir()),
+                name => panic!("unexpected entry {}", name),
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn dir_entry_ext_d_type() {
+    use cap_fs_ext::DirEntryRawExt;
+
+    const DT_REG: u32 = 8;
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.create("a"));
+    let entry = check!(check!(tmpdir.entries()).next().unwrap());
+    assert_eq!(check!(entry.d_type_raw()), DT_REG);
+}
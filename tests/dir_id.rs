@@ -0,0 +1,30 @@
+#[macro_use]
+mod sys_common;
+
+use std::collections::HashSet;
+use sys_common::io::tmpdir;
+
+#[test]
+fn dir_id_dedup() {
+    let tmpdir = tmpdir();
+    let clone = check!(tmpdir.try_clone());
+
+    let mut ids = HashSet::new();
+    ids.insert(check!(tmpdir.id()));
+    ids.insert(check!(clone.id()));
+    assert_eq!(ids.len(), 1);
+}
+
+#[test]
+fn dir_id_separately_opened() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("a"));
+    check!(tmpdir.create_dir("b"));
+    let a = check!(tmpdir.open_dir("a"));
+    let a_again = check!(tmpdir.open_dir("b/../a"));
+    let b = check!(tmpdir.open_dir("b"));
+
+    assert_eq!(check!(a.id()), check!(a_again.id()));
+    assert_ne!(check!(a.id()), check!(b.id()));
+    assert_ne!(check!(a.id()), check!(tmpdir.id()));
+}
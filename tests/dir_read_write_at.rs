@@ -0,0 +1,46 @@
+#[macro_use]
+mod sys_common;
+
+use sys_common::io::tmpdir;
+
+#[test]
+fn read_at_by_path() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello, world"));
+
+    let mut buf = [0_u8; 5];
+    assert_eq!(check!(tmpdir.read_at("file", &mut buf, 7)), 5);
+    assert_eq!(&buf, b"world");
+
+    // Reading past the end returns what's there.
+    assert_eq!(check!(tmpdir.read_at("file", &mut buf, 10)), 2);
+    assert_eq!(&buf[..2], b"ld");
+    assert_eq!(check!(tmpdir.read_at("file", &mut buf, 100)), 0);
+}
+
+#[test]
+fn write_at_by_path() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello, world"));
+
+    assert_eq!(check!(tmpdir.write_at("file", b"WORLD", 7)), 5);
+    assert_eq!(check!(tmpdir.read("file")), b"hello, WORLD");
+
+    assert_eq!(check!(tmpdir.write_at("file", b"H", 0)), 1);
+    assert_eq!(check!(tmpdir.read("file")), b"Hello, WORLD");
+}
+
+#[test]
+fn write_at_does_not_create() {
+    let tmpdir = tmpdir();
+    assert!(tmpdir.write_at("missing", b"data", 0).is_err());
+    assert!(!tmpdir.exists("missing"));
+}
+
+#[test]
+fn read_write_at_sandboxed() {
+    let tmpdir = tmpdir();
+    let mut buf = [0_u8; 1];
+    assert!(tmpdir.read_at("../file", &mut buf, 0).is_err());
+    assert!(tmpdir.write_at("/file", b"x", 0).is_err());
+}
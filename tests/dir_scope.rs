@@ -0,0 +1,115 @@
+#[macro_use]
+mod sys_common;
+
+#[cfg(feature = "trace_resolution")]
+use cap_std::fs::{OpenOptions, ResolutionDecision, ResolutionStep};
+use sys_common::io::tmpdir;
+
+#[test]
+fn scope_operations() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/b/c"));
+
+    let scoped = check!(tmpdir.scope("a/b/c"));
+    for i in 0..10 {
+        check!(scoped.write(format!("file{}", i), b"data"));
+    }
+    assert_eq!(check!(tmpdir.read_dir("a/b/c")).count(), 10);
+    assert_eq!(check!(tmpdir.read("a/b/c/file3")), b"data");
+
+    // The scoped `Dir` is sandboxed to the subdirectory.
+    assert!(scoped.open("file3").is_ok());
+    assert!(scoped.open("../c/file3").is_err());
+
+    let clone = check!(scoped.try_clone());
+    assert_eq!(check!(clone.id()), check!(scoped.id()));
+}
+
+#[test]
+fn batch_operations() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/b/c"));
+
+    let count = check!(tmpdir.batch("a/b/c", |dir| {
+        for i in 0..10 {
+            dir.write(format!("file{}", i), b"data")?;
+        }
+        Ok(dir.entries()?.count())
+    }));
+    assert_eq!(count, 10);
+    assert!(tmpdir.exists("a/b/c/file9"));
+
+    // Errors from resolving the path and from the closure are both returned.
+    assert!(tmpdir.batch("missing", |_| Ok(())).is_err());
+    assert!(tmpdir
+        .batch("a/b/c", |dir| dir.open("missing").map(drop))
+        .is_err());
+}
+
+/// `open_dir` holds an `O_PATH` file descriptor, the least a `Dir` needs,
+/// and clones share the same directory.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn open_dir_minimal_fd() {
+    use posish::fs::{fcntl_getfl, OFlags};
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/b/c"));
+
+    let dir = check!(tmpdir.open_dir("a/b/c"));
+    assert!(check!(fcntl_getfl(&dir)).contains(OFlags::PATH));
+    let scoped = check!(tmpdir.scope("a/b/c"));
+    assert!(check!(fcntl_getfl(&scoped)).contains(OFlags::PATH));
+
+    let clone = check!(dir.try_clone());
+    assert!(check!(fcntl_getfl(&clone)).contains(OFlags::PATH));
+    assert_eq!(check!(clone.id()), check!(dir.id()));
+}
+
+/// Count the path components resolved, whether by the kernel, which
+/// resolves a whole path in one step, or by the manual resolver.
+#[cfg(feature = "trace_resolution")]
+fn components_resolved(trace: &[ResolutionStep]) -> usize {
+    trace
+        .iter()
+        .map(|step| match step.decision() {
+            ResolutionDecision::Kernel => step.component().components().count(),
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Operations through a scoped `Dir` don't resolve the scope's path again.
+#[cfg(feature = "trace_resolution")]
+#[test]
+fn scope_resolves_less() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/b/c"));
+    for i in 0..10 {
+        check!(tmpdir.write(format!("a/b/c/file{}", i), b"data"));
+    }
+    let options = OpenOptions::new().read(true).clone();
+
+    let mut root_relative = 0;
+    for i in 0..10 {
+        let (result, trace) = tmpdir.open_traced(format!("a/b/c/file{}", i), &options);
+        check!(result);
+        root_relative += components_resolved(&trace);
+    }
+
+    // Resolving the scope itself takes as many steps as it has components.
+    let (result, trace) = tmpdir.open_traced("a/b/c", &options);
+    check!(result);
+    let mut scoped_total = components_resolved(&trace);
+    assert_eq!(scoped_total, 3);
+
+    let scoped = check!(tmpdir.scope("a/b/c"));
+    for i in 0..10 {
+        let (result, trace) = scoped.open_traced(format!("file{}", i), &options);
+        check!(result);
+        scoped_total += components_resolved(&trace);
+    }
+
+    assert_eq!(root_relative, 40);
+    assert_eq!(scoped_total, 13);
+}
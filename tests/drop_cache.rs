@@ -0,0 +1,78 @@
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::OpenOptionsDropCacheExt;
+use cap_std::fs::OpenOptions;
+use std::io::Read;
+use sys_common::io::tmpdir;
+
+#[test]
+fn drop_cache_smoke() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+
+    let file = check!(tmpdir.open("file"));
+    check!(file.drop_cache());
+
+    // The option works, and can be discarded by converting to `std`.
+    let mut file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().read(true).drop_cache_on_close(true)
+    ));
+    let mut contents = String::new();
+    check!(file.read_to_string(&mut contents));
+    assert_eq!(contents, "hello");
+    drop(file.into_std());
+}
+
+/// Return the number of pages of the file at `path` which are in the page
+/// cache, using `fincore`, or `None` if that's unavailable.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn resident_pages(path: &std::path::Path) -> Option<u64> {
+    let output = std::process::Command::new("fincore")
+        .arg("--noheadings")
+        .arg("--output")
+        .arg("PAGES")
+        .arg(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()?.trim().parse().ok()
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn drop_cache_on_close() {
+    use cap_std::{ambient_authority, fs::Dir};
+
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = check!(Dir::open_ambient_dir(tmpdir.path(), ambient_authority()));
+    let path = tmpdir.path().join("large");
+
+    let contents = vec![0x5a_u8; 8 << 20];
+    check!(dir.write("large", &contents));
+    check!(check!(dir.open("large")).sync_all());
+
+    let mut file = check!(dir.open_with(
+        "large",
+        OpenOptions::new().read(true).drop_cache_on_close(true)
+    ));
+    let mut buf = Vec::new();
+    check!(file.read_to_end(&mut buf));
+    assert_eq!(buf.len(), contents.len());
+
+    // This is best-effort: skip if we can't observe the page cache, or if
+    // the filesystem doesn't cache the file in the usual way.
+    match resident_pages(&path) {
+        Some(pages) if pages > 0 => (),
+        _ => {
+            eprintln!("skipping drop_cache_on_close: unable to observe the page cache");
+            return;
+        }
+    }
+
+    drop(file);
+    assert_eq!(resident_pages(&path), Some(0));
+}
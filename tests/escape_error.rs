@@ -0,0 +1,49 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::EscapeError;
+use std::path::Path;
+use sys_common::{io::tmpdir, symlink_supported};
+
+#[test]
+fn escape_error_plain() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("a"));
+
+    let err = tmpdir.open("a/../../x").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+    let escape = EscapeError::from_io_error(&err).unwrap();
+    assert_eq!(escape.symlink(), None);
+    assert_eq!(escape.target(), None);
+    assert!(err
+        .to_string()
+        .contains("a path led outside of the filesystem"));
+}
+
+#[test]
+fn escape_error_symlink() {
+    if !symlink_supported() {
+        return;
+    }
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("a"));
+    #[cfg(not(windows))]
+    check!(tmpdir.symlink("../../..", "a/up"));
+    #[cfg(windows)]
+    check!(tmpdir.symlink_dir("..\\..\\..", "a/up"));
+
+    let err = tmpdir.open("a/up/x").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+    let escape = EscapeError::from_io_error(&err).unwrap();
+
+    // `openat2` reports escapes without saying which symlink was
+    // responsible, so only the manual resolver can name it.
+    if escape.kernel_detected() {
+        assert_eq!(escape.symlink(), None);
+    } else {
+        assert_eq!(escape.symlink(), Some(Path::new("a").join("up").as_path()));
+        assert!(escape.target().is_some());
+        assert!(err.to_string().contains("up"));
+    }
+}
@@ -0,0 +1,27 @@
+#[macro_use]
+mod sys_common;
+
+use sys_common::io::tmpdir;
+
+#[test]
+fn filesystem_type_smoke() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.filesystem_type());
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn filesystem_type_tmpfs() {
+    use cap_std::{ambient_authority, fs::Dir, fs::FsType};
+    use sys_common::tmpfs::Tmpfs;
+
+    let tmpfs = match Tmpfs::mount("filesystem_type_tmpfs", &[]) {
+        Some(tmpfs) => tmpfs,
+        None => return,
+    };
+
+    let dir = check!(Dir::open_ambient_dir(tmpfs.path(), ambient_authority()));
+    let fs_type = check!(dir.filesystem_type());
+    assert_eq!(fs_type, FsType::Tmpfs);
+    assert!(!fs_type.is_network());
+}
@@ -0,0 +1,59 @@
+#[macro_use]
+mod sys_common;
+
+use std::{collections::BTreeMap, ops::ControlFlow};
+use sys_common::io::tmpdir;
+
+#[test]
+fn for_each_entry_counts() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+    for i in 0..10 {
+        check!(tmpdir.write(format!("file{}", i), b""));
+    }
+
+    let mut seen = BTreeMap::new();
+    check!(tmpdir.for_each_entry(|name, file_type| {
+        seen.insert(name.to_str().unwrap().to_owned(), file_type);
+        ControlFlow::Continue(())
+    }));
+
+    assert_eq!(seen.len(), 11);
+    assert!(!seen.contains_key("."));
+    assert!(!seen.contains_key(".."));
+    assert!(seen["file3"].is_file());
+    assert!(seen["dir"].is_dir());
+}
+
+#[test]
+fn for_each_entry_break() {
+    let tmpdir = tmpdir();
+    for i in 0..10 {
+        check!(tmpdir.write(format!("file{}", i), b""));
+    }
+
+    let mut count = 0;
+    check!(tmpdir.for_each_entry(|_name, _file_type| {
+        count += 1;
+        if count == 3 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }));
+    assert_eq!(count, 3);
+
+    // Each call scans from the beginning.
+    let mut count = 0;
+    check!(tmpdir.for_each_entry(|_name, _file_type| {
+        count += 1;
+        ControlFlow::Continue(())
+    }));
+    assert_eq!(count, 10);
+}
+
+#[test]
+fn for_each_entry_empty() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.for_each_entry(|name, _file_type| panic!("unexpected entry {:?}", name)));
+}
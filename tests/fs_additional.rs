@@ -672,6 +672,46 @@ fn dir_unsearchable_unreadable_ambient() {
     }
 }
 
+/// Test renaming and removing files in directories which can be searched
+/// but not read. Parent directories are opened with `O_PATH`, which needs
+/// no access to the directories themselves, so only the permissions that
+/// `renameat` and `unlinkat` need are required.
+#[cfg(any(target_os = "android", target_os = "linux", target_os = "redox"))]
+#[test]
+fn rename_in_unreadable_parents() {
+    use cap_std::fs::Permissions;
+    use std::os::unix::fs::PermissionsExt;
+
+    // Root isn't subject to these permissions, so there'd be nothing to test.
+    //
+    // Safety: `geteuid` has no preconditions.
+    #[allow(unsafe_code)]
+    if unsafe { libc::geteuid() } == 0 {
+        eprintln!("skipping rename_in_unreadable_parents: running as root");
+        return;
+    }
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/b"));
+    check!(tmpdir.write("a/b/file", b"hello"));
+
+    // `a` can only be searched, and `a/b` searched and written.
+    let mode = |mode| Permissions::from_std(std::fs::Permissions::from_mode(mode));
+    check!(tmpdir.set_permissions("a/b", mode(0o300)));
+    check!(tmpdir.set_permissions("a", mode(0o100)));
+
+    check!(tmpdir.rename("a/b/file", &tmpdir, "a/b/renamed"));
+    check!(tmpdir.hard_link("a/b/renamed", &tmpdir, "a/b/linked"));
+    check!(tmpdir.remove_file("a/b/linked"));
+    check!(tmpdir.create_dir("a/b/subdir"));
+    check!(tmpdir.remove_dir("a/b/subdir"));
+
+    check!(tmpdir.set_permissions("a", mode(0o700)));
+    check!(tmpdir.set_permissions("a/b", mode(0o700)));
+    assert_eq!(check!(tmpdir.read("a/b/renamed")), b"hello");
+    assert!(!tmpdir.exists("a/b/file"));
+}
+
 /// This test is the same as `symlink_hard_link` but uses `std::fs`'
 /// ambient API instead of `cap_std`. The purpose of this test is to
 /// confirm fundamentally OS-specific behaviors.
@@ -846,3 +886,29 @@ fn maybe_dir() {
     // Opening directories works on all platforms with `maybe_dir`.
     check!(tmpdir.open_with("dir", OpenOptions::new().read(true).maybe_dir(true)));
 }
+
+#[test]
+fn empty_and_root_paths() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("foo"));
+    check!(tmpdir.write("file", b"hello"));
+
+    // An empty path doesn't name anything.
+    assert_eq!(tmpdir.open("").unwrap_err().kind(), io::ErrorKind::NotFound);
+    assert_eq!(
+        tmpdir.open_dir("").unwrap_err().kind(),
+        io::ErrorKind::NotFound
+    );
+
+    // A lone root refers to the root of the sandbox.
+    let root = check!(tmpdir.open_dir("/"));
+    assert!(root.exists("file"));
+    assert_eq!(check!(root.id()), check!(tmpdir.id()));
+
+    // Other absolute paths are still escape attempts.
+    error_contains!(tmpdir.open("/file"), "a path led outside of the filesystem");
+    error_contains!(
+        tmpdir.open_dir("/foo"),
+        "a path led outside of the filesystem"
+    );
+}
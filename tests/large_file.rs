@@ -0,0 +1,63 @@
+// Windows doesn't create sparse files by default, so writing past 2 GiB
+// there would allocate that much space.
+#![cfg(not(windows))]
+
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use sys_common::io::tmpdir;
+
+/// An offset past what fits in a 32-bit `off_t`.
+const LARGE: u64 = (1 << 31) + (1 << 29);
+
+/// Test a file larger than a 32-bit `off_t`, which needs `O_LARGEFILE` on
+/// 32-bit platforms. It creates a 2.5 GiB sparse file, so elsewhere it only
+/// runs with `--ignored`.
+#[test]
+#[cfg_attr(not(target_pointer_width = "32"), ignore)]
+fn large_sparse_file() {
+    use std::os::unix::fs::MetadataExt;
+
+    let tmpdir = tmpdir();
+
+    let mut file = check!(tmpdir.open_with(
+        "sparse",
+        OpenOptions::new().read(true).write(true).create(true)
+    ));
+
+    // Don't fill up a filesystem which doesn't support sparse files. Probe
+    // with a small hole first.
+    check!(file.set_len(1 << 20));
+    if check!(file.metadata()).blocks() * 512 >= 1 << 20 {
+        eprintln!("large_sparse_file: the filesystem doesn't support sparse files; skipping");
+        return;
+    }
+
+    check!(file.set_len(LARGE + 5));
+    assert_eq!(check!(file.metadata()).len(), LARGE + 5);
+
+    assert_eq!(check!(file.seek(SeekFrom::Start(LARGE))), LARGE);
+    check!(file.write_all(b"hello"));
+    assert_eq!(check!(file.stream_position()), LARGE + 5);
+    drop(file);
+
+    // Reopen the file, which requires 64-bit offsets on 32-bit platforms.
+    let mut file = check!(tmpdir.open("sparse"));
+    assert_eq!(check!(file.seek(SeekFrom::End(-5))), LARGE);
+    let mut buf = [0; 5];
+    check!(file.read_exact(&mut buf));
+    assert_eq!(&buf, b"hello");
+
+    let mut buf = [0; 5];
+    assert_eq!(check!(tmpdir.read_at("sparse", &mut buf, LARGE)), 5);
+    assert_eq!(&buf, b"hello");
+    assert_eq!(check!(tmpdir.write_at("sparse", b"world", LARGE)), 5);
+    assert_eq!(check!(tmpdir.read_at("sparse", &mut buf, LARGE)), 5);
+    assert_eq!(&buf, b"world");
+
+    // The hole reads as zeros.
+    assert_eq!(check!(tmpdir.read_at("sparse", &mut buf, LARGE - 5)), 5);
+    assert_eq!(&buf, &[0; 5]);
+}
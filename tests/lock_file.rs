@@ -0,0 +1,63 @@
+#[macro_use]
+mod sys_common;
+
+use sys_common::io::tmpdir;
+
+#[test]
+fn try_lock_file_held() {
+    let tmpdir = tmpdir();
+    let guard = check!(tmpdir.lock_file("lock"));
+    assert!(tmpdir.exists("lock"));
+
+    assert!(check!(tmpdir.try_lock_file("lock")).is_none());
+
+    drop(guard);
+    assert!(check!(tmpdir.try_lock_file("lock")).is_some());
+}
+
+#[test]
+fn try_lock_file_preserves_contents() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("lock", b"1234"));
+
+    let _guard = check!(tmpdir.try_lock_file("lock")).unwrap();
+    assert_eq!(check!(tmpdir.read("lock")), b"1234");
+}
+
+#[test]
+fn lock_file_remove_on_drop() {
+    let tmpdir = tmpdir();
+    let mut guard = check!(tmpdir.try_lock_file("lock")).unwrap();
+    guard.remove_on_drop(true);
+    assert!(tmpdir.exists("lock"));
+
+    drop(guard);
+    assert!(!tmpdir.exists("lock"));
+}
+
+#[test]
+fn lock_file_across_threads() {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    let tmpdir = tmpdir();
+    let dir = check!(tmpdir.try_clone());
+    let released = Arc::new(AtomicBool::new(false));
+
+    let guard = check!(tmpdir.lock_file("lock"));
+    let thread = {
+        let released = Arc::clone(&released);
+        std::thread::spawn(move || {
+            // This blocks until the main thread drops its guard.
+            let _guard = dir.lock_file("lock").unwrap();
+            assert!(released.load(Ordering::SeqCst));
+        })
+    };
+
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    released.store(true, Ordering::SeqCst);
+    drop(guard);
+    thread.join().unwrap();
+}
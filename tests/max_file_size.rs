@@ -0,0 +1,85 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::OpenOptions;
+use std::io::{self, Seek, SeekFrom, Write};
+use sys_common::io::tmpdir;
+
+fn is_file_too_large(err: &io::Error) -> bool {
+    #[cfg(not(windows))]
+    {
+        posish::io::Errno::from_io_error(err) == Some(posish::io::Errno::FBIG)
+    }
+
+    #[cfg(windows)]
+    {
+        err.raw_os_error() == Some(223)
+    }
+}
+
+#[test]
+fn max_file_size_write_all() {
+    let tmp = tmpdir();
+    let tmpdir = check!(tmp.try_clone()).with_max_file_size(8);
+
+    check!(tmpdir.write("ok", b"12345678"));
+    assert_eq!(check!(tmpdir.read("ok")), b"12345678");
+
+    let err = tmpdir.write("big", b"123456789").unwrap_err();
+    assert!(is_file_too_large(&err));
+    assert_eq!(check!(tmpdir.read("big")), b"");
+}
+
+#[test]
+fn max_file_size_write() {
+    let tmp = tmpdir();
+    let tmpdir = check!(tmp.try_clone()).with_max_file_size(8);
+
+    let mut file = check!(tmpdir.create("file"));
+    check!(file.write_all(b"12345"));
+
+    // `write` writes as much as fits, and then fails.
+    assert_eq!(check!(file.write(b"6789")), 3);
+    assert!(is_file_too_large(&file.write(b"9").unwrap_err()));
+    assert_eq!(check!(tmpdir.read("file")), b"12345678");
+
+    // Overwriting within the limit is fine.
+    check!(file.seek(SeekFrom::Start(0)));
+    check!(file.write_all(b"abcdefgh"));
+    assert_eq!(check!(tmpdir.read("file")), b"abcdefgh");
+
+    // So is shrinking, but not growing past the limit.
+    check!(file.set_len(4));
+    assert!(is_file_too_large(&file.set_len(9).unwrap_err()));
+}
+
+#[test]
+fn max_file_size_append() {
+    let tmp = tmpdir();
+    check!(tmp.write("file", b"123456"));
+    let tmpdir = check!(tmp.try_clone()).with_max_file_size(8);
+
+    let mut file = check!(tmpdir.open_with("file", OpenOptions::new().append(true)));
+    check!(file.write_all(b"78"));
+    assert!(is_file_too_large(&file.write_all(b"9").unwrap_err()));
+    assert_eq!(check!(tmpdir.read("file")), b"12345678");
+}
+
+#[test]
+fn max_file_size_inherited() {
+    let tmp = tmpdir();
+    check!(tmp.create_dir("sub"));
+    let tmpdir = check!(tmp.try_clone()).with_max_file_size(4);
+
+    let sub = check!(tmpdir.open_dir("sub"));
+    assert!(is_file_too_large(&sub.write("file", b"12345").unwrap_err()));
+
+    for entry in check!(tmpdir.entries()) {
+        let entry = check!(entry);
+        let sub = check!(entry.open_dir());
+        assert!(is_file_too_large(&sub.write("file", b"12345").unwrap_err()));
+    }
+
+    // Other `Dir`s for the same directory aren't limited.
+    check!(tmp.write("sub/file", b"12345"));
+}
@@ -0,0 +1,58 @@
+// This file contains tests for `cap_fs_ext::MetadataExt::is_compressed` and
+// `cap_fs_ext::MetadataExt::is_encrypted`.
+
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::MetadataExt;
+use sys_common::io::tmpdir;
+
+#[test]
+fn plain_file_attributes() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello, world"));
+
+    let metadata = check!(tmpdir.metadata("file"));
+    assert!(!metadata.is_compressed());
+    assert!(!metadata.is_encrypted());
+}
+
+#[cfg(windows)]
+#[test]
+fn compressed_file() {
+    use cap_std::fs::OpenOptions;
+    use std::{os::windows::io::AsRawHandle, ptr};
+    use winapi::um::{
+        ioapiset::DeviceIoControl, winioctl::FSCTL_SET_COMPRESSION,
+        winnt::COMPRESSION_FORMAT_DEFAULT,
+    };
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello, world"));
+    let file = check!(tmpdir.open_with("file", OpenOptions::new().read(true).write(true)));
+
+    let mut format = COMPRESSION_FORMAT_DEFAULT;
+    let mut returned = 0;
+    let ok = unsafe {
+        DeviceIoControl(
+            file.as_raw_handle() as _,
+            FSCTL_SET_COMPRESSION,
+            &mut format as *mut _ as *mut _,
+            std::mem::size_of_val(&format) as u32,
+            ptr::null_mut(),
+            0,
+            &mut returned,
+            ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        // Only some filesystems, such as NTFS, support compression.
+        eprintln!("skipping compressed_file: unable to compress the file");
+        return;
+    }
+
+    let metadata = check!(tmpdir.metadata("file"));
+    assert!(metadata.is_compressed());
+    assert!(!metadata.is_encrypted());
+    assert!(check!(file.metadata()).is_compressed());
+}
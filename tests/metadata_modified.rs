@@ -0,0 +1,71 @@
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::DirExt;
+use cap_std::time::{Duration, SystemClock};
+use sys_common::io::tmpdir;
+
+#[test]
+fn unmodified() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+
+    let before = check!(tmpdir.metadata("file"));
+    let after = check!(tmpdir.metadata("file"));
+    assert!(!after.is_modified_since(&before));
+    assert_eq!(after.fingerprint(), before.fingerprint());
+}
+
+#[test]
+fn modified_size() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+    let before = check!(tmpdir.metadata("file"));
+
+    // Pin the modification time, so that only the size differs, as it may
+    // on filesystems with coarse timestamps.
+    check!(tmpdir.write("file", b"hello, world"));
+    check!(tmpdir.set_times("file", None, Some(check!(before.modified()).into())));
+
+    let after = check!(tmpdir.metadata("file"));
+    assert!(after.is_modified_since(&before));
+    assert_ne!(after.fingerprint(), before.fingerprint());
+}
+
+#[test]
+fn modified_mtime() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+    check!(tmpdir.set_times("file", None, Some(SystemClock::UNIX_EPOCH.into())));
+    let before = check!(tmpdir.metadata("file"));
+
+    // Same size, different modification time.
+    check!(tmpdir.write("file", b"world"));
+    check!(tmpdir.set_times(
+        "file",
+        None,
+        Some((SystemClock::UNIX_EPOCH + Duration::from_secs(1)).into())
+    ));
+
+    let after = check!(tmpdir.metadata("file"));
+    assert!(after.is_modified_since(&before));
+    assert_ne!(after.fingerprint(), before.fingerprint());
+}
+
+#[cfg(not(windows))]
+#[test]
+fn modified_replaced() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+    check!(tmpdir.set_times("file", None, Some(SystemClock::UNIX_EPOCH.into())));
+    let before = check!(tmpdir.metadata("file"));
+
+    // A different file with the same size and modification time.
+    check!(tmpdir.write("other", b"world"));
+    check!(tmpdir.set_times("other", None, Some(SystemClock::UNIX_EPOCH.into())));
+    check!(tmpdir.rename("other", &tmpdir, "file"));
+
+    let after = check!(tmpdir.metadata("file"));
+    assert!(after.is_modified_since(&before));
+    assert_ne!(after.fingerprint(), before.fingerprint());
+}
@@ -0,0 +1,60 @@
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::OpenOptionsMissingComponentExt;
+use cap_std::fs::{MissingComponentError, OpenOptions, SymlinkTargetType};
+use std::{io, path::Path};
+use sys_common::{io::tmpdir, symlink_supported};
+
+fn missing(dir: &cap_std::fs::Dir, path: &str) -> (std::path::PathBuf, bool) {
+    let err = dir
+        .open_with(
+            path,
+            OpenOptions::new().read(true).report_missing_component(true),
+        )
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    let missing = MissingComponentError::from_io_error(&err).unwrap();
+    assert!(err.to_string().contains("doesn't exist"));
+    (missing.component().to_path_buf(), missing.is_parent())
+}
+
+#[test]
+fn missing_parent_vs_leaf() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("a"));
+
+    assert_eq!(missing(&tmpdir, "a/b/c"), (Path::new("a/b").into(), true));
+    assert_eq!(missing(&tmpdir, "a/c"), (Path::new("a/c").into(), false));
+    assert_eq!(missing(&tmpdir, "b"), (Path::new("b").into(), false));
+    assert_eq!(missing(&tmpdir, "b/c/d"), (Path::new("b").into(), true));
+}
+
+#[test]
+fn missing_through_symlink() {
+    if !symlink_supported() {
+        return;
+    }
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("a"));
+    check!(tmpdir.symlink_with_type("a", "link", SymlinkTargetType::Dir));
+
+    // Components are reported with symlinks resolved.
+    assert_eq!(
+        missing(&tmpdir, "link/x/y"),
+        (Path::new("a/x").into(), true)
+    );
+    assert_eq!(missing(&tmpdir, "link/x"), (Path::new("a/x").into(), false));
+}
+
+#[test]
+fn missing_component_not_requested() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("a"));
+
+    let err = tmpdir.open("a/b/c").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(MissingComponentError::from_io_error(&err).is_none());
+    assert!(err.raw_os_error().is_some());
+}
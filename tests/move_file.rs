@@ -0,0 +1,151 @@
+#[macro_use]
+mod sys_common;
+
+use sys_common::io::tmpdir;
+
+#[test]
+fn move_file_same_filesystem() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dst"));
+    check!(tmpdir.write("src", b"hello, world"));
+    let dst = check!(tmpdir.open_dir("dst"));
+
+    check!(tmpdir.move_file("src", &dst, "moved"));
+
+    assert!(!tmpdir.exists("src"));
+    assert_eq!(check!(dst.read("moved")), b"hello, world");
+    assert_eq!(check!(dst.entries()).count(), 1);
+}
+
+#[test]
+fn move_file_replaces_destination() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("src", b"new"));
+    check!(tmpdir.write("dst", b"old contents"));
+
+    check!(tmpdir.move_file("src", &tmpdir, "dst"));
+
+    assert!(!tmpdir.exists("src"));
+    assert_eq!(check!(tmpdir.read("dst")), b"new");
+}
+
+#[test]
+fn move_file_missing_source() {
+    let tmpdir = tmpdir();
+    assert_eq!(
+        tmpdir.move_file("src", &tmpdir, "dst").unwrap_err().kind(),
+        std::io::ErrorKind::NotFound
+    );
+    assert!(!tmpdir.exists("dst"));
+}
+
+/// Use `/dev/shm`, which is commonly a separate tmpfs mount, as the
+/// destination. If it's unavailable, or on the same filesystem as the
+/// temporary directory, skip the test.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn move_file_cross_filesystem() {
+    use cap_fs_ext::MetadataExt;
+    use cap_std::{ambient_authority, fs::Dir};
+
+    let tmpdir = tmpdir();
+    let shm = match Dir::open_ambient_dir("/dev/shm", ambient_authority()) {
+        Ok(shm) => shm,
+        Err(_) => return,
+    };
+    let other = match cap_tempfile::TempDir::new_in(&shm) {
+        Ok(other) => other,
+        Err(_) => return,
+    };
+    if check!(other.dir_metadata()).dev() == check!(tmpdir.dir_metadata()).dev() {
+        return;
+    }
+
+    let contents = vec![0xa5_u8; 1 << 20];
+    check!(tmpdir.write("src", &contents));
+    check!(other.write("dst", b"old contents"));
+
+    check!(tmpdir.move_file("src", &other, "dst"));
+
+    assert!(!tmpdir.exists("src"));
+    assert_eq!(check!(other.read("dst")), contents);
+
+    // The temporary file has been renamed into place.
+    assert_eq!(check!(other.entries()).count(), 1);
+}
+
+#[test]
+fn rename_or_move_cross_dir() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/sub"));
+    check!(tmpdir.create_dir("b"));
+    check!(tmpdir.write("a/file", b"hello, world"));
+    let a = check!(tmpdir.open_dir("a"));
+    let b = check!(tmpdir.open_dir("b"));
+
+    check!(a.rename_or_move("file", &b, "file"));
+    check!(a.rename_or_move("sub", &b, "sub"));
+
+    assert!(!a.exists("file"));
+    assert!(!a.exists("sub"));
+    assert_eq!(check!(b.read("file")), b"hello, world");
+    assert!(check!(b.metadata("sub")).is_dir());
+}
+
+#[test]
+fn rename_or_move_sandboxed() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("a"));
+    check!(tmpdir.create_dir("b"));
+    check!(tmpdir.write("a/file", b"hello, world"));
+    check!(tmpdir.write("outside", b"outside"));
+    let a = check!(tmpdir.open_dir("a"));
+    let b = check!(tmpdir.open_dir("b"));
+
+    // Each path is resolved within its own `Dir`.
+    assert!(a.rename_or_move("file", &b, "../escaped").is_err());
+    assert!(a.rename_or_move("../outside", &b, "file").is_err());
+
+    assert!(a.exists("file"));
+    assert!(tmpdir.exists("outside"));
+    assert!(!tmpdir.exists("escaped"));
+    assert!(!b.exists("file"));
+}
+
+/// As in `move_file_cross_filesystem`, use `/dev/shm` as the destination,
+/// skipping the test if it isn't a separate filesystem.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn rename_or_move_cross_filesystem() {
+    use cap_fs_ext::MetadataExt;
+    use cap_std::{ambient_authority, fs::Dir};
+
+    let tmpdir = tmpdir();
+    let shm = match Dir::open_ambient_dir("/dev/shm", ambient_authority()) {
+        Ok(shm) => shm,
+        Err(_) => return,
+    };
+    let other = match cap_tempfile::TempDir::new_in(&shm) {
+        Ok(other) => other,
+        Err(_) => return,
+    };
+    if check!(other.dir_metadata()).dev() == check!(tmpdir.dir_metadata()).dev() {
+        return;
+    }
+
+    check!(tmpdir.write("file", b"hello, world"));
+    check!(tmpdir.rename_or_move("file", &other, "file"));
+    assert!(!tmpdir.exists("file"));
+    assert_eq!(check!(other.read("file")), b"hello, world");
+
+    // A plain rename reports the cross-device error.
+    check!(tmpdir.write("file", b"hello, world"));
+    let err = tmpdir.rename("file", &other, "renamed").unwrap_err();
+    assert!(posish::io::Errno::from_io_error(&err) == Some(posish::io::Errno::XDEV));
+
+    // Directories can't be moved by copying.
+    check!(tmpdir.create_dir("dir"));
+    assert!(tmpdir.rename_or_move("dir", &other, "dir").is_err());
+    assert!(tmpdir.exists("dir"));
+    assert!(!other.exists("dir"));
+}
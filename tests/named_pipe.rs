@@ -0,0 +1,130 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{OpenOptions, PipeOptions};
+use std::io::{self, Read, Write};
+
+#[cfg(unix)]
+#[test]
+fn named_pipe_round_trip() {
+    use std::os::unix::fs::FileTypeExt;
+    use sys_common::io::tmpdir;
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+
+    let mut server = check!(tmpdir.create_named_pipe("sub/fifo", PipeOptions::new().read(true)));
+    assert!(check!(tmpdir.metadata("sub/fifo")).file_type().is_fifo());
+
+    let mut client = check!(tmpdir.open_with("sub/fifo", OpenOptions::new().write(true)));
+    check!(client.write_all(b"hello, pipe"));
+    drop(client);
+
+    let mut message = String::new();
+    check!(server.read_to_string(&mut message));
+    assert_eq!(message, "hello, pipe");
+}
+
+#[cfg(unix)]
+#[test]
+fn named_pipe_default_mode() {
+    use std::os::unix::fs::PermissionsExt;
+    use sys_common::io::tmpdir;
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    let sub = check!(tmpdir.open_dir("sub")).with_default_file_mode(0o600);
+
+    let _server = check!(sub.create_named_pipe("fifo", PipeOptions::new().read(true)));
+    let mode = check!(sub.metadata("fifo")).permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[cfg(unix)]
+#[test]
+fn named_pipe_errors() {
+    use cap_std::fs::EscapeError;
+    use sys_common::io::tmpdir;
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b""));
+
+    let err = tmpdir
+        .create_named_pipe("file", PipeOptions::new().read(true))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+
+    let err = tmpdir
+        .create_named_pipe("../fifo", PipeOptions::new().read(true))
+        .unwrap_err();
+    assert!(EscapeError::from_io_error(&err).is_some());
+
+    let err = tmpdir
+        .create_named_pipe("fifo", &PipeOptions::new())
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    let err = tmpdir
+        .create_named_pipe("fifo", PipeOptions::new().read(true).max_instances(2))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+
+    // Nothing is left behind by the failures.
+    assert!(!tmpdir.exists("fifo"));
+}
+
+#[cfg(windows)]
+#[test]
+fn named_pipe_round_trip() {
+    use cap_std::{ambient_authority, fs::Dir};
+
+    let pipes = check!(Dir::open_ambient_dir(r"\\.\pipe\", ambient_authority()));
+    let name = format!("cap-std-test-{}", std::process::id());
+
+    let mut server = check!(pipes.create_named_pipe(&name, PipeOptions::new().read(true)));
+
+    let mut client = check!(pipes.open_with(&name, OpenOptions::new().write(true)));
+    check!(client.write_all(b"hello, pipe"));
+    drop(client);
+
+    // The server end reports `ERROR_BROKEN_PIPE` once the client is gone.
+    let mut buf = [0; 11];
+    check!(server.read_exact(&mut buf));
+    assert_eq!(&buf, b"hello, pipe");
+    assert!(server.read(&mut buf).is_err());
+}
+
+#[cfg(windows)]
+#[test]
+fn named_pipe_instances() {
+    use cap_std::{ambient_authority, fs::Dir};
+
+    let pipes = check!(Dir::open_ambient_dir(r"\\.\pipe\", ambient_authority()));
+    let name = format!("cap-std-test-instances-{}", std::process::id());
+
+    // With the default of one instance, a second one is refused.
+    let _first = check!(pipes.create_named_pipe(&name, PipeOptions::new().write(true)));
+    let err = pipes
+        .create_named_pipe(&name, PipeOptions::new().write(true))
+        .unwrap_err();
+    assert_eq!(
+        err.raw_os_error(),
+        Some(winapi::shared::winerror::ERROR_PIPE_BUSY as i32)
+    );
+
+    // Allowing more instances permits them, up to the limit.
+    let name = format!("cap-std-test-instances-two-{}", std::process::id());
+    let options = PipeOptions::new().write(true).max_instances(2).clone();
+    let _first = check!(pipes.create_named_pipe(&name, &options));
+    let _second = check!(pipes.create_named_pipe(&name, &options));
+    let err = pipes.create_named_pipe(&name, &options).unwrap_err();
+    assert_eq!(
+        err.raw_os_error(),
+        Some(winapi::shared::winerror::ERROR_PIPE_BUSY as i32)
+    );
+
+    let err = pipes
+        .create_named_pipe(&name, PipeOptions::new().write(true).max_instances(0))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,101 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::OpenOptions;
+use std::io::{self, Write};
+use sys_common::io::tmpdir;
+
+#[test]
+fn no_create_modify_existing() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+    let dir = check!(tmpdir.try_clone()).with_no_create();
+
+    let mut file = check!(dir.open_with("file", OpenOptions::new().write(true).truncate(true)));
+    check!(file.write_all(b"world"));
+    drop(file);
+
+    assert_eq!(check!(dir.read("file")), b"world");
+}
+
+#[test]
+fn no_create_rejects_create() {
+    let tmpdir = tmpdir();
+    let dir = check!(tmpdir.try_clone()).with_no_create();
+
+    let err = dir
+        .open_with("missing", OpenOptions::new().write(true).create(true))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    assert_eq!(
+        dir.write("missing", b"").unwrap_err().kind(),
+        io::ErrorKind::PermissionDenied
+    );
+    assert!(!tmpdir.exists("missing"));
+
+    // The restriction carries over to subdirectories.
+    check!(tmpdir.create_dir("sub"));
+    let sub = check!(dir.open_dir("sub"));
+    assert_eq!(
+        sub.create("missing").unwrap_err().kind(),
+        io::ErrorKind::PermissionDenied
+    );
+    assert!(!tmpdir.exists("sub/missing"));
+}
+
+#[test]
+fn no_create_rejects_create_new() {
+    let tmpdir = tmpdir();
+    let dir = check!(tmpdir.try_clone()).with_no_create();
+
+    let err = dir
+        .open_with("missing", OpenOptions::new().write(true).create_new(true))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    assert!(!tmpdir.exists("missing"));
+}
+
+#[test]
+fn no_create_through_read_dir() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    check!(tmpdir.write("file", b"hello"));
+    let dir = check!(tmpdir.try_clone()).with_no_create();
+
+    for entry in check!(dir.entries()) {
+        let entry = check!(entry);
+        if entry.file_name() == "sub" {
+            // The restriction carries over to directories opened from entries.
+            let sub = check!(entry.open_dir());
+            assert_eq!(
+                sub.create("missing").unwrap_err().kind(),
+                io::ErrorKind::PermissionDenied
+            );
+        } else {
+            // The restriction applies to opening entries too.
+            let err = entry
+                .open_with(OpenOptions::new().write(true).create(true))
+                .unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+            let mut file = check!(entry.open_with(OpenOptions::new().write(true).truncate(true)));
+            check!(file.write_all(b"world"));
+        }
+    }
+
+    assert!(!tmpdir.exists("sub/missing"));
+    assert_eq!(check!(tmpdir.read("file")), b"world");
+}
+
+#[test]
+fn read_dir_open_with_create() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+
+    // Without `with_no_create`, entries may be opened with `create`.
+    let entry = check!(check!(tmpdir.entries()).next().unwrap());
+    let mut file = check!(entry.open_with(OpenOptions::new().append(true).create(true)));
+    check!(file.write_all(b", world"));
+    drop(file);
+
+    assert_eq!(check!(tmpdir.read("file")), b"hello, world");
+}
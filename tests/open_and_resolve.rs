@@ -0,0 +1,82 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{Dir, OpenOptions};
+use std::{io::Read, path::Path};
+use sys_common::{io::tmpdir, symlink_supported};
+
+#[cfg(not(windows))]
+fn symlink_file(dir: &Dir, src: &str, dst: &str) {
+    check!(dir.symlink(src, dst));
+}
+
+#[cfg(windows)]
+fn symlink_file(dir: &Dir, src: &str, dst: &str) {
+    check!(dir.symlink_file(src, dst));
+}
+
+fn read(dir: &Dir, path: &str) -> (String, std::path::PathBuf) {
+    let (mut file, resolved) = check!(dir.open_and_resolve(path, OpenOptions::new().read(true)));
+    let mut contents = String::new();
+    check!(file.read_to_string(&mut contents));
+    (contents, resolved)
+}
+
+#[test]
+fn open_and_resolve_plain() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/b"));
+    check!(tmpdir.write("a/b/file", "hello"));
+
+    assert_eq!(
+        read(&tmpdir, "a/./b/../b/file"),
+        ("hello".to_owned(), Path::new("a/b/file").to_path_buf())
+    );
+
+    let (_, resolved) = check!(tmpdir.open_and_resolve(".", OpenOptions::new().read(true)));
+    assert_eq!(resolved, Path::new("."));
+}
+
+#[test]
+fn open_and_resolve_symlink() {
+    if !symlink_supported() {
+        return;
+    }
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/b"));
+    check!(tmpdir.create_dir("c"));
+    check!(tmpdir.write("a/b/file", "hello"));
+    symlink_file(&tmpdir, "a/b/file", "link");
+    symlink_file(&tmpdir, "../a/b/file", "c/link");
+    symlink_file(&tmpdir, "../link", "c/link_to_link");
+
+    let expected = ("hello".to_owned(), Path::new("a/b/file").to_path_buf());
+    assert_eq!(read(&tmpdir, "link"), expected);
+    assert_eq!(read(&tmpdir, "c/link"), expected);
+    assert_eq!(read(&tmpdir, "c/link_to_link"), expected);
+}
+
+#[test]
+fn open_and_resolve_create() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("a"));
+
+    let (_, resolved) = check!(tmpdir.open_and_resolve(
+        "a/../a/new",
+        OpenOptions::new().write(true).create_new(true)
+    ));
+    assert_eq!(resolved, Path::new("a/new"));
+    assert!(tmpdir.exists("a/new"));
+}
+
+#[test]
+fn open_and_resolve_escape() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("a"));
+
+    error_contains!(
+        tmpdir.open_and_resolve("a/../..", OpenOptions::new().read(true)),
+        "a path led outside of the filesystem"
+    );
+}
@@ -0,0 +1,69 @@
+#![cfg(target_os = "linux")]
+
+#[macro_use]
+mod sys_common;
+
+use cap_std::{
+    ambient_authority,
+    fs::{Dir, OpenOptions},
+};
+use std::{
+    io,
+    path::Path,
+    process::{Child, Command},
+    thread,
+    time::Duration,
+};
+
+/// Copy `sleep` into `dir` and run it for `seconds`.
+fn run_sleep_copy(dir: &Path, seconds: &str) -> Child {
+    let exe = dir.join("sleep");
+    check!(std::fs::copy("/bin/sleep", &exe));
+
+    // Other tests forking concurrently can briefly hold the copy open for
+    // writing, which makes the exec itself fail with `ETXTBSY`.
+    loop {
+        match Command::new(&exe).arg(seconds).spawn() {
+            Err(err)
+                if posish::io::Errno::from_io_error(&err) == Some(posish::io::Errno::TXTBSY) =>
+            {
+                thread::sleep(Duration::from_millis(10))
+            }
+            result => return check!(result),
+        }
+    }
+}
+
+#[test]
+fn busy_retry_succeeds_after_exit() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = check!(Dir::open_ambient_dir(tmp.path(), ambient_authority()));
+    let mut child = run_sleep_copy(tmp.path(), "0.2");
+    let options = OpenOptions::new().write(true).truncate(true).clone();
+
+    // Reap the child once it exits, so that the executable is released.
+    let waiter = thread::spawn(move || child.wait());
+
+    let file = check!(dir.open_with_busy_retry("sleep", &options, 10));
+    drop(file);
+    check!(waiter.join().unwrap());
+}
+
+#[test]
+fn busy_retry_fails_cleanly() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = check!(Dir::open_ambient_dir(tmp.path(), ambient_authority()));
+    let mut child = run_sleep_copy(tmp.path(), "10");
+    let options = OpenOptions::new().write(true).clone();
+
+    let without_retry = dir.open_with("sleep", &options);
+    let with_retries = dir.open_with_busy_retry("sleep", &options, 2);
+
+    check!(child.kill());
+    check!(child.wait());
+
+    let err = without_retry.unwrap_err();
+    assert!(posish::io::Errno::from_io_error(&err) == Some(posish::io::Errno::TXTBSY));
+    let err: io::Error = with_retries.unwrap_err();
+    assert!(posish::io::Errno::from_io_error(&err) == Some(posish::io::Errno::TXTBSY));
+}
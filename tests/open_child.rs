@@ -0,0 +1,63 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{EscapeError, OpenOptions, SymlinkTargetType};
+use std::io::{self, Read};
+use sys_common::{io::tmpdir, symlink_supported};
+
+#[test]
+fn open_child_basic() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+
+    let mut file = check!(tmpdir.open_child("file", OpenOptions::new().read(true)));
+    let mut contents = String::new();
+    check!(file.read_to_string(&mut contents));
+    assert_eq!(contents, "hello");
+
+    check!(tmpdir.open_child("new", OpenOptions::new().write(true).create_new(true)));
+    assert!(tmpdir.is_file("new"));
+
+    let err = tmpdir
+        .open_child("missing", OpenOptions::new().read(true))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn open_child_rejects_paths() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("a"));
+    check!(tmpdir.write("a/b", b""));
+
+    for name in &["a/b", "..", ".", "", "/", "/a", "a/", "./a"] {
+        let err = tmpdir
+            .open_child(name, OpenOptions::new().read(true))
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{:?}", name);
+    }
+}
+
+#[test]
+fn open_child_symlinks() {
+    if !symlink_supported() {
+        return;
+    }
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+    check!(tmpdir.symlink_with_type("file", "link", SymlinkTargetType::File));
+    check!(tmpdir.symlink_with_type("../outside", "escape", SymlinkTargetType::File));
+
+    // Symlinks within the directory are followed as usual.
+    let mut file = check!(tmpdir.open_child("link", OpenOptions::new().read(true)));
+    let mut contents = String::new();
+    check!(file.read_to_string(&mut contents));
+    assert_eq!(contents, "hello");
+
+    // Symlinks leading outside of the directory are still rejected.
+    let err = tmpdir
+        .open_child("escape", OpenOptions::new().read(true))
+        .unwrap_err();
+    assert!(EscapeError::from_io_error(&err).is_some());
+}
@@ -0,0 +1,92 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::OpenOptions;
+use std::io::{self, Read};
+use sys_common::io::tmpdir;
+
+#[test]
+fn open_if_size() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("small", b"hi"));
+    check!(tmpdir.write("large", [0_u8; 4096]));
+
+    let small_enough = |m: &cap_std::fs::Metadata| m.len() <= 1024;
+
+    let mut file = check!(tmpdir.open_if("small", OpenOptions::new().read(true), small_enough))
+        .expect("file is under the threshold");
+    let mut contents = String::new();
+    check!(file.read_to_string(&mut contents));
+    assert_eq!(contents, "hi");
+
+    assert!(check!(tmpdir.open_if("large", OpenOptions::new().read(true), small_enough)).is_none());
+
+    let err = tmpdir
+        .open_if("missing", OpenOptions::new().read(true), small_enough)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn open_if_type() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+    check!(tmpdir.write("file", b""));
+
+    let is_file = |m: &cap_std::fs::Metadata| m.is_file();
+    assert!(check!(tmpdir.open_if("file", OpenOptions::new().read(true), is_file)).is_some());
+
+    // Windows can't open directories as files.
+    #[cfg(not(windows))]
+    assert!(check!(tmpdir.open_if("dir", OpenOptions::new().read(true), is_file)).is_none());
+}
+
+#[test]
+fn open_if_truncate() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+
+    let options = OpenOptions::new().write(true).truncate(true).clone();
+
+    // A file which doesn't qualify isn't truncated.
+    assert!(check!(tmpdir.open_if("file", &options, |_| false)).is_none());
+    assert_eq!(check!(tmpdir.read("file")), b"hello");
+
+    assert!(check!(tmpdir.open_if("file", &options, |_| true)).is_some());
+    assert_eq!(check!(tmpdir.read("file")), b"");
+
+    let err = tmpdir
+        .open_if("new", OpenOptions::new().write(true).create(true), |_| true)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(!tmpdir.exists("new"));
+}
+
+/// On Linux, files are checked before they're opened for real, so rejecting
+/// a FIFO doesn't wait for a writer, and the file needn't be readable.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn open_if_checks_before_opening() {
+    use cap_std::fs::{Permissions, PipeOptions};
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    let tmpdir = tmpdir();
+    drop(check!(
+        tmpdir.create_named_pipe("fifo", PipeOptions::new().read(true))
+    ));
+    let is_file = |m: &cap_std::fs::Metadata| m.is_file();
+    assert!(check!(tmpdir.open_if("fifo", OpenOptions::new().read(true), is_file)).is_none());
+
+    let mut called = false;
+    let is_fifo = |m: &cap_std::fs::Metadata| {
+        called = true;
+        m.file_type().is_fifo()
+    };
+    check!(tmpdir.write("file", b"hello"));
+    check!(tmpdir.set_permissions(
+        "file",
+        Permissions::from_std(std::fs::Permissions::from_mode(0o000))
+    ));
+    assert!(check!(tmpdir.open_if("file", OpenOptions::new().read(true), is_fifo)).is_none());
+    assert!(called);
+}
@@ -0,0 +1,28 @@
+#[macro_use]
+mod sys_common;
+
+use std::io::Read;
+use sys_common::io::tmpdir;
+
+#[test]
+fn open_sequential_read() {
+    let tmpdir = tmpdir();
+    let contents = (0..1 << 20).map(|i| i as u8).collect::<Vec<u8>>();
+    check!(tmpdir.write("file", &contents));
+
+    let mut file = check!(tmpdir.open_sequential("file"));
+    let mut buf = Vec::new();
+    check!(file.read_to_end(&mut buf));
+    assert_eq!(buf, contents);
+}
+
+#[test]
+fn open_sequential_is_read_only() {
+    use std::io::Write;
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+
+    let mut file = check!(tmpdir.open_sequential("file"));
+    assert!(file.write_all(b"world").is_err());
+}
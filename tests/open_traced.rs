@@ -0,0 +1,96 @@
+#![cfg(feature = "trace_resolution")]
+
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{
+    DanglingSymlinkError, EscapeError, OpenOptions, ResolutionDecision, SymlinkTargetType,
+};
+use std::{io::Read, path::Path};
+use sys_common::{io::tmpdir, symlink_supported};
+
+/// Return `true` if the trace is a single step from the kernel resolving the
+/// whole path, in which case there are no individual steps to check.
+fn kernel_resolved(trace: &[cap_std::fs::ResolutionStep], path: &str) -> bool {
+    match trace {
+        [step] if step.decision() == ResolutionDecision::Kernel => {
+            assert_eq!(step.component(), Path::new(path));
+            assert!(!step.is_symlink());
+            true
+        }
+        _ => false,
+    }
+}
+
+#[test]
+fn open_traced_through_symlink() {
+    if !symlink_supported() {
+        return;
+    }
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/b"));
+    check!(tmpdir.write("a/b/file", b"hello"));
+    check!(tmpdir.symlink_with_type("b", "a/link", SymlinkTargetType::Dir));
+
+    let (result, trace) = tmpdir.open_traced("a/link/file", OpenOptions::new().read(true));
+    let mut file = check!(result);
+    let mut contents = String::new();
+    check!(file.read_to_string(&mut contents));
+    assert_eq!(contents, "hello");
+
+    if kernel_resolved(&trace, "a/link/file") {
+        return;
+    }
+    let symlink = trace
+        .iter()
+        .find(|step| step.is_symlink())
+        .expect("the symlink step should be recorded");
+    assert_eq!(symlink.component(), Path::new("link"));
+    assert_eq!(symlink.symlink_target(), Some(Path::new("b")));
+    assert_eq!(symlink.decision(), ResolutionDecision::Follow);
+    assert_eq!(
+        trace.last().unwrap().component(),
+        Path::new("file"),
+        "{:?}",
+        trace
+    );
+}
+
+#[test]
+fn open_traced_escape() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("a"));
+
+    let (result, trace) = tmpdir.open_traced("a/../..", OpenOptions::new().read(true));
+    let err = result.unwrap_err();
+    assert!(EscapeError::from_io_error(&err).is_some());
+
+    if kernel_resolved(&trace, "a/../..") {
+        return;
+    }
+    let last = trace.last().unwrap();
+    assert_eq!(last.component(), Path::new(".."));
+    assert_eq!(last.decision(), ResolutionDecision::Reject);
+}
+
+#[test]
+fn open_traced_dangling_symlink() {
+    if !symlink_supported() {
+        return;
+    }
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.symlink_with_type("missing", "dangling", SymlinkTargetType::File));
+
+    let (result, trace) = tmpdir.open_traced("dangling", OpenOptions::new().read(true));
+    let err = result.unwrap_err();
+    assert!(DanglingSymlinkError::from_io_error(&err).is_some());
+
+    if kernel_resolved(&trace, "dangling") {
+        return;
+    }
+    let symlink = &trace[0];
+    assert_eq!(symlink.component(), Path::new("dangling"));
+    assert_eq!(symlink.symlink_target(), Some(Path::new("missing")));
+}
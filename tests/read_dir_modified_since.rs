@@ -0,0 +1,44 @@
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::DirExt;
+use cap_std::time::{Duration, SystemClock};
+use std::collections::BTreeSet;
+use sys_common::io::tmpdir;
+
+#[test]
+fn read_dir_modified_since() {
+    let tmpdir = tmpdir();
+    let old = SystemClock::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    let since = old + Duration::from_secs(60);
+    let new = since + Duration::from_secs(60);
+
+    for name in &["a", "b", "c", "d"] {
+        check!(tmpdir.write(name, b"contents"));
+        check!(tmpdir.set_times(name, None, Some(old.into())));
+    }
+    check!(tmpdir.create_dir("dir"));
+    check!(tmpdir.set_times("dir", None, Some(new.into())));
+
+    // Touch some of the files.
+    check!(tmpdir.set_times("b", None, Some(new.into())));
+    check!(tmpdir.set_times("d", None, Some(new.into())));
+
+    let names = |entries: cap_std::fs::ReadDirModifiedSince| {
+        entries
+            .map(|entry| check!(entry).file_name().into_string().unwrap())
+            .collect::<BTreeSet<_>>()
+    };
+
+    let modified = names(check!(tmpdir.read_dir_modified_since(since)));
+    assert_eq!(
+        modified,
+        ["b", "d", "dir"].iter().map(|s| s.to_string()).collect()
+    );
+
+    let modified = names(check!(tmpdir.read_dir_modified_since(since)).skip_dirs());
+    assert_eq!(modified, ["b", "d"].iter().map(|s| s.to_string()).collect());
+
+    // Nothing was modified after the latest time.
+    assert_eq!(check!(tmpdir.read_dir_modified_since(new)).count(), 0);
+}
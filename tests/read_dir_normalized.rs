@@ -0,0 +1,56 @@
+#![cfg(feature = "normalize_names")]
+
+#[macro_use]
+mod sys_common;
+
+use std::ffi::OsString;
+use sys_common::io::tmpdir;
+
+const COMPOSED: &str = "caf\u{e9}";
+const DECOMPOSED: &str = "cafe\u{301}";
+
+fn names(dir: &cap_std::fs::Dir) -> Vec<OsString> {
+    check!(dir.read_dir_normalized())
+        .iter()
+        .map(|entry| entry.name().to_os_string())
+        .collect()
+}
+
+#[test]
+fn read_dir_normalized_consistent_order() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("composed"));
+    check!(tmpdir.create_dir("decomposed"));
+    let composed = check!(tmpdir.open_dir("composed"));
+    let decomposed = check!(tmpdir.open_dir("decomposed"));
+
+    // "cafe\u{301}" sorts before "caff" by raw bytes, but "caf\u{e9}"
+    // sorts after it, so normalization changes the order.
+    for name in &["cafe", "caff", "cafz"] {
+        check!(composed.write(name, b""));
+        check!(decomposed.write(name, b""));
+    }
+    check!(composed.write(COMPOSED, b""));
+    check!(decomposed.write(DECOMPOSED, b""));
+
+    let expected: Vec<OsString> =
+        vec!["cafe".into(), "caff".into(), "cafz".into(), COMPOSED.into()];
+    assert_eq!(names(&composed), expected);
+    assert_eq!(names(&decomposed), expected);
+}
+
+#[test]
+fn read_dir_normalized_raw_name() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write(DECOMPOSED, b"hello"));
+
+    let entries = check!(tmpdir.read_dir_normalized());
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].name(), COMPOSED);
+
+    // The raw name can be used to access the entry, even on filesystems
+    // which don't normalize names.
+    let raw_name = entries[0].raw_name();
+    assert_eq!(check!(tmpdir.read(&raw_name)), b"hello");
+    assert_eq!(entries[0].entry().file_name(), raw_name);
+}
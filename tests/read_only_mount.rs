@@ -0,0 +1,42 @@
+#[macro_use]
+mod sys_common;
+
+use sys_common::io::tmpdir;
+
+#[test]
+fn read_only_mount_tmpdir() {
+    let tmpdir = tmpdir();
+    assert!(!check!(tmpdir.is_read_only_mount()));
+
+    check!(tmpdir.create_dir("sub"));
+    let sub = check!(tmpdir.open_dir("sub"));
+    assert!(!check!(sub.is_read_only_mount()));
+}
+
+/// Find a read-only mount in `/proc/self/mounts`, if there is one, and check
+/// that it's reported as such.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn read_only_mount_ro() {
+    use cap_std::{ambient_authority, fs::Dir};
+
+    let mounts = match std::fs::read_to_string("/proc/self/mounts") {
+        Ok(mounts) => mounts,
+        Err(_) => return,
+    };
+    for line in mounts.lines() {
+        let fields = line.split(' ').collect::<Vec<_>>();
+        if fields.len() < 4 || !fields[3].split(',').any(|opt| opt == "ro") {
+            continue;
+        }
+        // Mount points with spaces and other special characters are escaped;
+        // don't bother decoding them.
+        if fields[1].contains('\\') {
+            continue;
+        }
+        if let Ok(dir) = Dir::open_ambient_dir(fields[1], ambient_authority()) {
+            assert!(check!(dir.is_read_only_mount()), "{}", fields[1]);
+            return;
+        }
+    }
+}
@@ -0,0 +1,92 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::OpenOptions;
+use std::io::{self, Read, Write};
+use sys_common::io::tmpdir;
+
+#[test]
+fn read_to_end_capped_under_limit() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello, world"));
+    let file = check!(tmpdir.open("file"));
+
+    let mut buf = b"prefix:".to_vec();
+    assert_eq!(check!(file.read_to_end_capped(&mut buf, 12)), 12);
+    assert_eq!(buf, b"prefix:hello, world");
+
+    // At EOF, there's nothing more to read.
+    assert_eq!(check!(file.read_to_end_capped(&mut buf, 0)), 0);
+}
+
+#[test]
+fn read_to_end_capped_over_limit() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello, world"));
+    let file = check!(tmpdir.open("file"));
+
+    let mut buf = b"prefix:".to_vec();
+    assert_eq!(
+        file.read_to_end_capped(&mut buf, 11).unwrap_err().kind(),
+        io::ErrorKind::InvalidData
+    );
+    assert_eq!(buf, b"prefix:");
+}
+
+#[test]
+fn read_to_end_capped_from_position() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello, world"));
+    let mut file = check!(tmpdir.open("file"));
+    let mut skip = [0_u8; 7];
+    check!(file.read_exact(&mut skip));
+
+    let mut buf = Vec::new();
+    assert_eq!(check!(file.read_to_end_capped(&mut buf, 5)), 5);
+    assert_eq!(buf, b"world");
+}
+
+#[test]
+fn read_to_end_capped_growing_file() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"small"));
+    let file = check!(tmpdir.open("file"));
+
+    // Grow the file after it's been opened, so that the size reported when
+    // reading starts is smaller than what ends up being read.
+    let mut writer = check!(tmpdir.open_with("file", OpenOptions::new().append(true)));
+    let writer_thread = std::thread::spawn(move || {
+        for _ in 0..64 {
+            writer.write_all(&[b'x'; 1024]).unwrap();
+        }
+    });
+
+    let mut buf = Vec::new();
+    let result = file.read_to_end_capped(&mut buf, 1024);
+    writer_thread.join().unwrap();
+    match result {
+        Ok(n) => {
+            assert!(n <= 1024);
+            assert_eq!(buf.len(), n);
+        }
+        Err(err) => {
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert!(buf.is_empty());
+        }
+    }
+
+    // Once the writer is done, the file is definitely over the limit.
+    let file = check!(tmpdir.open("file"));
+    let mut buf = Vec::new();
+    assert_eq!(
+        file.read_to_end_capped(&mut buf, 1024).unwrap_err().kind(),
+        io::ErrorKind::InvalidData
+    );
+    assert!(buf.is_empty());
+
+    let file = check!(tmpdir.open("file"));
+    assert_eq!(
+        check!(file.read_to_end_capped(&mut buf, 1 << 20)),
+        5 + 64 * 1024
+    );
+}
@@ -0,0 +1,111 @@
+// This file contains tests for `cap_std::fs::File::set_sparse`.
+
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::OpenOptions;
+use sys_common::io::tmpdir;
+
+#[test]
+fn set_sparse_smoke() {
+    let tmpdir = tmpdir();
+    let file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().read(true).write(true).create(true)
+    ));
+
+    match file.set_sparse(true) {
+        Ok(()) => check!(file.set_sparse(false)),
+        // Only some Windows filesystems, such as NTFS, support sparse files.
+        Err(err) if cfg!(windows) => eprintln!("skipping set_sparse_smoke: {}", err),
+        Err(err) => panic!("set_sparse failed: {}", err),
+    }
+}
+
+#[cfg(windows)]
+#[test]
+fn set_sparse_punch_hole() {
+    use std::{os::windows::io::AsRawHandle, ptr};
+    use winapi::um::{
+        ioapiset::DeviceIoControl,
+        winioctl::{FSCTL_QUERY_ALLOCATED_RANGES, FSCTL_SET_ZERO_DATA},
+    };
+
+    /// `FILE_ZERO_DATA_INFORMATION` and `FILE_ALLOCATED_RANGE_BUFFER`, from
+    /// <winioctl.h>, have the same layout.
+    #[repr(C)]
+    #[derive(Clone, Copy, Default, Debug)]
+    struct Range {
+        start: i64,
+        end_or_len: i64,
+    }
+
+    const LEN: i64 = 1024 * 1024;
+    const HOLE_START: i64 = 256 * 1024;
+    const HOLE_END: i64 = 768 * 1024;
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", vec![0xa5_u8; LEN as usize]));
+    let file = check!(tmpdir.open_with("file", OpenOptions::new().read(true).write(true)));
+    if let Err(err) = file.set_sparse(true) {
+        // Only some filesystems, such as NTFS, support sparse files.
+        eprintln!("skipping set_sparse_punch_hole: {}", err);
+        return;
+    }
+
+    let mut zero = Range {
+        start: HOLE_START,
+        end_or_len: HOLE_END,
+    };
+    let mut returned = 0;
+    let ok = unsafe {
+        DeviceIoControl(
+            file.as_raw_handle() as _,
+            FSCTL_SET_ZERO_DATA,
+            &mut zero as *mut _ as *mut _,
+            std::mem::size_of_val(&zero) as u32,
+            ptr::null_mut(),
+            0,
+            &mut returned,
+            ptr::null_mut(),
+        )
+    };
+    assert_ne!(ok, 0, "{}", std::io::Error::last_os_error());
+
+    let mut query = Range {
+        start: 0,
+        end_or_len: LEN,
+    };
+    let mut ranges = [Range::default(); 16];
+    let ok = unsafe {
+        DeviceIoControl(
+            file.as_raw_handle() as _,
+            FSCTL_QUERY_ALLOCATED_RANGES,
+            &mut query as *mut _ as *mut _,
+            std::mem::size_of_val(&query) as u32,
+            ranges.as_mut_ptr() as *mut _,
+            std::mem::size_of_val(&ranges) as u32,
+            &mut returned,
+            ptr::null_mut(),
+        )
+    };
+    assert_ne!(ok, 0, "{}", std::io::Error::last_os_error());
+
+    let ranges = &ranges[..returned as usize / std::mem::size_of::<Range>()];
+    assert!(!ranges.is_empty());
+    for range in ranges {
+        let end = range.start + range.end_or_len;
+        assert!(
+            end <= HOLE_START || range.start >= HOLE_END,
+            "{:?} overlaps the hole",
+            range
+        );
+    }
+
+    // The data outside the hole is intact, and the hole reads as zeros.
+    let contents = check!(tmpdir.read("file"));
+    assert!(contents[..HOLE_START as usize].iter().all(|b| *b == 0xa5));
+    assert!(contents[HOLE_START as usize..HOLE_END as usize]
+        .iter()
+        .all(|b| *b == 0));
+}
@@ -0,0 +1,85 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::StorageErrorKind;
+use std::io;
+
+#[cfg(not(windows))]
+#[test]
+fn storage_error_kind_codes() {
+    use posish::io::Errno;
+
+    assert_eq!(
+        StorageErrorKind::from_io_error(&Errno::NOSPC.io_error()),
+        Some(StorageErrorKind::StorageFull)
+    );
+    assert_eq!(
+        StorageErrorKind::from_io_error(&Errno::DQUOT.io_error()),
+        Some(StorageErrorKind::QuotaExceeded)
+    );
+    assert_eq!(
+        StorageErrorKind::from_io_error(&Errno::NOENT.io_error()),
+        None
+    );
+}
+
+#[cfg(windows)]
+#[test]
+fn storage_error_kind_codes() {
+    use winapi::shared::winerror::{
+        ERROR_DISK_FULL, ERROR_DISK_QUOTA_EXCEEDED, ERROR_FILE_NOT_FOUND,
+    };
+
+    assert_eq!(
+        StorageErrorKind::from_io_error(&io::Error::from_raw_os_error(ERROR_DISK_FULL as i32)),
+        Some(StorageErrorKind::StorageFull)
+    );
+    assert_eq!(
+        StorageErrorKind::from_io_error(&io::Error::from_raw_os_error(
+            ERROR_DISK_QUOTA_EXCEEDED as i32
+        )),
+        Some(StorageErrorKind::QuotaExceeded)
+    );
+    assert_eq!(
+        StorageErrorKind::from_io_error(&io::Error::from_raw_os_error(ERROR_FILE_NOT_FOUND as i32)),
+        None
+    );
+}
+
+#[test]
+fn storage_error_kind_custom() {
+    let err = io::Error::new(io::ErrorKind::WriteZero, "no space");
+    assert_eq!(StorageErrorKind::from_io_error(&err), None);
+}
+
+/// Fill a tiny tmpfs and check that the failing write is classified as
+/// `StorageFull`.
+#[cfg(target_os = "linux")]
+#[test]
+fn storage_full_on_tmpfs() {
+    use cap_std::{ambient_authority, fs::Dir};
+    use std::io::Write;
+    use sys_common::tmpfs::Tmpfs;
+
+    let tmpfs = match Tmpfs::mount("storage_full_on_tmpfs", &["size=64k"]) {
+        Some(tmpfs) => tmpfs,
+        None => return,
+    };
+
+    let result = (|| -> io::Result<()> {
+        let dir = Dir::open_ambient_dir(tmpfs.path(), ambient_authority())?;
+        let mut file = dir.create("fill")?;
+        let chunk = [0_u8; 4096];
+        loop {
+            file.write_all(&chunk)?;
+        }
+    })();
+
+    let err = result.unwrap_err();
+    assert_eq!(
+        StorageErrorKind::from_io_error(&err),
+        Some(StorageErrorKind::StorageFull),
+        "unexpected error: {}",
+        err
+    );
+}
@@ -40,6 +40,35 @@ fn basic_symlinks() {
         .is_symlink());
 }
 
+#[test]
+fn symlink_with_type() {
+    use cap_std::fs::SymlinkTargetType;
+
+    if !symlink_supported() {
+        return;
+    }
+
+    let tmpdir = tmpdir();
+
+    check!(tmpdir.create("file"));
+    check!(tmpdir.create_dir("dir"));
+
+    check!(tmpdir.symlink_with_type("file", "file_symlink_file", SymlinkTargetType::File));
+    check!(tmpdir.symlink_with_type("dir", "dir_symlink_dir", SymlinkTargetType::Dir));
+    check!(tmpdir.symlink_with_type("file", "file_symlink_auto", SymlinkTargetType::Auto));
+    check!(tmpdir.symlink_with_type("dir", "dir_symlink_auto", SymlinkTargetType::Auto));
+
+    assert!(check!(tmpdir.metadata("file_symlink_file")).is_file());
+    assert!(check!(tmpdir.metadata("dir_symlink_dir")).is_dir());
+    assert!(check!(tmpdir.metadata("file_symlink_auto")).is_file());
+    assert!(check!(tmpdir.metadata("dir_symlink_auto")).is_dir());
+
+    // As with `symlink`, absolute targets are refused.
+    assert!(tmpdir
+        .symlink_with_type("/", "absolute", SymlinkTargetType::Auto)
+        .is_err());
+}
+
 #[test]
 fn symlink_absolute() {
     let tmpdir = tmpdir();
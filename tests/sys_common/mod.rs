@@ -1,6 +1,8 @@
 mod symlink_junction;
 
 pub mod io;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub mod tmpfs;
 
 pub use symlink_junction::*;
 
@@ -0,0 +1,46 @@
+//! A temporary tmpfs mount, for tests which need a filesystem with
+//! particular properties.
+
+use std::{path::Path, process::Command};
+
+/// A tmpfs mounted on a temporary directory, which is unmounted on drop.
+pub struct Tmpfs {
+    mount_point: tempfile::TempDir,
+}
+
+impl Tmpfs {
+    /// Mount a tmpfs with the given `mount` options, such as `size=64k`.
+    ///
+    /// Mounting requires privileges, so if it fails, this prints a message
+    /// naming `test` as skipped, and returns `None`.
+    #[allow(dead_code)]
+    pub fn mount(test: &str, options: &[&str]) -> Option<Self> {
+        let mount_point = tempfile::tempdir().unwrap();
+        let mut command = Command::new("mount");
+        command.arg("-t").arg("tmpfs");
+        if !options.is_empty() {
+            command.arg("-o").arg(options.join(","));
+        }
+        let status = command.arg("tmpfs").arg(mount_point.path()).status();
+        if !matches!(status, Ok(status) if status.success()) {
+            eprintln!("skipping {}: unable to mount a tmpfs", test);
+            return None;
+        }
+        Some(Self { mount_point })
+    }
+
+    /// Return the path of the mount point.
+    #[allow(dead_code)]
+    pub fn path(&self) -> &Path {
+        self.mount_point.path()
+    }
+}
+
+impl Drop for Tmpfs {
+    fn drop(&mut self) {
+        Command::new("umount")
+            .arg(self.mount_point.path())
+            .status()
+            .unwrap();
+    }
+}
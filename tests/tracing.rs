@@ -0,0 +1,195 @@
+#![cfg(feature = "tracing")]
+
+#[macro_use]
+mod sys_common;
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+use sys_common::io::tmpdir;
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+/// A span seen by a `SpanRecorder`, with its fields formatted as strings.
+#[derive(Clone, Debug)]
+struct RecordedSpan {
+    name: &'static str,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl RecordedSpan {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .rev()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A `Subscriber` which records every span created while it's the default.
+#[derive(Clone, Default)]
+struct SpanRecorder {
+    spans: Arc<Mutex<Vec<RecordedSpan>>>,
+}
+
+impl SpanRecorder {
+    fn spans_named(&self, name: &str) -> Vec<RecordedSpan> {
+        let spans = self.spans.lock().unwrap();
+        spans
+            .iter()
+            .filter(|span| span.name == name)
+            .cloned()
+            .collect()
+    }
+}
+
+struct Fields<'a>(&'a mut Vec<(&'static str, String)>);
+
+impl Visit for Fields<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.push((field.name(), format!("{:?}", value)));
+    }
+}
+
+impl Subscriber for SpanRecorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attrs: &span::Attributes<'_>) -> span::Id {
+        let mut spans = self.spans.lock().unwrap();
+        let mut fields = Vec::new();
+        attrs.record(&mut Fields(&mut fields));
+        spans.push(RecordedSpan {
+            name: attrs.metadata().name(),
+            fields,
+        });
+        span::Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, id: &span::Id, values: &span::Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        let span = &mut spans[id.into_u64() as usize - 1];
+        values.record(&mut Fields(&mut span.fields));
+    }
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+#[test]
+fn tracing_open() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+    check!(tmpdir.write("dir/file", b""));
+
+    let recorder = SpanRecorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        check!(tmpdir.open("dir/file"));
+        tmpdir.open("missing").unwrap_err();
+    });
+
+    let spans = recorder.spans_named("open");
+    assert_eq!(spans.len(), 2);
+    assert_eq!(spans[0].field("path"), Some("dir/file"));
+    assert_eq!(spans[0].field("ok"), Some("true"));
+    assert_eq!(spans[0].field("error"), None);
+    assert_eq!(spans[1].field("path"), Some("missing"));
+    assert_eq!(spans[1].field("ok"), Some("false"));
+    assert!(spans[1].field("error").is_some());
+}
+
+#[test]
+fn tracing_rename_and_remove() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("a", b""));
+
+    let recorder = SpanRecorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        check!(tmpdir.rename("a", &tmpdir, "b"));
+        check!(tmpdir.remove_file("b"));
+    });
+
+    let spans = recorder.spans_named("rename");
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].field("from"), Some("a"));
+    assert_eq!(spans[0].field("to"), Some("b"));
+    assert_eq!(spans[0].field("ok"), Some("true"));
+
+    let spans = recorder.spans_named("remove_file");
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].field("path"), Some("b"));
+}
+
+#[test]
+fn tracing_dirs() {
+    let tmpdir = tmpdir();
+
+    let recorder = SpanRecorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        check!(tmpdir.create_dir("a"));
+        check!(tmpdir.create_dir_all("a/b/c"));
+        check!(tmpdir.open_dir("a/b"));
+        tmpdir.open_dir("missing").unwrap_err();
+    });
+
+    let spans = recorder.spans_named("create_dir");
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].field("path"), Some("a"));
+    assert_eq!(spans[0].field("ok"), Some("true"));
+
+    let spans = recorder.spans_named("create_dir_all");
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].field("path"), Some("a/b/c"));
+
+    let spans = recorder.spans_named("open_dir");
+    assert_eq!(spans.len(), 2);
+    assert_eq!(spans[0].field("path"), Some("a/b"));
+    assert_eq!(spans[0].field("ok"), Some("true"));
+    assert_eq!(spans[1].field("path"), Some("missing"));
+    assert_eq!(spans[1].field("ok"), Some("false"));
+}
+
+/// `File::metadata` gets a span, but reads and writes, which can be very
+/// frequent, don't.
+#[test]
+fn tracing_file() {
+    use std::io::{Read, Write};
+
+    let tmpdir = tmpdir();
+    let mut file = check!(tmpdir.create("file"));
+
+    let recorder = SpanRecorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        check!(file.write_all(b"hello"));
+        check!(file.flush());
+        check!(file.sync_all());
+        check!(file.set_len(4));
+        check!(file.metadata());
+        let mut buf = [0; 1];
+        file.read(&mut buf).unwrap_err();
+    });
+
+    let spans = recorder.spans_named("file_metadata");
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].field("ok"), Some("true"));
+
+    for name in &[
+        "file_write",
+        "file_flush",
+        "file_sync_all",
+        "file_set_len",
+        "file_read",
+    ] {
+        assert!(recorder.spans_named(name).is_empty(), "{}", name);
+    }
+}
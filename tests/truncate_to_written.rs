@@ -0,0 +1,121 @@
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::OpenOptionsTruncateToWrittenExt;
+use cap_std::fs::OpenOptions;
+use std::io::{Seek, SeekFrom, Write};
+use sys_common::io::tmpdir;
+
+#[test]
+fn truncate_to_written_on_drop() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"a long original file"));
+
+    let mut file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().write(true).truncate_to_written(true)
+    ));
+    check!(file.write_all(b"short"));
+    drop(file);
+
+    assert_eq!(check!(tmpdir.read("file")), b"short");
+}
+
+#[test]
+fn truncate_to_written_unwritten() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"a long original file"));
+
+    // Opening and dropping without writing leaves the file alone.
+    let file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().write(true).truncate_to_written(true)
+    ));
+    drop(file);
+    assert_eq!(check!(tmpdir.read("file")), b"a long original file");
+
+    // Even if the position has moved.
+    let mut file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().write(true).truncate_to_written(true)
+    ));
+    check!(file.seek(SeekFrom::Start(6)));
+    check!(file.finish());
+    assert_eq!(check!(tmpdir.read("file")), b"a long original file");
+}
+
+#[test]
+fn truncate_to_written_finish() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"a long original file"));
+
+    // The furthest write counts, not the last one.
+    let mut file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().write(true).truncate_to_written(true)
+    ));
+    check!(file.seek(SeekFrom::Start(2)));
+    check!(file.write_all(b"LONG"));
+    check!(file.seek(SeekFrom::Start(0)));
+    check!(file.write_all(b"A"));
+    check!(file.finish());
+    assert_eq!(check!(tmpdir.read("file")), b"A LONG");
+
+    // Writing past the end doesn't truncate anything.
+    let mut file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().write(true).truncate_to_written(true)
+    ));
+    check!(file.write_all(b"longer than before"));
+    check!(file.finish());
+    assert_eq!(check!(tmpdir.read("file")), b"longer than before");
+
+    // `set_len` sets the length to truncate to.
+    let file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().write(true).truncate_to_written(true)
+    ));
+    check!(file.set_len(6));
+    check!(file.finish());
+    assert_eq!(check!(tmpdir.read("file")), b"longer");
+}
+
+#[cfg(unix)]
+#[test]
+fn truncate_to_written_at() {
+    use std::os::unix::fs::FileExt;
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"a long original file"));
+
+    let file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().write(true).truncate_to_written(true)
+    ));
+    check!(file.write_all_at(b"LONG", 2));
+    check!(file.finish());
+    assert_eq!(check!(tmpdir.read("file")), b"a LONG");
+}
+
+#[test]
+fn truncate_to_written_disarmed() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"a long original file"));
+
+    // Converting to `std` discards the option.
+    let mut file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().write(true).truncate_to_written(true)
+    ));
+    check!(file.write_all(b"A"));
+    drop(file.into_std());
+    assert_eq!(check!(tmpdir.read("file")), b"A long original file");
+
+    // Without write access, the option has no effect.
+    let file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().read(true).truncate_to_written(true)
+    ));
+    check!(file.finish());
+    assert_eq!(check!(tmpdir.read("file")), b"A long original file");
+}
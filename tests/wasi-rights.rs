@@ -0,0 +1,51 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::OpenOptions;
+use sys_common::io::tmpdir;
+
+#[cfg(target_os = "wasi")]
+#[test]
+fn open_with_restricted_rights() {
+    use std::io::{Read, Seek, SeekFrom};
+
+    // Rights bits from the WASI `rights` type.
+    const RIGHTS_FD_READ: u64 = 1 << 1;
+    const RIGHTS_FD_SEEK: u64 = 1 << 2;
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello, world"));
+
+    // Read-only, without the ability to seek.
+    let mut file = check!(tmpdir.open_with_rights(
+        "file",
+        OpenOptions::new().read(true),
+        RIGHTS_FD_READ,
+        0
+    ));
+    let mut buf = [0_u8; 5];
+    check!(file.read_exact(&mut buf));
+    assert_eq!(&buf, b"hello");
+    assert!(file.seek(SeekFrom::Start(0)).is_err());
+
+    // With the seek right, seeking succeeds.
+    let mut file = check!(tmpdir.open_with_rights(
+        "file",
+        OpenOptions::new().read(true),
+        RIGHTS_FD_READ | RIGHTS_FD_SEEK,
+        0
+    ));
+    check!(file.seek(SeekFrom::Start(7)));
+}
+
+#[cfg(not(target_os = "wasi"))]
+#[test]
+fn open_with_rights_unsupported() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello, world"));
+
+    let err = tmpdir
+        .open_with_rights("file", OpenOptions::new().read(true), !0, !0)
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+}
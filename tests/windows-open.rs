@@ -158,3 +158,31 @@ fn windows_open_special() {
         tmpdir.create(&format!(".\\{}.ext", device)).unwrap_err();
     }
 }
+
+#[test]
+#[cfg(windows)]
+fn windows_open_reserved_components() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    check!(tmpdir.write("conference.txt", b"hello"));
+    check!(tmpdir.write("sub\\com10.log", b"hello"));
+
+    for path in &[
+        "CON",
+        "com1.log",
+        "sub\\nul",
+        "sub\\CON .txt",
+        "aux\\file",
+        "sub\\..\\Prn.tar.gz",
+        "COM\u{b9}",
+    ] {
+        let err = tmpdir.open(path).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{}", path);
+        let err = tmpdir.create(path).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{}", path);
+    }
+
+    // Names which merely start with a reserved name are ordinary files.
+    check!(tmpdir.open("conference.txt"));
+    check!(tmpdir.open("sub\\com10.log"));
+}
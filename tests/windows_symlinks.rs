@@ -91,3 +91,55 @@ fn windows_symlinks_ambient() {
             .is_symlink()
     );
 }
+
+#[test]
+fn windows_symlink_with_type_dangling_dir() {
+    use cap_std::fs::SymlinkTargetType;
+
+    if !symlink_supported() {
+        return;
+    }
+
+    let tmpdir = tmpdir();
+
+    // The target doesn't exist yet, so only the explicit hint tells us to
+    // create a directory symlink.
+    check!(tmpdir.symlink_with_type("dir", "dir_symlink", SymlinkTargetType::Dir));
+    check!(tmpdir.symlink_with_type("dir", "auto_symlink", SymlinkTargetType::Auto));
+    assert!(tmpdir.metadata("dir_symlink").is_err());
+
+    check!(tmpdir.create_dir("dir"));
+    check!(tmpdir.create("dir/file"));
+
+    assert!(check!(tmpdir.metadata("dir_symlink")).is_dir());
+    check!(tmpdir.open_dir("dir_symlink"));
+    assert!(tmpdir.exists("dir_symlink/file"));
+
+    // `Auto` saw no target, so it made a file symlink, which Windows won't
+    // resolve to a directory.
+    assert!(tmpdir.open_dir("auto_symlink").is_err());
+}
+
+#[test]
+fn windows_symlink_with_type_auto() {
+    use cap_std::fs::SymlinkTargetType;
+
+    if !symlink_supported() {
+        return;
+    }
+
+    let tmpdir = tmpdir();
+
+    check!(tmpdir.create("file"));
+    check!(tmpdir.create_dir("dir"));
+    check!(tmpdir.create_dir("sub"));
+
+    check!(tmpdir.symlink_with_type("file", "file_symlink", SymlinkTargetType::Auto));
+    check!(tmpdir.symlink_with_type("dir", "dir_symlink", SymlinkTargetType::Auto));
+    // The target is resolved relative to the link's parent directory.
+    check!(tmpdir.symlink_with_type("../dir", "sub/dir_symlink", SymlinkTargetType::Auto));
+
+    assert!(check!(tmpdir.metadata("file_symlink")).is_file());
+    assert!(check!(tmpdir.metadata("dir_symlink")).is_dir());
+    assert!(check!(tmpdir.metadata("sub/dir_symlink")).is_dir());
+}